@@ -21,6 +21,18 @@ pub enum PulpCommand {
     /// Start a debate simulation server
     #[command(subcommand)]
     Serve(ServeCmd),
+
+    /// Manage the Neo4j database
+    #[command(subcommand)]
+    Db(DbCmd),
+
+    /// List, inspect and delete simulation runs
+    #[command(subcommand)]
+    Runs(RunsCmd),
+
+    /// Rescore a stored run under a scoring policy, keeping the scores it already has
+    #[command(name = "score")]
+    Score(ScoreArgs),
 }
 
 #[derive(Subcommand, Debug, Clone)]
@@ -41,6 +53,19 @@ pub struct EnneagramArgs {
     /// The config file to use for the simulation
     #[arg(short, long)]
     pub file: String,
+
+    /// Seed of the simulation's random choices, replaying a cassette needs the seed it was recorded with
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    #[command(flatten)]
+    pub store: StoreArgs,
+
+    #[command(flatten)]
+    pub scoring: ScoringArgs,
+
+    #[command(flatten)]
+    pub llm: LlmArgs,
 }
 
 #[derive(Args, Debug, Clone)]
@@ -48,6 +73,16 @@ pub struct BusinessArgs {
     /// The config file used for the simulation
     #[arg(short, long)]
     pub file: String,
+
+    /// Seed of the simulation's random choices, replaying a cassette needs the seed it was recorded with
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    #[command(flatten)]
+    pub store: StoreArgs,
+
+    #[command(flatten)]
+    pub llm: LlmArgs,
 }
 
 #[derive(Args, Debug, Clone)]
@@ -56,9 +91,125 @@ pub struct PersonasArgs {
     #[arg(short, long)]
     pub file: String,
 
+    /// Seed of the simulation's random choices, replaying a cassette needs the seed it was recorded with
+    #[arg(long)]
+    pub seed: Option<u64>,
+
     /// Whether or not to initialize the neo4j database with a ser of users
     #[arg(short, long)]
     pub init: Option<i64>,
+
+    #[command(flatten)]
+    pub store: StoreArgs,
+
+    #[command(flatten)]
+    pub scoring: ScoringArgs,
+
+    #[command(flatten)]
+    pub llm: LlmArgs,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct StoreArgs {
+    /// Where to persist the simulation, `memory` runs without a database and `sqlite:FILE` writes to a single file
+    #[arg(
+        long,
+        value_name = "neo4j|sqlite[:FILE]|memory",
+        default_value = "neo4j"
+    )]
+    pub store: String,
+
+    /// Writes sent to the store together, 1 writes every entity and relationship on its own
+    #[arg(long, value_name = "WRITES")]
+    pub batch_size: Option<usize>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ScoringArgs {
+    /// Score responses with the weights, caps and decay of a scoring policy TOML file
+    #[arg(long, value_name = "FILE")]
+    pub policy: Option<String>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct LlmArgs {
+    /// Record every LLM request and reply to a cassette file
+    #[arg(long, value_name = "FILE", conflicts_with = "replay")]
+    pub record: Option<String>,
+
+    /// Replay LLM replies from a cassette file instead of calling the provider
+    #[arg(long, value_name = "FILE")]
+    pub replay: Option<String>,
+
+    /// Override the built in prompts with the templates in a TOML file or directory
+    #[arg(long, value_name = "PATH")]
+    pub prompts: Option<String>,
+
+    /// Cache replies in `memory` or in a SQLite file, so unchanged prompts are only sent once
+    #[arg(long, value_name = "memory|FILE")]
+    pub cache: Option<String>,
+
+    /// Stop sending prompts once a token count (200000, 200k) or dollar amount ($2.50) is spent
+    #[arg(long, value_name = "LIMIT")]
+    pub budget: Vec<String>,
+
+    /// Write the token usage and cost of the run to a JSON file
+    #[arg(long, value_name = "FILE", default_value = "pulpcalc-summary.json")]
+    pub summary: String,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum DbCmd {
+    /// Create the id constraints and indexes of the Neo4j graph and apply pending schema migrations
+    #[command(name = "init")]
+    Init,
+
+    /// Convert numeric and boolean properties stored as strings to native Neo4j types
+    #[command(name = "migrate")]
+    Migrate,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum RunsCmd {
+    /// List every recorded run, oldest first
+    #[command(name = "list")]
+    List(RunsArgs),
+
+    /// Show a run and how many entities and relationships it wrote
+    #[command(name = "show")]
+    Show(RunArgs),
+
+    /// Delete a run along with everything it wrote
+    #[command(name = "delete")]
+    Delete(RunArgs),
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct RunsArgs {
+    #[command(flatten)]
+    pub store: StoreArgs,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct RunArgs {
+    /// Id of the run, as printed when it started
+    pub id: String,
+
+    #[command(flatten)]
+    pub store: StoreArgs,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ScoreArgs {
+    /// Id of the run to rescore, as printed when it started
+    #[arg(long)]
+    pub run: String,
+
+    #[command(flatten)]
+    pub scoring: ScoringArgs,
+
+    #[command(flatten)]
+    pub store: StoreArgs,
 }
 
 #[derive(Subcommand, Debug, Clone)]
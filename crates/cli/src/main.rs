@@ -4,14 +4,13 @@ use clap::Parser;
 use eyre::Result;
 use futures::future::join_all;
 use pulpcalc_common::prelude::*;
+use pulpcalc_external::cassette::CassetteMode;
 use rand::prelude::*;
 use simulator::{
     new_business_from_file, new_enneagram_from_file, new_personas_from_file,
-    personas::{
-        models::{Gender, PersonasUser},
-        PersonasSimulation,
-    },
+    personas::PersonasSimulation,
 };
+use std::collections::HashMap;
 use tokio::task;
 
 #[tokio::main]
@@ -26,6 +25,14 @@ async fn main() -> Result<()> {
 
                 let simulations = new_enneagram_from_file(&args.file);
 
+                let mut cfg = Config::init().await;
+                use_store_args(&mut cfg, &args.store).await;
+                use_llm_args(&mut cfg, &args.llm);
+                use_scoring_args(&mut cfg, &args.scoring);
+                use_seed(&mut cfg, args.seed);
+                start_run(&cfg, "enneagram", &args.file).await;
+
+                let mut rng = cfg.rng();
                 let mut ts = vec![];
 
                 let debates: Vec<Debate> = Vec::new();
@@ -35,8 +42,12 @@ async fn main() -> Result<()> {
                     d.topic = sim.topic.clone();
                     d.category = sim.category.clone();
 
+                    // Every simulation shares one LLM provider so the rate limits apply across them,
+                    // and draws from its own RNG seeded from the run's so they don't race for choices
+                    let cfg = cfg.clone();
+                    let mut rng = StdRng::seed_from_u64(rng.gen());
                     let t = task::spawn(async move {
-                        if let Err(e) = sim.run_simulation(Config::init().await, d).await {
+                        if let Err(e) = sim.run_simulation(cfg, d, &mut rng).await {
                             println!("{}", e);
                         }
                     });
@@ -49,10 +60,20 @@ async fn main() -> Result<()> {
                 for debate in debates {
                     println!("Debate: {:?}", debate);
                 }
+
+                flush_store(&cfg).await;
+                report_usage(&cfg, &args.llm);
+                check_cassette(&cfg);
             }
 
             cli::SimCmd::Business(args) => {
-                let simulations = new_business_from_file(args.file);
+                let simulations = new_business_from_file(args.file.clone());
+
+                let mut cfg = Config::init().await;
+                use_store_args(&mut cfg, &args.store).await;
+                use_llm_args(&mut cfg, &args.llm);
+                use_seed(&mut cfg, args.seed);
+                start_run(&cfg, "business", &args.file).await;
 
                 let mut ts = vec![];
 
@@ -63,8 +84,10 @@ async fn main() -> Result<()> {
                     d.topic = sim.topic.clone();
                     d.category = sim.category.clone();
 
+                    // Every simulation shares one LLM provider so the rate limits apply across them
+                    let cfg = cfg.clone();
                     let t = task::spawn(async move {
-                        if let Err(e) = sim.run_simulation(Config::init().await, d).await {
+                        if let Err(e) = sim.run_simulation(cfg, d).await {
                             println!("{}", e);
                         }
                     });
@@ -77,6 +100,10 @@ async fn main() -> Result<()> {
                 for debate in debates {
                     println!("Debate: {:?}", debate);
                 }
+
+                flush_store(&cfg).await;
+                report_usage(&cfg, &args.llm);
+                check_cassette(&cfg);
             }
 
             cli::SimCmd::Personas(args) => {
@@ -85,147 +112,23 @@ async fn main() -> Result<()> {
                     args.file
                 );
 
-                let personas_sim = new_personas_from_file(args.file);
-                let cfg = Config::init().await;
-
-                if let Some(init_count) = args.init {
-                    let mut users: Vec<PersonasUser> = Vec::new();
-
-                    for _ in 0..init_count {
-                        let mut user = PersonasUser::default();
-
-                        if personas_sim.adults_only {
-                            user.age = 18;
-                        } else {
-                            let rint_age = (random::<f32>()
-                                * personas_sim.user_restrictions.clone().unwrap().max_age as f32)
-                                .floor() as i64;
-                            user.age = rint_age;
-                        }
-
-                        user.vote_valid_influence = personas_sim
-                            .user_restrictions
-                            .as_ref()
-                            .unwrap()
-                            .vote_valid_reason
-                            .clone();
-                        user.vote_invalid_influence = personas_sim
-                            .user_restrictions
-                            .as_ref()
-                            .unwrap()
-                            .vote_invalid_reason
-                            .clone();
-                        user.vote_abstain_influence = personas_sim
-                            .user_restrictions
-                            .as_ref()
-                            .unwrap()
-                            .vote_abstain_reason
-                            .clone();
-
-                        user.personality.personality_base.core_desire = "money".to_string();
-                        user.personality.personality_base.core_fear = "death".to_string();
-
-                        let rint_enneagram = (random::<f32>() * 9.0).floor() as i64;
-
-                        user.personality.personality_base.enneagram = rint_enneagram;
-
-                        users.push(user);
-                    }
-
-                    let mut j = 0.0;
-                    let mut mdu: Vec<PersonasUser> = Vec::new();
-                    while j < personas_sim
-                        .user_restrictions
-                        .as_ref()
-                        .unwrap()
-                        .male_distribution
-                        * (personas_sim.simulation_size - 1) as f64
-                    {
-                        let mut user = users.pop().unwrap();
-                        user.gender = Gender::from("male");
-
-                        let user_id = user
-                            .create(&cfg.neo4j_graph)
-                            .await
-                            .expect("failed to create user");
-                        user.base_user.id = user_id;
-
-                        mdu.push(user);
-
-                        j += 1.0;
-                    }
-
-                    let mut z = 0.0;
-                    let mut fdu: Vec<PersonasUser> = Vec::new();
-                    while z
-                        < (personas_sim
-                            .user_restrictions
-                            .as_ref()
-                            .unwrap()
-                            .female_distribution
-                            * (personas_sim.simulation_size - 1) as f64)
-                            .floor()
-                    {
-                        let mut user = users.pop().unwrap();
-                        user.gender = Gender::from("female");
-
-                        let user_id = user
-                            .create(&cfg.neo4j_graph)
-                            .await
-                            .expect("failed to create user");
-                        user.base_user.id = user_id;
+                let personas_sim = new_personas_from_file(args.file.clone());
+                let mut cfg = Config::init().await;
+                use_store_args(&mut cfg, &args.store).await;
+                use_llm_args(&mut cfg, &args.llm);
+                use_scoring_args(&mut cfg, &args.scoring);
+                use_seed(&mut cfg, args.seed);
+                start_run(&cfg, "personas", &args.file).await;
 
-                        fdu.push(user);
-
-                        z += 1.0;
-                    }
+                let mut rng = cfg.rng();
 
-                    let mut k = 0.0;
-                    let mut odu: Vec<PersonasUser> = Vec::new();
-                    while k
-                        < (personas_sim
-                            .user_restrictions
-                            .as_ref()
-                            .unwrap()
-                            .other_distribution
-                            * (personas_sim.simulation_size - 1) as f64)
-                            .floor()
-                    {
-                        let mut user = users.pop().unwrap();
-                        user.gender = Gender::from("other");
-
-                        let user_id = user
-                            .create(&cfg.neo4j_graph)
+                if let Some(init_count) = args.init {
+                    if let Err(e) =
+                        PersonasSimulation::init_users(&cfg, &personas_sim, init_count, &mut rng)
                             .await
-                            .expect("failed to create user");
-                        user.base_user.id = user_id;
-
-                        odu.push(user);
-
-                        k += 1.0;
-                    }
-
-                    let mut l = 0.0;
-                    let mut rnsdu: Vec<PersonasUser> = Vec::new();
-                    while l < personas_sim
-                        .user_restrictions
-                        .as_ref()
-                        .unwrap()
-                        .not_saying_gender_distribution
-                        * (personas_sim.simulation_size - 1) as f64
                     {
-                        let mut user = users.pop().unwrap();
-                        user.gender = Gender::from("rather not say");
-
-                        let user_id = user
-                            .create(&cfg.neo4j_graph)
-                            .await
-                            .expect("failed to create user");
-                        user.base_user.id = user_id;
-
-                        rnsdu.push(user);
-
-                        l += 1.0;
+                        println!("{}", e);
+                        std::process::exit(1);
                     }
                 }
 
@@ -258,9 +161,13 @@ async fn main() -> Result<()> {
                 let mut ps = PersonasSimulation::default();
                 ps.debates = debates;
 
-                if let Err(e) = ps.run_simulation(cfg, personas_sim).await {
+                if let Err(e) = ps.run_simulation(cfg.clone(), personas_sim, &mut rng).await {
                     println!("{}", e);
                 }
+
+                flush_store(&cfg).await;
+                report_usage(&cfg, &args.llm);
+                check_cassette(&cfg);
             }
         },
 
@@ -275,6 +182,185 @@ async fn main() -> Result<()> {
                 println!("Starting the REST server {}", args.port)
             }
         },
+
+        Some(cli::PulpCommand::Db(cmd)) => match cmd {
+            // Bring the graph up to the latest schema
+            cli::DbCmd::Init => {
+                let store = connect_neo4j(&Config::init().await).await;
+
+                match store.init_schema().await {
+                    Ok(applied) => {
+                        for version in &applied {
+                            println!("  applied schema migration {}", version);
+                        }
+
+                        match store.schema_version().await {
+                            Ok(version) => println!("Schema at version {}", version),
+                            Err(e) => println!("{}", e),
+                        }
+                    }
+
+                    Err(e) => {
+                        println!("{}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+
+            // Convert string typed properties written by older versions
+            cli::DbCmd::Migrate => {
+                let store = connect_neo4j(&Config::init().await).await;
+
+                match store.migrate_property_types().await {
+                    Ok(migrated) => {
+                        let mut converted = 0;
+                        let mut unconvertible = 0;
+                        for migration in &migrated {
+                            if migration.converted > 0 {
+                                println!(
+                                    "  {}: {} nodes converted",
+                                    migration.property, migration.converted
+                                );
+                            }
+
+                            if !migration.unconvertible.is_empty() {
+                                println!(
+                                    "  {}: {} nodes could not be converted: {}",
+                                    migration.property,
+                                    migration.unconvertible.len(),
+                                    migration.unconvertible.join(", ")
+                                );
+                            }
+
+                            converted += migration.converted;
+                            unconvertible += migration.unconvertible.len();
+                        }
+
+                        println!(
+                            "Converted {} string typed properties, {} could not be converted",
+                            converted, unconvertible
+                        );
+
+                        // Reading the nodes left with strings fails until they are fixed by hand
+                        if unconvertible > 0 {
+                            std::process::exit(1);
+                        }
+                    }
+
+                    Err(e) => {
+                        println!("{}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+        },
+
+        Some(cli::PulpCommand::Runs(cmd)) => match cmd {
+            cli::RunsCmd::List(args) => {
+                let mut cfg = Config::init().await;
+                use_store_args(&mut cfg, &args.store).await;
+
+                match cfg.store.get_all_runs().await {
+                    Ok(runs) => {
+                        for run in runs {
+                            println!(
+                                "{}  {}  {}  started {}  config {}",
+                                run.id,
+                                run.simulation,
+                                run.config_file,
+                                run.started_at,
+                                &run.config_hash[..run.config_hash.len().min(12)]
+                            );
+                        }
+                    }
+
+                    Err(e) => {
+                        println!("{}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+
+            cli::RunsCmd::Show(args) => {
+                let mut cfg = Config::init().await;
+                use_store_args(&mut cfg, &args.store).await;
+
+                let run = match cfg.store.get_run(&args.id).await {
+                    Ok(run) => run,
+                    Err(e) => {
+                        println!("{}", e);
+                        std::process::exit(1);
+                    }
+                };
+
+                println!("Run {}", run.id);
+                println!("  simulation: {}", run.simulation);
+                println!("  config: {} (sha256 {})", run.config_file, run.config_hash);
+                println!("  seed: {}", run.seed as u64);
+                println!("  started: {}", run.started_at);
+
+                match cfg.store.run_counts(&run.id).await {
+                    Ok(counts) => {
+                        for (label, count) in counts {
+                            println!("  {}: {}", label, count);
+                        }
+                    }
+
+                    Err(e) => {
+                        println!("{}", e);
+                        std::process::exit(1);
+                    }
+                }
+
+                match cfg.store.get_score_versions(&run.id).await {
+                    Ok(versions) => {
+                        for version in versions {
+                            println!(
+                                "  score version {}  policy {}  scored {}",
+                                version.id, version.policy, version.scored_at
+                            );
+                        }
+                    }
+
+                    Err(e) => {
+                        println!("{}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+
+            cli::RunsCmd::Delete(args) => {
+                let mut cfg = Config::init().await;
+                use_store_args(&mut cfg, &args.store).await;
+
+                // Deleting a run that doesn't exist is a typo rather than a no-op
+                if let Err(e) = cfg.store.get_run(&args.id).await {
+                    println!("{}", e);
+                    std::process::exit(1);
+                }
+
+                match cfg.store.delete_run(&args.id).await {
+                    Ok(()) => println!("Deleted run {}", args.id),
+
+                    Err(e) => {
+                        println!("{}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+        },
+
+        Some(cli::PulpCommand::Score(args)) => {
+            let mut cfg = Config::init().await;
+            use_store_args(&mut cfg, &args.store).await;
+            use_scoring_args(&mut cfg, &args.scoring);
+
+            if let Err(e) = rescore(&cfg, &args.run).await {
+                println!("{}", e);
+                std::process::exit(1);
+            }
+        }
+
         None => {
             println!("No command given");
         }
@@ -282,3 +368,221 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// Connects the store selected with `--store`
+async fn use_store_args(cfg: &mut Config, args: &cli::StoreArgs) {
+    if let Some(batch_size) = args.batch_size {
+        cfg.store_batch_size = batch_size;
+    }
+
+    if let Err(e) = cfg.use_store(&args.store).await {
+        println!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+/// Applies the `--seed` flag, printing the seed so the run can be repeated
+fn use_seed(cfg: &mut Config, seed: Option<u64>) {
+    if let Some(seed) = seed {
+        cfg.seed = seed;
+    }
+
+    println!("Simulation seed {}", cfg.seed);
+}
+
+/// Starts the run everything the simulation writes is tagged with
+async fn start_run(cfg: &Config, simulation: &str, config_file: &str) {
+    match cfg.start_run(simulation, config_file).await {
+        Ok(run) => println!("Run {}", run.id),
+
+        Err(e) => {
+            println!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Connects to the Neo4j database the `db` commands work on
+async fn connect_neo4j(cfg: &Config) -> Neo4jStore {
+    match cfg.neo4j_store().await {
+        Ok(store) => store,
+        Err(e) => {
+            println!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Writes what a simulation that stopped early left buffered
+async fn flush_store(cfg: &Config) {
+    if let Err(e) = cfg.store.flush().await {
+        println!("{}", e);
+    }
+}
+
+/// Scores the run with `id` under the policy in use and records the scores as a new version, printing
+/// how they compare with the previous version or, for the first one, with the scores written during the run
+async fn rescore(cfg: &Config, id: &str) -> Result<(), PulpError> {
+    let run = cfg.store.get_run(id).await?;
+    let previous = cfg.store.get_score_versions(&run.id).await?.pop();
+
+    let scored = cfg.scoring.score_run(cfg.store.as_ref(), &run.id).await?;
+
+    let baseline: HashMap<String, i64> = match &previous {
+        Some(version) => cfg
+            .store
+            .get_scores(&version.id)
+            .await?
+            .into_iter()
+            .map(|score| (score.entity_id, score.score))
+            .collect(),
+
+        None => scored
+            .iter()
+            .map(|(score, stored)| (score.entity_id.clone(), *stored))
+            .collect(),
+    };
+
+    let scores = scored
+        .into_iter()
+        .map(|(score, _)| score)
+        .collect::<Vec<_>>();
+    let version = ScoreVersion::new(&run.id, &cfg.scoring.id);
+    let version_id = cfg.store.create_score_version(&version, &scores).await?;
+
+    println!("Score version {}", version_id);
+    match &previous {
+        Some(previous) => println!(
+            "  compared with version {} (policy {})",
+            previous.id, previous.policy
+        ),
+        None => println!("  compared with the scores written during the run"),
+    }
+
+    let responses = scores
+        .iter()
+        .filter(|score| score.label == "Response")
+        .collect::<Vec<_>>();
+    let changed = responses
+        .iter()
+        .filter(|score| baseline.get(&score.entity_id) != Some(&score.score))
+        .count();
+    println!("  responses: {}, {} changed", responses.len(), changed);
+
+    for debate in scores.iter().filter(|score| score.label == "Debate") {
+        println!(
+            "  debate {}: {} -> {}",
+            debate.entity_id,
+            baseline.get(&debate.entity_id).copied().unwrap_or_default(),
+            debate.score
+        );
+    }
+
+    Ok(())
+}
+
+/// Applies the `--prompts`, `--cache`, `--budget` and `--record` or `--replay` flags
+fn use_llm_args(cfg: &mut Config, args: &cli::LlmArgs) {
+    if let Some(path) = &args.prompts {
+        if let Err(e) = cfg.use_prompts(path) {
+            println!("{}", e);
+            std::process::exit(1);
+        }
+    }
+
+    if let Some(cache) = &args.cache {
+        if let Err(e) = cfg.use_cache(cache) {
+            println!("{}", e);
+            std::process::exit(1);
+        }
+    }
+
+    for limit in &args.budget {
+        match cfg.llm_budget.clone().limit(limit) {
+            Ok(budget) => cfg.llm_budget = budget,
+
+            Err(e) => {
+                println!("{}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let cassette = match (&args.record, &args.replay) {
+        (Some(path), _) => Some((CassetteMode::Record, path)),
+        (None, Some(path)) => Some((CassetteMode::Replay, path)),
+        (None, None) => None,
+    };
+
+    if let Some((mode, path)) = cassette {
+        if let Err(e) = cfg.use_cassette(mode, path) {
+            println!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Applies the `--policy` flag
+fn use_scoring_args(cfg: &mut Config, args: &cli::ScoringArgs) {
+    if let Some(path) = &args.policy {
+        if let Err(e) = cfg.use_policy(path) {
+            println!("{}", e);
+            std::process::exit(1);
+        }
+    }
+
+    println!("Scoring with policy {}", cfg.scoring.id);
+}
+
+/// Prints the token usage and cost of the run and writes them to the `--summary` file
+fn report_usage(cfg: &Config, args: &cli::LlmArgs) {
+    let summary = cfg.llm_usage.summary();
+
+    println!(
+        "LLM usage: {} requests, {} prompt tokens, {} completion tokens, ${:.4}",
+        summary.total.requests,
+        summary.total.prompt_tokens,
+        summary.total.completion_tokens,
+        summary.total.cost
+    );
+    for (model, totals) in &summary.models {
+        println!(
+            "  {}: {} tokens, ${:.4}",
+            model, totals.total_tokens, totals.cost
+        );
+    }
+    for (prompt, totals) in &summary.prompts {
+        println!(
+            "  {}: {} requests, {} tokens, ${:.4}",
+            prompt, totals.requests, totals.total_tokens, totals.cost
+        );
+    }
+
+    if let Some(cache) = &cfg.llm_cache {
+        println!(
+            "LLM cache: {} hits, {} misses",
+            cache.hits(),
+            cache.misses()
+        );
+    }
+
+    if summary.budget_exceeded {
+        println!("The run stopped early after reaching its budget");
+    }
+
+    if let Err(e) = cfg.llm_usage.write_summary(&args.summary) {
+        println!("failed to write the run summary: {}", e);
+    }
+}
+
+/// Exits non zero when a replayed simulation asked for a reply that was never recorded
+fn check_cassette(cfg: &Config) {
+    let misses = cfg.cassette_misses();
+    if misses > 0 {
+        println!(
+            "{} LLM requests had no recorded reply in the cassette",
+            misses
+        );
+        std::process::exit(1);
+    }
+}
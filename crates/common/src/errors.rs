@@ -1,3 +1,4 @@
+use pulpcalc_external::errors::LlmError;
 use thiserror::Error;
 
 #[derive(Debug, PartialEq, Error)]
@@ -12,15 +13,45 @@ pub enum PulpError {
 #[derive(Error, Debug, PartialEq)]
 pub enum SimulationError {
     #[error("simulation llm error: {0}")]
-    LLMError(String),
+    LLMError(LlmError),
+
+    #[error("simulation prompt template error in {0}: {1}")]
+    TemplateError(String, TemplateError),
+
+    #[error("simulation prompt registry error: {0}")]
+    PromptRegistryError(String),
+
+    #[error("simulation budget exceeded: {0}")]
+    BudgetExceeded(String),
 
     #[error("simulation neo4j error: {0}")]
     Neo4jError(String),
 
+    #[error("simulation sqlite error: {0}")]
+    SqliteError(String),
+
+    #[error("simulation store error: {0}")]
+    StoreError(String),
+
     #[error("simulation error: {0}")]
     SimError(String),
 }
 
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum TemplateError {
+    #[error("unclosed placeholder at byte {0}")]
+    Unclosed(usize),
+
+    #[error("invalid placeholder name `{0}`")]
+    InvalidName(String),
+
+    #[error("missing bindings for {}", .0.join(", "))]
+    Missing(Vec<String>),
+
+    #[error("unused bindings for {}", .0.join(", "))]
+    Unused(Vec<String>),
+}
+
 #[derive(Error, Debug, PartialEq)]
 pub enum ApiError {
     #[error("grpc error: {0}")]
@@ -29,3 +60,9 @@ pub enum ApiError {
     #[error("rest error: {0}")]
     RESTError(String),
 }
+
+impl From<LlmError> for PulpError {
+    fn from(e: LlmError) -> Self {
+        PulpError::SimulationError(SimulationError::LLMError(e))
+    }
+}
@@ -1,14 +1,26 @@
 pub mod config;
 pub mod errors;
-pub mod llm_config;
 pub mod models;
+pub mod prompt;
+pub mod registry;
+pub mod reply;
+pub mod scoring;
 pub mod simulation;
+pub mod store;
+pub mod template;
+pub mod usage;
 
 /// Re-export common Pulpcalc types and functions
 pub mod prelude {
     pub use crate::config::*;
     pub use crate::errors::*;
-    pub use crate::llm_config::*;
     pub use crate::models::*;
+    pub use crate::prompt::*;
+    pub use crate::registry::*;
+    pub use crate::reply::*;
+    pub use crate::scoring::*;
     pub use crate::simulation::*;
+    pub use crate::store::*;
+    pub use crate::template::*;
+    pub use crate::usage::*;
 }
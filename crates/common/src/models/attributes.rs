@@ -1,30 +1,68 @@
+use super::gpt_scoring::metrics::ContentMetrics;
+
 #[derive(Debug, Clone, Default)]
 pub struct Attributes {
     pub relevance: f64,
 
     pub soundness: f64,
 
+    pub grammar: f64,
+
+    /// URLs and citations
     pub references: i64,
 
     pub word_count: i64,
 
     pub mastery_vocab_words: Vec<String>,
+
+    pub sentence_count: i64,
+
+    /// Flesch-Kincaid grade level
+    pub readability: f64,
+
+    /// Distinct words over words, from 0 to 1
+    pub lexical_diversity: f64,
+
+    /// Numbers, percentages and statistic words
+    pub statistics: i64,
+
+    pub spelling_errors: i64,
+
+    /// LLM sub-scorers, i.e. `grammar`, whose prompt failed and that counted as 0
+    pub failed_scorers: Vec<String>,
 }
 
 impl Attributes {
+    /// Attributes from the LLM `relevance`, `soundness`, `grammar` and `mastery_vocab_words` and the locally
+    /// computed `metrics`
     pub fn new(
         relevance: f64,
         soundness: f64,
-        references: i64,
-        word_count: i64,
+        grammar: f64,
         mastery_vocab_words: Vec<String>,
+        metrics: &ContentMetrics,
     ) -> Self {
         Self {
             relevance,
             soundness,
-            references,
-            word_count,
+            grammar,
+            references: metrics.references(),
+            word_count: metrics.word_count,
             mastery_vocab_words,
+            sentence_count: metrics.sentence_count,
+            readability: metrics.readability,
+            lexical_diversity: metrics.lexical_diversity,
+            statistics: metrics.statistics,
+            spelling_errors: metrics.misspellings.len() as i64,
+            failed_scorers: Vec::new(),
         }
     }
+
+    /// Mastery vocabulary words the LLM found
+    pub fn mastery_words(&self) -> i64 {
+        self.mastery_vocab_words
+            .iter()
+            .filter(|word| !word.is_empty())
+            .count() as i64
+    }
 }
@@ -35,4 +35,15 @@ impl Engagements {
             response_timing,
         }
     }
+
+    /// Points of every engagement together
+    pub fn total(&self) -> i64 {
+        self.report_harmful_to_others
+            + self.report_abuseof_platform
+            + self.hide
+            + self.vote_validity
+            + self.vote_condfidence
+            + self.response_distance
+            + self.response_timing
+    }
 }
@@ -0,0 +1,518 @@
+use crate::errors::{PulpError, SimulationError};
+use std::{collections::HashSet, fs};
+
+/// Base forms of common English words, the dictionary spelling is checked against
+const ENGLISH_WORDS: &str = include_str!("words.txt");
+
+/// Suffixes stripped off a word before it is looked up, with the endings that can replace them
+const SUFFIXES: &[(&str, &[&str])] = &[
+    ("'s", &[""]),
+    ("ies", &["y"]),
+    ("es", &[""]),
+    ("s", &[""]),
+    ("ied", &["y"]),
+    ("ed", &["", "e"]),
+    ("ing", &["", "e"]),
+    ("ily", &["y"]),
+    ("ly", &["", "le"]),
+    ("ier", &["y"]),
+    ("iest", &["y"]),
+    ("er", &["", "e"]),
+    ("est", &["", "e"]),
+    ("ness", &[""]),
+    ("ment", &[""]),
+    ("ful", &[""]),
+    ("less", &[""]),
+    ("able", &["", "e"]),
+    ("ity", &["", "e"]),
+    ("ion", &["", "e"]),
+    ("al", &[""]),
+    ("ism", &[""]),
+    ("ist", &[""]),
+    ("ize", &["", "e"]),
+    ("ise", &["", "e"]),
+];
+
+/// Prefixes stripped off a word before it is looked up
+const PREFIXES: &[&str] = &[
+    "un", "re", "non", "dis", "pre", "mis", "over", "under", "counter", "anti", "co", "multi",
+    "inter", "sub", "super",
+];
+
+/// Words that mention a statistic without a number
+const STATISTIC_WORDS: &[&str] = &[
+    "percent",
+    "percentage",
+    "percentile",
+    "median",
+    "average",
+    "million",
+    "billion",
+    "trillion",
+];
+
+/// Cheap, reproducible measures of the content of a response, computed locally without prompting an LLM
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContentMetrics {
+    pub word_count: i64,
+
+    pub sentence_count: i64,
+
+    /// Flesch-Kincaid grade level, the years of schooling needed to follow the content
+    pub readability: f64,
+
+    /// Distinct words over words, from 0 to 1
+    pub lexical_diversity: f64,
+
+    pub urls: i64,
+
+    /// Bracketed `[1]` markers and parenthetical `(Author, 2020)` citations
+    pub citations: i64,
+
+    /// Numbers, percentages and statistic words like `median`
+    pub statistics: i64,
+
+    /// Words the dictionary doesn't know that are a single edit away from a word it does
+    pub misspellings: Vec<String>,
+}
+
+impl ContentMetrics {
+    pub fn measure(content: &str, dictionary: &Dictionary) -> Self {
+        let mut metrics = Self::default();
+
+        let mut words = Vec::new();
+        let mut sentence_start = true;
+        let mut previous_number = false;
+
+        for raw in content.split_whitespace() {
+            let ends_sentence = ends_sentence(raw);
+
+            if is_url(raw) {
+                metrics.urls += 1;
+                sentence_start = ends_sentence;
+                continue;
+            }
+
+            let token = raw
+                .trim_matches(|c: char| !c.is_alphanumeric() && !"$€£%'".contains(c))
+                .trim_matches('\'');
+            if !token.contains(char::is_alphanumeric) {
+                continue;
+            }
+
+            metrics.word_count += 1;
+
+            // `[1]` is a citation marker rather than a number
+            let number = is_number(token) && !raw.starts_with('[');
+            let lowercase = token.to_lowercase();
+            if number || (!previous_number && STATISTIC_WORDS.contains(&lowercase.as_str())) {
+                metrics.statistics += 1;
+            }
+            previous_number = number;
+
+            if token
+                .chars()
+                .all(|c| c.is_alphabetic() || c == '\'' || c == '-')
+            {
+                // Capitalised words within a sentence are names, which no dictionary lists
+                let name = !sentence_start && token.starts_with(char::is_uppercase);
+                let acronym = token
+                    .chars()
+                    .filter(|c| c.is_alphabetic())
+                    .all(char::is_uppercase);
+
+                if !name && !acronym && dictionary.misspelled(&lowercase) {
+                    metrics.misspellings.push(lowercase.clone());
+                }
+
+                words.push(lowercase);
+            }
+
+            sentence_start = ends_sentence;
+        }
+
+        metrics.sentence_count = sentences(content);
+        metrics.citations = citations(content);
+
+        if !words.is_empty() {
+            let syllables = words.iter().map(|word| syllables(word)).sum::<i64>();
+            let words_per_sentence = words.len() as f64 / metrics.sentence_count.max(1) as f64;
+            let syllables_per_word = syllables as f64 / words.len() as f64;
+
+            metrics.readability = 0.39 * words_per_sentence + 11.8 * syllables_per_word - 15.59;
+            metrics.lexical_diversity =
+                words.iter().collect::<HashSet<_>>().len() as f64 / words.len() as f64;
+        }
+
+        metrics
+    }
+
+    /// URLs and citations, the references the content makes
+    pub fn references(&self) -> i64 {
+        self.urls + self.citations
+    }
+}
+
+/// Words the spelling check knows, the embedded English list plus any added from files
+#[derive(Debug, Clone)]
+pub struct Dictionary {
+    words: HashSet<String>,
+}
+
+impl Default for Dictionary {
+    fn default() -> Self {
+        Self::english()
+    }
+}
+
+impl Dictionary {
+    /// Base forms of common English words
+    pub fn english() -> Self {
+        let mut dictionary = Self {
+            words: HashSet::new(),
+        };
+        dictionary.add(ENGLISH_WORDS);
+
+        dictionary
+    }
+
+    /// Adds the words of a file, one per line and `#` starting a comment, i.e. the vocabulary of a debate topic
+    pub fn extend_from_file(&mut self, path: &str) -> Result<(), PulpError> {
+        let words = fs::read_to_string(path).map_err(|e| {
+            PulpError::SimulationError(SimulationError::SimError(format!(
+                "spelling dictionary {}: {}",
+                path, e
+            )))
+        })?;
+
+        self.add(&words);
+
+        Ok(())
+    }
+
+    fn add(&mut self, words: &str) {
+        self.words.extend(
+            words
+                .lines()
+                .map(str::trim)
+                .filter(|word| !word.is_empty() && !word.starts_with('#'))
+                .map(str::to_lowercase),
+        );
+    }
+
+    /// Whether `word`, or the word it is inflected or prefixed from, is in the dictionary
+    pub fn knows(&self, word: &str) -> bool {
+        if self.lists(word) {
+            return true;
+        }
+
+        if word.contains('-') {
+            return word
+                .split('-')
+                .filter(|part| !part.is_empty())
+                .all(|part| self.knows(part));
+        }
+
+        PREFIXES
+            .iter()
+            .any(|prefix| match word.strip_prefix(prefix) {
+                Some(rest) if rest.len() > 2 => self.lists(rest),
+                _ => false,
+            })
+    }
+
+    /// Whether `word` or the word it is inflected from is listed
+    fn lists(&self, word: &str) -> bool {
+        self.words.contains(word) || stems(word).iter().any(|stem| self.words.contains(stem))
+    }
+
+    /// Whether `word` is a likely misspelling, unknown but a single edit away from a known word.
+    /// Unknown words nothing known is close to are taken for vocabulary the dictionary lacks
+    pub fn misspelled(&self, word: &str) -> bool {
+        if word.chars().count() < 3 || self.knows(word) {
+            return false;
+        }
+
+        edits(word).iter().any(|edit| self.lists(edit))
+    }
+}
+
+/// The words `word` can be inflected or derived from
+fn stems(word: &str) -> Vec<String> {
+    let mut stems = Vec::new();
+
+    for (suffix, endings) in SUFFIXES {
+        let base = match word.strip_suffix(suffix) {
+            Some(base) if base.len() > 1 => base,
+            _ => continue,
+        };
+
+        for ending in *endings {
+            stems.push(format!("{}{}", base, ending));
+        }
+
+        // running, stopped, bigger
+        let mut last = base.chars().rev();
+        if let (Some(c), Some(before)) = (last.next(), last.next()) {
+            if c == before && matches!(*suffix, "ing" | "ed" | "er" | "est") {
+                stems.push(base[..base.len() - c.len_utf8()].to_string());
+            }
+        }
+    }
+
+    stems
+}
+
+/// Every word a single deletion, transposition, replacement or insertion away from `word`
+fn edits(word: &str) -> Vec<String> {
+    let chars = word.chars().collect::<Vec<_>>();
+    let mut edits = Vec::new();
+
+    for i in 0..=chars.len() {
+        let (head, tail) = chars.split_at(i);
+        let head = head.iter().collect::<String>();
+
+        if let Some((_, rest)) = tail.split_first() {
+            edits.push(format!("{}{}", head, rest.iter().collect::<String>()));
+        }
+
+        if tail.len() > 1 {
+            edits.push(format!(
+                "{}{}{}{}",
+                head,
+                tail[1],
+                tail[0],
+                tail[2..].iter().collect::<String>()
+            ));
+        }
+
+        for c in 'a'..='z' {
+            if let Some((_, rest)) = tail.split_first() {
+                edits.push(format!("{}{}{}", head, c, rest.iter().collect::<String>()));
+            }
+
+            edits.push(format!("{}{}{}", head, c, tail.iter().collect::<String>()));
+        }
+    }
+
+    edits
+}
+
+fn ends_sentence(token: &str) -> bool {
+    token
+        .trim_end_matches(|c: char| "\"')]".contains(c))
+        .ends_with(['.', '!', '?'])
+}
+
+/// Runs of text ended by `.`, `!` or `?` and whitespace, so decimals don't end one, and the text after the last
+fn sentences(content: &str) -> i64 {
+    let tokens = content.split_whitespace().collect::<Vec<_>>();
+    let ended = tokens.iter().filter(|token| ends_sentence(token)).count() as i64;
+
+    match tokens.last() {
+        Some(last) if !ends_sentence(last) => ended + 1,
+        _ => ended,
+    }
+}
+
+fn is_url(token: &str) -> bool {
+    let token = token.trim_start_matches(|c: char| "(<[\"'".contains(c));
+
+    token.starts_with("http://") || token.starts_with("https://") || token.starts_with("www.")
+}
+
+/// `42`, `3.5%`, `$1,200` or `10k`, a number with a short unit at most
+fn is_number(token: &str) -> bool {
+    let digits = token.trim_start_matches(['$', '€', '£', '+', '-']);
+    if !digits.starts_with(|c: char| c.is_ascii_digit()) {
+        return false;
+    }
+
+    let unit = digits.trim_start_matches(|c: char| c.is_ascii_digit() || ".,%".contains(c));
+
+    unit.chars().count() <= 2 && unit.chars().all(char::is_alphabetic)
+}
+
+/// Bracketed `[1]` or `[1, 2]` markers and parentheses naming an author and a year, `(Smith et al., 2020)`
+fn citations(content: &str) -> i64 {
+    let mut citations = 0;
+
+    for (open, close) in [('[', ']'), ('(', ')')] {
+        for (start, _) in content.match_indices(open) {
+            let inner = match content[start + 1..].find(close) {
+                Some(end) => &content[start + 1..start + 1 + end],
+                None => continue,
+            };
+
+            let cited = if open == '[' {
+                !inner.trim().is_empty()
+                    && inner.split(',').all(|n| {
+                        !n.trim().is_empty() && n.trim().chars().all(|c| c.is_ascii_digit())
+                    })
+            } else {
+                inner.chars().any(char::is_alphabetic)
+                    && inner.split(|c: char| !c.is_alphanumeric()).any(|word| {
+                        let year = word.trim_end_matches(|c: char| c.is_ascii_lowercase());
+                        year.len() == 4
+                            && year.parse::<u32>().is_ok_and(|y| (1600..2100).contains(&y))
+                    })
+            };
+
+            if cited {
+                citations += 1;
+            }
+        }
+    }
+
+    citations
+}
+
+/// Vowel groups of `word`, leaving out a silent final `e`, at least 1
+fn syllables(word: &str) -> i64 {
+    let word = word.trim_end_matches("'s");
+    let mut syllables = 0;
+    let mut vowel = false;
+
+    for c in word.chars() {
+        let is_vowel = "aeiouy".contains(c);
+        if is_vowel && !vowel {
+            syllables += 1;
+        }
+        vowel = is_vowel;
+    }
+
+    if word.ends_with('e') && !word.ends_with("le") && syllables > 1 {
+        syllables -= 1;
+    }
+
+    syllables.max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn measure(content: &str) -> ContentMetrics {
+        ContentMetrics::measure(content, &Dictionary::english())
+    }
+
+    #[test]
+    fn nothing_is_measured_in_empty_content() {
+        assert_eq!(measure(""), ContentMetrics::default());
+    }
+
+    #[test]
+    fn counts_words_sentences_and_distinct_words() {
+        let metrics = measure("The cat saw the dog. It ran away!");
+
+        assert_eq!(metrics.word_count, 8);
+        assert_eq!(metrics.sentence_count, 2);
+        assert_eq!(metrics.lexical_diversity, 7.0 / 8.0);
+
+        // Text after the last full stop is a sentence, a decimal doesn't end one
+        assert_eq!(measure("It costs 2.5 dollars. Or less").sentence_count, 2);
+    }
+
+    #[test]
+    fn long_words_and_sentences_read_at_a_higher_grade() {
+        let simple = measure("The dog ran. The cat sat. We had fun.");
+        let complex = measure(
+            "Comprehensive environmental regulation necessitates considerable administrative \
+             coordination between governmental institutions and independent organizations.",
+        );
+
+        assert!(simple.readability < 2.0, "{}", simple.readability);
+        assert!(complex.readability > 16.0, "{}", complex.readability);
+    }
+
+    #[test]
+    fn counts_references_and_statistics() {
+        let metrics = measure(
+            "Prices rose 3.5% last year [1]. See https://example.com for the median wage (Smith, 2020)!",
+        );
+
+        // The URL isn't a word and the citation marker isn't a number
+        assert_eq!(metrics.word_count, 13);
+        assert_eq!(metrics.sentence_count, 2);
+        assert_eq!(metrics.urls, 1);
+        assert_eq!(metrics.citations, 2);
+        assert_eq!(metrics.references(), 3);
+        assert_eq!(metrics.statistics, 3);
+
+        // A statistic word right after a number is part of the same statistic
+        assert_eq!(measure("About 40 percent agreed.").statistics, 1);
+    }
+
+    #[test]
+    fn ordinary_prose_has_no_misspellings() {
+        let metrics = measure(
+            "Honestly, I'm torn on this issue. My grandparents ran a small bakery, and every \
+             morning they woke before sunrise, kneaded the dough and chatted with neighbours \
+             about the weather. Critics worry that remote work blurs the boundaries between home \
+             and office, while supporters argue that flexible schedules boost productivity and \
+             widen the talent pool. Wouldn't it be wiser to listen to the people who've lived \
+             through these changes? Vaccines eradicated smallpox and nearly eliminated polio, \
+             measles and whooping cough, yet hesitancy often stems from mistrust of institutions.",
+        );
+
+        assert!(metrics.word_count > 80);
+        assert_eq!(metrics.misspellings, Vec::<String>::new());
+    }
+
+    #[test]
+    fn lists_words_a_single_edit_from_a_known_word() {
+        let metrics = measure("Teh goverment should recieve more fundng.");
+
+        assert_eq!(
+            metrics.misspellings,
+            vec!["teh", "goverment", "recieve", "fundng"]
+        );
+    }
+
+    #[test]
+    fn names_acronyms_and_unfamiliar_vocabulary_are_not_misspellings() {
+        let metrics = measure("Yesterday Bobb met NASA engineers studying zygomorphic flowers.");
+
+        assert_eq!(metrics.misspellings, Vec::<String>::new());
+    }
+
+    #[test]
+    fn knows_inflected_and_prefixed_forms() {
+        let dictionary = Dictionary::english();
+
+        for word in [
+            "running",
+            "studies",
+            "happier",
+            "rewrote",
+            "children",
+            "unfair",
+            "well-known",
+            "doesn't",
+            "policymakers",
+        ] {
+            assert!(dictionary.knows(word), "{}", word);
+        }
+
+        assert!(!dictionary.knows("zyxwvut"));
+    }
+
+    #[test]
+    fn words_added_from_a_file_are_known() {
+        let path = env::temp_dir().join(format!("pulpcalc-dictionary-{}.txt", std::process::id()));
+        fs::write(&path, "# Vocabulary of the topic\nzygomorph\n\n").unwrap();
+
+        let mut dictionary = Dictionary::english();
+        dictionary
+            .extend_from_file(&path.to_string_lossy())
+            .unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(dictionary.knows("zygomorphs"));
+        assert!(dictionary.misspelled("zygomroph"));
+        assert!(Dictionary::english()
+            .extend_from_file("/nonexistent/words.txt")
+            .is_err());
+    }
+}
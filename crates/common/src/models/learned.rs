@@ -0,0 +1,18 @@
+#[derive(Debug, Default, Clone)]
+pub struct Learned {
+    pub id: String,
+
+    pub learned_content: String,
+
+    pub reason: String,
+}
+
+impl Learned {
+    pub fn new(id: String, learned_content: String, reason: String) -> Self {
+        Self {
+            id,
+            learned_content,
+            reason,
+        }
+    }
+}
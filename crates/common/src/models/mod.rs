@@ -1,16 +1,26 @@
 pub mod attributes;
+pub mod blog;
 pub mod debate;
 pub mod engagements;
 pub mod gpt_scoring;
+pub mod learned;
+pub mod personas_user;
 pub mod reference;
 pub mod response;
+pub mod score;
+pub mod simulation_run;
 pub mod user;
 pub mod vote;
 
 pub use attributes::Attributes;
+pub use blog::Blog;
 pub use debate::Debate;
 pub use engagements::Engagements;
+pub use learned::Learned;
+pub use personas_user::{Gender, PersonasUser};
 pub use reference::Reference;
 pub use response::Response;
+pub use score::{Score, ScoreVersion};
+pub use simulation_run::SimulationRun;
 pub use user::User;
 pub use vote::Vote;
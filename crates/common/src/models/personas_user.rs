@@ -0,0 +1,194 @@
+use super::user::User;
+
+#[derive(Debug, Clone, Default)]
+pub enum Gender {
+    Male,
+    Female,
+    #[default]
+    Other,
+    RatherNotSay,
+}
+
+impl From<&str> for Gender {
+    fn from(value: &str) -> Self {
+        match value {
+            "male" => Gender::Male,
+            "female" => Gender::Female,
+            "other" => Gender::Other,
+            "rather not say" => Gender::RatherNotSay,
+            "rather_not_say" => Gender::RatherNotSay,
+            _ => Gender::RatherNotSay,
+        }
+    }
+}
+
+impl ToString for Gender {
+    fn to_string(&self) -> String {
+        match self {
+            Gender::Male => "male".to_string(),
+            Gender::Female => "female".to_string(),
+            Gender::Other => "other".to_string(),
+            Gender::RatherNotSay => "rather_not_say".to_string(),
+            _ => "rather_not_say".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub enum PoliticalOrientation {
+    Right,
+    Left,
+    #[default]
+    Center,
+}
+
+impl From<&str> for PoliticalOrientation {
+    fn from(value: &str) -> Self {
+        match value {
+            "right" => PoliticalOrientation::Right,
+            "left" => PoliticalOrientation::Left,
+            "center" => PoliticalOrientation::Center,
+            _ => PoliticalOrientation::Center,
+        }
+    }
+}
+
+impl ToString for PoliticalOrientation {
+    fn to_string(&self) -> String {
+        match self {
+            PoliticalOrientation::Right => "right".to_string(),
+            PoliticalOrientation::Left => "left".to_string(),
+            PoliticalOrientation::Center => "center".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EPL {
+    pub ethos: f64,
+    pub pathos: f64,
+    pub logos: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PersonasUser {
+    pub age: i64,
+
+    pub gender: Gender,
+
+    pub political_orientation: PoliticalOrientation,
+
+    pub vote_valid_influence: Vec<f64>,
+
+    pub vote_invalid_influence: Vec<f64>,
+
+    pub vote_abstain_influence: Vec<f64>,
+
+    pub knoweledge: Knowledge,
+
+    pub network: Network,
+
+    pub personality: Personality,
+
+    pub base_user: User,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Knowledge {
+    pub knowledge_references: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Network {
+    pub network_size: NetworkSize,
+
+    pub network_activity: NetworkActivity,
+
+    pub network_composition: NetworkComposition,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Personality {
+    pub personality_content: PersonalityContent,
+
+    pub personality_base: PersonalityBase,
+
+    pub personality_engagement: PersonalityEngagement,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NetworkSize {
+    pub followers: i64,
+
+    pub following: i64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NetworkActivity {
+    pub impressions: i64,
+
+    pub engagements: i64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NetworkComposition {
+    pub network_personality: Vec<Personality>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PersonalityContent {
+    pub input: f64,
+
+    pub output: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PersonalityBase {
+    pub core_fear: String,
+
+    pub enneagram: i64,
+
+    pub core_desire: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PersonalityEngagement {
+    pub voting_tendency: (i64, i64, i64),
+
+    pub hide_tendency: f64,
+
+    pub report_tendency: f64,
+
+    pub engagement_type: i64,
+}
+
+impl PersonasUser {
+    pub fn new(
+        age: i64,
+        gender: &str,
+        political_orientation: &str,
+        vote_valid_influence: Vec<f64>,
+        vote_invalid_influence: Vec<f64>,
+        vote_abstain_influence: Vec<f64>,
+        knowledge: Knowledge,
+        network: Network,
+        personality: Personality,
+        base_user: User,
+    ) -> Self {
+        let g = Gender::from(gender);
+        let po = PoliticalOrientation::from(political_orientation);
+
+        Self {
+            age,
+            gender: g,
+            political_orientation: po,
+            vote_valid_influence,
+            vote_invalid_influence,
+            vote_abstain_influence,
+            knoweledge: knowledge,
+            network,
+            personality,
+            base_user,
+        }
+    }
+}
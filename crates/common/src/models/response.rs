@@ -1,14 +1,14 @@
+use super::attributes::Attributes;
 use super::engagements::Engagements;
-use super::reference::Reference;
-use super::user::User;
-use super::{attributes::Attributes, Debate};
-use crate::errors::{PulpError, SimulationError};
-use crate::llm_config::LLMRequest;
-use crate::models::gpt_scoring::*;
-use neo4rs::{Graph, Query};
-use std::sync::Arc;
-use tokio::{join, task, task::JoinError};
-use uuid::Uuid;
+use crate::config::Config;
+use crate::errors::PulpError;
+use crate::models::gpt_scoring::{metrics::ContentMetrics, *};
+use crate::prompt::Prompt;
+use crate::registry::PromptRegistry;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use std::fmt::Debug;
+use tokio::{join, task};
 
 #[derive(Debug, Default, Clone)]
 pub struct Response {
@@ -45,6 +45,19 @@ pub struct Response {
     pub attributes: Attributes,
 
     pub engagements: Engagements,
+
+    /// Name of the prompt that generated the content
+    pub prompt_name: String,
+
+    /// Version of the prompt that generated the content
+    pub prompt_version: String,
+
+    /// Time the response was written at, in milliseconds. Simulations stamp it from their `SimulationClock`,
+    /// responses written without a time get the Unix time they were stored at
+    pub created_at: i64,
+
+    /// Id of the scoring policy the response was last scored under
+    pub scoring_policy: String,
 }
 
 impl Response {
@@ -66,6 +79,10 @@ impl Response {
         references: Vec<String>,
         attributes: Attributes,
         engagements: Engagements,
+        prompt_name: String,
+        prompt_version: String,
+        created_at: i64,
+        scoring_policy: String,
     ) -> Self {
         Self {
             id,
@@ -85,753 +102,206 @@ impl Response {
             references,
             attributes,
             engagements,
+            prompt_name,
+            prompt_version,
+            created_at,
+            scoring_policy,
         }
     }
 
-    // State variables for readbility
-    pub fn calculate_engagement_score(&mut self) -> i64 {
-        // let mut report_harmful_to_others = 0;
-        // let mut report_abuse_of_platform = 0;
-        // let mut hide = 0;
-        // let mut vote_validity = 0;
-        // let mut vote_confidence = 0;
-        // let mut response_distance = 0;
-        // let response_timing = 0;
-
-        self.engagements = Engagements::new(0, 0, 0, 0, 0, 0, 0);
-
-        0
-    }
-
-    // TODO: if let some here
-    #[allow(unused_mut)]
-    #[allow(unused_assignments)]
-    pub async fn calculate_content_attribute_score(
-        &mut self,
-        open_ai_key: Arc<String>,
-    ) -> Result<i64, PulpError> {
-        let mut init_score: i64 = self.score;
-        let mut relevance = 0.0;
-        let mut soundness = 0.0;
-        // let mut stats_included = 0;
-        let mut references = 0;
-        // let mut syntax_and_grammar = 0;
-        // let mut spelling = 0;
-        let mut word_count: i64 = 0;
-        let mut mastery_vocabulary: i64 = 0;
-
-        let mut relevance_prompt = RelevanceContentPrompt::default();
-        relevance_prompt.replace_attributes(vec![
-            ("THIS_TOPIC".to_string(), self.topic_of_response.clone()),
-            ("THIS_CONTENT".to_string(), self.content.clone()),
-        ]);
-
-        let mut soundness_prompt = SoundnessContentPrompt::default();
-        soundness_prompt.replace_attributes(vec![
-            ("THIS_TOPIC".to_string(), self.topic_of_response.clone()),
-            ("THIS_CONTENT".to_string(), self.content.clone()),
-        ]);
-
-        let mut mastery_prompt = MasteryVocabContentPrompt::default();
-        mastery_prompt.replace_attributes(vec![
-            ("THIS_TOPIC".to_string(), self.topic_of_response.clone()),
-            ("THIS_CONTENT".to_string(), self.content.clone()),
-        ]);
-
-        let (rel, mastery, sound): (
-            Result<RelevanceResponse, JoinError>,
-            Result<MasteryVocabResponse, JoinError>,
-            Result<SoundnessResponse, JoinError>,
-        ) = join!(
-            task::spawn({
-                let key = open_ai_key.clone();
-
-                async move {
-                    match relevance_prompt.clone().send(key).await {
-                        Ok(rel) => {
-                            println!("rel: {:?}", rel);
-
-                            rel
-                        }
-
-                        Err(e) => {
-                            println!("err: {:?}", e);
-
-                            RelevanceResponse::default()
-                        }
-                    }
-                }
-            }),
-            task::spawn({
-                let key = open_ai_key.clone();
-
-                async move {
-                    match mastery_prompt.clone().send(key.clone()).await {
-                        Ok(mast) => {
-                            println!("mast: {:?}", mast);
-
-                            mast
-                        }
-
-                        Err(e) => {
-                            println!("err: {:?}", e);
-
-                            MasteryVocabResponse::default()
-                        }
-                    }
-                }
-            }),
-            task::spawn({
-                let key = open_ai_key.clone();
-
-                async move {
-                    match soundness_prompt.clone().send(key).await {
-                        Ok(sound) => {
-                            println!("sound: {:?}", sound);
-
-                            sound
-                        }
-
-                        Err(e) => {
-                            println!("err: {:?}", e);
-
-                            SoundnessResponse::default()
-                        }
-                    }
-                }
-            })
-        );
-
-        relevance = rel.unwrap().relevance;
-        soundness = sound.unwrap().soundness;
-        mastery_vocabulary = if let Some(words) = mastery.unwrap().mastery_words {
-            let l = words.len() as i64;
-
-            self.attributes = Attributes::new(
-                relevance,
-                soundness,
-                references,
-                self.content.split_whitespace().count() as i64,
-                words,
-            );
-
-            l
-        } else {
-            self.attributes = Attributes::new(
-                relevance,
-                soundness,
-                references,
-                self.content.split_whitespace().count() as i64,
-                vec!["".to_string()],
-            );
-
-            0
-        };
-
-        println!("relevance: {:?}", relevance);
-        println!("soundness: {:?}", soundness);
-
-        init_score +=
-            (mastery_vocabulary + self.invalid_vote_count) * (relevance + soundness) as i64;
-
-        println!("init_score: {:#?}", init_score);
-
-        Ok(init_score)
-    }
-
-    pub fn calculate_reply_score(&self) -> i64 {
-        0
+    /// Records the prompt, and the revision of it, that generated the content
+    pub fn stamp_prompt<R>(&mut self, prompt: &Prompt<R>, registry: &PromptRegistry) {
+        self.prompt_name = prompt.name.to_string();
+        self.prompt_version = registry.version(prompt.name);
     }
 
-    // ==================================
-    //           Neo4j Methods          =
-    // ==================================
+    /// Scores the votes, reports, hides and replies stored for the response so far, each weighted by the
+    /// points the scoring policy gives its action, and keeps the breakdown as its `engagements`.
+    ///
+    /// Replies earn less the deeper they are in the thread and direct replies earn more the sooner they came.
+    /// A response that isn't stored yet has no engagement. Only reads the store, so scoring after the writes
+    /// of a round are flushed doesn't flush again, `store_score` writes the result.
+    pub async fn calculate_engagement_score(&mut self, config: &Config) -> Result<i64, PulpError> {
+        let policy = &config.scoring;
 
-    pub async fn create(&self, graph: &Graph) -> Result<String, PulpError> {
-        let id = Uuid::new_v4().to_string();
-        let q = Query::new(
-            "CREATE (r:Response {id: $id, content: $content, score: $score, valid_vote_count: $vvc, invalid_vote_count: $ivc, abstain_vote_count: $avc, hide_count: $hide_count, report_count: $report_count, ethos: $ethos, pathos: $pathos, logos: $logos}) RETURN (r.id)".to_string(),
-        )
-        .param("id", id.clone())
-        .param("content", self.content.to_string())
-        .param("score", 0)
-        .param("vvc", 0)
-        .param("ivc", 0)
-        .param("avc", 0)
-        .param("hide_count", 0)
-        .param("report_count", 0)
-        // .param("replies", vec![""])
-        .param("ethos", 0.0)
-        .param("pathos", 0.0)
-        .param("logos", 0.0);
-
-        match graph.start_txn().await {
-            Ok(tx) => {
-                match tx.execute(q).await {
-                    Ok(mut res) => {
-                        let row = res.next().await.unwrap().unwrap();
-
-                        let id: Option<String> = row.get("(r.id)");
-
-                        id
-                    }
-
-                    Err(e) => {
-                        return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                            e.to_string(),
-                        )));
-                    }
-                };
-
-                if let Err(e) = tx.commit().await {
-                    return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                        e.to_string(),
-                    )));
-                };
-            }
+        self.engagements = Engagements::default();
+        self.scoring_policy = policy.id.clone();
 
-            Err(e) => {
-                return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                    e.to_string(),
-                )));
-            }
-        };
-
-        Ok(id)
-    }
-
-    pub async fn get_response(&self, graph: &Graph) -> Result<Self, PulpError> {
-        let q = Query::new("MATCH (r:Response {id: $id})".to_string()).param("id", self.id.clone());
-
-        let response = match graph.start_txn().await {
-            Ok(tx) => {
-                let response = match tx.execute(q).await {
-                    Ok(mut res) => {
-                        let row = res.next().await.unwrap().unwrap();
-
-                        let id: Option<String> = row.get("r.id");
-                        let content: Option<String> = row.get("r.content");
-                        let score: Option<i64> = row.get("r.score");
-                        let valid_vote_count: Option<i64> = row.get("r.valid_vote_count");
-                        let invalid_vote_count: Option<i64> = row.get("r.invalid_vote_count");
-                        let abstain_vote_count: Option<i64> = row.get("r.abstain_vote_count");
-                        let hide_count: Option<i64> = row.get("r.hide_count");
-                        let report_count: Option<i64> = row.get("r.report_count");
-                        let ethos: Option<f64> = row.get("r.ethos");
-                        let pathos: Option<f64> = row.get("r.pathos");
-                        let logos: Option<f64> = row.get("r.logos");
-
-                        let mut response = Response::default();
-                        response.content = content.unwrap();
-                        response.id = id.unwrap();
-                        response.score = score.unwrap();
-                        response.valid_vote_count = valid_vote_count.unwrap();
-                        response.invalid_vote_count = invalid_vote_count.unwrap();
-                        response.abstain_vote_count = abstain_vote_count.unwrap();
-                        response.hide_count = hide_count.unwrap();
-                        response.report_count = report_count.unwrap();
-                        response.ethos = ethos.unwrap();
-                        response.pathos = pathos.unwrap();
-                        response.logos = logos.unwrap();
-
-                        response
-                    }
-
-                    Err(e) => {
-                        return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                            e.to_string(),
-                        )));
-                    }
-                };
-
-                if let Err(e) = tx.commit().await {
-                    return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                        e.to_string(),
-                    )));
-                };
-
-                response
-            }
-
-            Err(e) => {
-                return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                    e.to_string(),
-                )));
-            }
-        };
-
-        Ok(response)
-    }
-
-    pub async fn update_response(&self, graph: &Graph) -> Result<(), PulpError> {
-        let q = Query::new("MATCH (r:Response {id: $id}) SET r.content = $content, r.confidence = $confidence, r.score = $score, r.valid_vote_count = $vvc, r.invalid_vote_count = $ivc, r.abstain_vote_count = $avc, r.author_id = $author_id)".to_string())
-            .param("id", self.id.clone())
-            .param("content", self.content.clone())
-            .param("confidence", self.confidence.to_string())
-            .param("score", self.score)
-            .param("vvc", self.valid_vote_count)
-            .param("ivc", self.invalid_vote_count)
-            .param("avc", self.abstain_vote_count);
-
-        match graph.start_txn().await {
-            Ok(tx) => {
-                match tx.execute(q).await {
-                    Ok(_) => {}
-
-                    Err(e) => {
-                        return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                            e.to_string(),
-                        )));
-                    }
-                };
-
-                if let Err(e) = tx.commit().await {
-                    return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                        e.to_string(),
-                    )));
-                };
-            }
-
-            Err(e) => {
-                return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                    e.to_string(),
-                )));
-            }
+        if self.id.is_empty() {
+            return Ok(0);
         }
 
-        Ok(())
-    }
-
-    pub async fn update_valid_vote_count(
-        &self,
-        graph: &Graph,
-        count: i64,
-    ) -> Result<(), PulpError> {
-        let q =
-            Query::new("MATCH (r:Response {id: $id}) SET r.valid_vote_count = $vvc".to_string())
-                .param("id", self.id.clone())
-                .param("vvc", count);
-
-        match graph.start_txn().await {
-            Ok(tx) => {
-                match tx.execute(q).await {
-                    Ok(_) => {}
-
-                    Err(e) => {
-                        return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                            e.to_string(),
-                        )));
-                    }
-                };
-
-                if let Err(e) = tx.commit().await {
-                    return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                        e.to_string(),
-                    )));
-                };
-            }
+        let stored = config.store.get_response(&self.id).await?;
+        self.engagements = policy.engagements(config.store.as_ref(), &stored).await?;
 
-            Err(e) => {
-                return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                    e.to_string(),
-                )));
-            }
-        }
-
-        Ok(())
+        Ok(policy.engagement_score(&self.engagements))
     }
 
-    pub async fn update_invalid_vote_count(
-        &self,
-        graph: &Graph,
-        count: i64,
-    ) -> Result<(), PulpError> {
-        let q =
-            Query::new("MATCH (r:Response {id: $id}) SET r.invalid_vote_count = $ivc".to_string())
-                .param("id", self.id.clone())
-                .param("ivc", count);
-
-        match graph.start_txn().await {
-            Ok(tx) => {
-                match tx.execute(q).await {
-                    Ok(_) => {}
-
-                    Err(e) => {
-                        return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                            e.to_string(),
-                        )));
-                    }
-                };
-
-                if let Err(e) = tx.commit().await {
-                    return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                        e.to_string(),
-                    )));
-                };
-            }
-
-            Err(e) => {
-                return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                    e.to_string(),
-                )));
-            }
-        }
-
-        Ok(())
+    /// Writes the score of the response with the policy and engagement breakdown it was scored with
+    pub async fn store_score(&self, config: &Config) -> Result<(), PulpError> {
+        config
+            .store
+            .update_response_engagements(&self.id, &self.engagements)
+            .await?;
+
+        config
+            .store
+            .update_response_score(&self.id, self.score, &self.scoring_policy)
+            .await
     }
-    pub async fn update_abstain_vote_count(
-        &self,
-        graph: &Graph,
-        count: i64,
-    ) -> Result<(), PulpError> {
-        let q =
-            Query::new("MATCH (r:Response {id: $id}) SET r.abstain_vote_count = $avc".to_string())
-                .param("id", self.id.clone())
-                .param("avc", count);
-
-        match graph.start_txn().await {
-            Ok(tx) => {
-                match tx.execute(q).await {
-                    Ok(_) => {}
-
-                    Err(e) => {
-                        return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                            e.to_string(),
-                        )));
-                    }
-                };
-
-                if let Err(e) = tx.commit().await {
-                    return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                        e.to_string(),
-                    )));
-                };
-            }
 
-            Err(e) => {
-                return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                    e.to_string(),
-                )));
-            }
-        }
+    /// Content points of the response from the LLM relevance, soundness, grammar and mastery vocabulary
+    /// and the locally computed metrics. A sub-scorer whose prompt fails counts as 0 and is recorded in
+    /// `attributes.failed_scorers`, so the score degrades rather than the whole response failing
+    pub async fn calculate_content_attribute_score(
+        &mut self,
+        config: &Config,
+    ) -> Result<i64, PulpError> {
+        let mut init_score: i64 = self.score;
 
-        Ok(())
-    }
+        let metrics = ContentMetrics::measure(&self.content, &config.dictionary);
 
-    pub async fn update_score(&self, graph: &Graph, score: i64) -> Result<(), PulpError> {
-        let q = Query::new("MATCH (r:Response {id: $id}) SET r.score = $score".to_string())
-            .param("id", self.id.clone())
-            .param("score", score);
-
-        match graph.start_txn().await {
-            Ok(tx) => {
-                match tx.execute(q).await {
-                    Ok(_) => {}
-
-                    Err(e) => {
-                        return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                            e.to_string(),
-                        )));
-                    }
-                };
-
-                if let Err(e) = tx.commit().await {
-                    return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                        e.to_string(),
-                    )));
-                };
-            }
+        let relevance_prompt = relevance_prompt(&self.topic_of_response, &self.content);
+        let soundness_prompt = soundness_prompt(&self.topic_of_response, &self.content);
+        let grammar_prompt = grammar_prompt(&self.content);
+        let mastery_prompt = mastery_vocab_prompt(&self.topic_of_response, &self.content);
 
-            Err(e) => {
-                return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                    e.to_string(),
-                )));
-            }
-        }
+        let (rel, sound, grammar, mastery) = join!(
+            sub_score(config, relevance_prompt),
+            sub_score(config, soundness_prompt),
+            sub_score(config, grammar_prompt),
+            sub_score(config, mastery_prompt),
+        );
 
-        Ok(())
-    }
+        let mut failed_scorers = Vec::new();
+        let mut failed = |name: &str| failed_scorers.push(name.to_string());
 
-    pub async fn update_ethos(&self, graph: &Graph, val: f64) -> Result<(), PulpError> {
-        let q = Query::new("MATCH (r:Response {id: $id}) SET r.ethos = $ethos".to_string())
-            .param("id", self.id.clone())
-            .param("ethos", val);
-
-        match graph.start_txn().await {
-            Ok(tx) => {
-                match tx.execute(q).await {
-                    Ok(_) => {}
-
-                    Err(e) => {
-                        return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                            e.to_string(),
-                        )));
-                    }
-                };
-
-                if let Err(e) = tx.commit().await {
-                    return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                        e.to_string(),
-                    )));
-                };
+        let relevance = match rel {
+            Some(rel) => rel.relevance,
+            None => {
+                failed("relevance");
+                0.0
             }
-
-            Err(e) => {
-                return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                    e.to_string(),
-                )));
+        };
+        let soundness = match sound {
+            Some(sound) => sound.soundness,
+            None => {
+                failed("soundness");
+                0.0
             }
-        }
-
-        Ok(())
-    }
-
-    pub async fn update_logos(&self, graph: &Graph, val: f64) -> Result<(), PulpError> {
-        let q = Query::new("MATCH (r:Response {id: $id}) SET r.logos = $logos".to_string())
-            .param("id", self.id.clone())
-            .param("logos", val);
-
-        match graph.start_txn().await {
-            Ok(tx) => {
-                match tx.execute(q).await {
-                    Ok(_) => {}
-
-                    Err(e) => {
-                        return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                            e.to_string(),
-                        )));
-                    }
-                };
-
-                if let Err(e) = tx.commit().await {
-                    return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                        e.to_string(),
-                    )));
-                };
+        };
+        let grammar = match grammar {
+            Some(grammar) => grammar.grammar,
+            None => {
+                failed("grammar");
+                0.0
             }
-
-            Err(e) => {
-                return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                    e.to_string(),
-                )));
+        };
+        let mastery_words = match mastery {
+            Some(mastery) => mastery
+                .mastery_words
+                .unwrap_or_else(|| vec!["".to_string()]),
+            None => {
+                failed("mastery_vocab");
+                vec!["".to_string()]
             }
-        }
-
-        Ok(())
-    }
+        };
 
-    pub async fn update_pathos(&self, graph: &Graph, val: f64) -> Result<(), PulpError> {
-        let q = Query::new("MATCH (r:Response {id: $id}) SET r.pathos = $pathos".to_string())
-            .param("id", self.id.clone())
-            .param("pathos", val);
-
-        match graph.start_txn().await {
-            Ok(tx) => {
-                match tx.execute(q).await {
-                    Ok(_) => {}
-
-                    Err(e) => {
-                        return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                            e.to_string(),
-                        )));
-                    }
-                };
-
-                if let Err(e) = tx.commit().await {
-                    return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                        e.to_string(),
-                    )));
-                };
-            }
+        self.attributes = Attributes::new(relevance, soundness, grammar, mastery_words, &metrics);
+        self.attributes.failed_scorers = failed_scorers;
 
-            Err(e) => {
-                return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                    e.to_string(),
-                )));
-            }
-        }
+        init_score += config
+            .scoring
+            .content_score(&self.attributes, self.invalid_vote_count);
+        self.scoring_policy = config.scoring.id.clone();
 
-        Ok(())
+        Ok(init_score)
     }
 
-    pub async fn delete_response(&self, graph: &Graph) -> Result<(), PulpError> {
-        let q = Query::new("MATCH (r:Response {id: $id}) DETACH DELETE r".to_string())
-            .param("id", self.id.clone());
-
-        match graph.start_txn().await {
-            Ok(tx) => {
-                match tx.execute(q).await {
-                    Ok(_) => {}
-
-                    Err(e) => {
-                        return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                            e.to_string(),
-                        )));
-                    }
-                };
-
-                if let Err(e) = tx.commit().await {
-                    return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                        e.to_string(),
-                    )));
-                };
-            }
-
-            Err(e) => {
-                return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                    e.to_string(),
-                )));
-            }
-        }
-
-        Ok(())
+    pub fn calculate_reply_score(&self) -> i64 {
+        0
     }
+}
 
-    pub async fn add_user_responded(&self, graph: &Graph, user: User) -> Result<(), PulpError> {
-        let q = Query::new("MATCH (r:Response {id: $id}) MATCH (u:User {id: $user_id}) CREATE (u)-[:RESPONDED]->(r)".to_string())
-            .param("id", self.id.clone())
-            .param("user_id", user.id.clone());
-
-        match graph.start_txn().await {
-            Ok(tx) => {
-                match tx.execute(q).await {
-                    Ok(_) => {}
-
-                    Err(e) => {
-                        return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                            e.to_string(),
-                        )));
-                    }
-                };
-
-                if let Err(e) = tx.commit().await {
-                    return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                        e.to_string(),
-                    )));
-                };
-            }
+/// Sends the prompt of a sub-scorer on a task of its own, `None` when the prompt or the task fails
+async fn sub_score<R>(config: &Config, prompt: Prompt<R>) -> Option<R>
+where
+    R: DeserializeOwned + JsonSchema + Debug + Send + 'static,
+{
+    let config = config.clone();
 
-            Err(e) => {
-                return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                    e.to_string(),
-                )));
-            }
-        }
-
-        Ok(())
+    match task::spawn(async move { prompt.send(&config).await }).await {
+        Ok(Ok(reply)) => Some(reply),
+        Ok(Err(_)) | Err(_) => None,
     }
+}
 
-    pub async fn add_has_referecne(
-        &self,
-        graph: &Graph,
-        reference: Reference,
-    ) -> Result<(), PulpError> {
-        let q = Query::new("MATCH (r:Response {id: $id}) MATCH (ref:Reference {id: $reference_id}) CREATE (r)-[:HAS_REFERENCE]->(ref)".to_string())
-            .param("id", self.id.clone())
-            .param("reference_id", reference.id.clone());
-
-        match graph.start_txn().await {
-            Ok(tx) => {
-                match tx.execute(q).await {
-                    Ok(_) => {}
-
-                    Err(e) => {
-                        return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                            e.to_string(),
-                        )));
-                    }
-                };
-
-                if let Err(e) = tx.commit().await {
-                    return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                        e.to_string(),
-                    )));
-                };
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs};
 
-            Err(e) => {
-                return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                    e.to_string(),
-                )));
-            }
-        }
+    /// A config sending prompts to the mock provider
+    async fn mock_config() -> Config {
+        let mut config = Config::default().await;
+        config.llm_provider = Some(String::from("mock"));
+        config.llm = config.new_llm_provider();
 
-        Ok(())
+        config
     }
 
-    pub async fn add_reply_relationship(
-        &self,
-        graph: &Graph,
-        reply: Self,
-    ) -> Result<(), PulpError> {
-        let q = Query::new("MATCH (r:Response {id: $id}) MATCH (rep:Response {id: $reply_id}) CREATE (r)-[:REPLIED]->(rep)".to_string())
-            .param("id", self.id.clone())
-            .param("reply_id", reply.id.clone());
-
-        match graph.start_txn().await {
-            Ok(tx) => {
-                match tx.execute(q).await {
-                    Ok(_) => {}
-
-                    Err(e) => {
-                        return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                            e.to_string(),
-                        )));
-                    }
-                };
-
-                if let Err(e) = tx.commit().await {
-                    return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                        e.to_string(),
-                    )));
-                };
-            }
-
-            Err(e) => {
-                return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                    e.to_string(),
-                )));
-            }
+    fn response() -> Response {
+        Response {
+            topic_of_response: "Is remote work here to stay?".to_string(),
+            content: "Remote work lets teams hire anywhere, though mentoring juniors is harder."
+                .to_string(),
+            ..Default::default()
         }
-
-        Ok(())
     }
 
-    pub async fn add_debate_response_relationship(
-        &self,
-        graph: &Graph,
-        debate: Debate,
-    ) -> Result<(), PulpError> {
-        let q = Query::new("MATCH (r:Response {id: $id}) MATCH (d:Debate {id: $debate_id}) CREATE (r)-[:RESPONSE]->(d)".to_string())
-            .param("id", self.id.clone())
-            .param("debate_id", debate.id.clone());
-
-        match graph.start_txn().await {
-            Ok(tx) => {
-                match tx.execute(q).await {
-                    Ok(_) => {}
-
-                    Err(e) => {
-                        return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                            e.to_string(),
-                        )));
-                    }
-                };
-
-                if let Err(e) = tx.commit().await {
-                    return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                        e.to_string(),
-                    )));
-                };
-            }
-
-            Err(e) => {
-                return Err(PulpError::SimulationError(SimulationError::Neo4jError(
-                    e.to_string(),
-                )));
-            }
-        }
+    #[tokio::test]
+    async fn a_failing_sub_scorer_counts_as_nothing_and_is_recorded() {
+        let config = mock_config().await;
+        let mut scored = response();
+        let expected = scored
+            .calculate_content_attribute_score(&config)
+            .await
+            .unwrap();
+        assert!(scored.attributes.failed_scorers.is_empty());
+
+        // The mock provider answers prompts it recognizes, this grammar template isn't one of them
+        let path = env::temp_dir().join(format!("pulpcalc-degrade-{}.toml", std::process::id()));
+        fs::write(
+            &path,
+            "[grammar]\nversion = \"unrecognized\"\ntemplate = \"Rate the writing of {{content}}\"\n",
+        )
+        .unwrap();
+
+        let mut config = mock_config().await;
+        config.use_prompts(&path.to_string_lossy()).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let mut degraded = response();
+        let score = degraded
+            .calculate_content_attribute_score(&config)
+            .await
+            .unwrap();
+
+        let attributes = &degraded.attributes;
+        assert_eq!(attributes.failed_scorers, vec!["grammar"]);
+        assert_eq!(attributes.grammar, 0.0);
+
+        // The other sub-scorers got the same replies as before and still count
+        assert_eq!(attributes.relevance, scored.attributes.relevance);
+        assert_eq!(attributes.soundness, scored.attributes.soundness);
+        assert_eq!(
+            attributes.mastery_vocab_words,
+            scored.attributes.mastery_vocab_words
+        );
+        assert_eq!(attributes.word_count, scored.attributes.word_count);
 
-        Ok(())
+        assert_eq!(score, config.scoring.content_score(attributes, 0));
+        assert!(score > 0);
+        assert!(score <= expected);
     }
 }
@@ -0,0 +1,52 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Scores of a run recomputed under a scoring policy, kept side by side with the scores written during
+/// the run and every earlier version
+#[derive(Debug, Default, Clone)]
+pub struct ScoreVersion {
+    pub id: String,
+
+    /// Id of the run whose debates and responses were scored
+    pub run_id: String,
+
+    /// Id of the scoring policy they were scored under
+    pub policy: String,
+
+    /// Unix time the version was scored at, in seconds
+    pub scored_at: i64,
+}
+
+impl ScoreVersion {
+    /// A new version of the scores of the run with `run_id`, scored now under `policy`
+    pub fn new(run_id: &str, policy: &str) -> Self {
+        let scored_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default();
+
+        Self {
+            id: String::new(),
+            run_id: run_id.to_string(),
+            policy: policy.to_string(),
+            scored_at,
+        }
+    }
+}
+
+/// Score of a single debate or response within a score version
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Score {
+    /// Id of the debate or response
+    pub entity_id: String,
+
+    /// `Debate` or `Response`
+    pub label: String,
+
+    /// Weighted content points
+    pub content: i64,
+
+    /// Weighted engagement points
+    pub engagement: i64,
+
+    pub score: i64,
+}
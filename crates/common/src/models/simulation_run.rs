@@ -0,0 +1,54 @@
+use crate::errors::{PulpError, SimulationError};
+use sha2::{Digest, Sha256};
+use std::{
+    fs,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use uuid::Uuid;
+
+/// A single `pulpcalc sim` invocation, every entity and relationship it writes is tagged with its id
+#[derive(Debug, Default, Clone)]
+pub struct SimulationRun {
+    pub id: String,
+
+    /// Kind of simulation, i.e. `enneagram`
+    pub simulation: String,
+
+    /// Path of the simulation config file
+    pub config_file: String,
+
+    /// Sha256 of the simulation config file, telling apart runs of a file that was edited in between
+    pub config_hash: String,
+
+    /// Seed of the RNG the simulation drew its random choices from, stored with the bits of the `u64` seed
+    pub seed: i64,
+
+    /// Unix time the run started at, in seconds
+    pub started_at: i64,
+}
+
+impl SimulationRun {
+    /// A new run of the `simulation` configured by the file at `config_file`
+    pub fn new(simulation: &str, config_file: &str, seed: u64) -> Result<Self, PulpError> {
+        let config = fs::read(config_file).map_err(|e| {
+            PulpError::SimulationError(SimulationError::StoreError(format!(
+                "failed to read {}: {}",
+                config_file, e
+            )))
+        })?;
+
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default();
+
+        Ok(Self {
+            id: Uuid::new_v4().to_string(),
+            simulation: simulation.to_string(),
+            config_file: config_file.to_string(),
+            config_hash: format!("{:x}", Sha256::digest(&config)),
+            seed: seed as i64,
+            started_at,
+        })
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt;
+
 #[derive(Debug, Clone)]
 pub enum VoteType {
     Valid(Option<String>),
@@ -22,6 +24,38 @@ impl From<&str> for VoteType {
     }
 }
 
+impl VoteType {
+    /// The vote of `kind`, i.e. `valid`, given for `reason`, an empty reason is no reason
+    pub fn with_reason(kind: &str, reason: Option<String>) -> Self {
+        let reason = reason.filter(|r| !r.is_empty());
+
+        match VoteType::from(kind) {
+            VoteType::Valid(_) => VoteType::Valid(reason),
+            VoteType::Invalid(_) => VoteType::Invalid(reason),
+            VoteType::Abstain(_) => VoteType::Abstain(reason),
+        }
+    }
+
+    /// The reason given for the vote, if any
+    pub fn reason(&self) -> Option<String> {
+        match self {
+            VoteType::Valid(reason) | VoteType::Invalid(reason) | VoteType::Abstain(reason) => {
+                reason.clone()
+            }
+        }
+    }
+}
+
+impl fmt::Display for VoteType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VoteType::Valid(_) => write!(f, "valid"),
+            VoteType::Invalid(_) => write!(f, "invalid"),
+            VoteType::Abstain(_) => write!(f, "abstain"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct Vote {
     /// The id of the vote
@@ -35,15 +69,30 @@ pub struct Vote {
 
     /// The id of the response upon which the vote was cast
     pub response_id: String,
+
+    /// Name of the prompt that cast the vote
+    pub prompt_name: String,
+
+    /// Version of the prompt that cast the vote
+    pub prompt_version: String,
 }
 
 impl Vote {
-    pub fn new(id: String, vote_type: VoteType, debate_id: String, response_id: String) -> Self {
+    pub fn new(
+        id: String,
+        vote_type: VoteType,
+        debate_id: String,
+        response_id: String,
+        prompt_name: String,
+        prompt_version: String,
+    ) -> Self {
         Self {
             id,
             vote_type,
             debate_id,
             response_id,
+            prompt_name,
+            prompt_version,
         }
     }
 }
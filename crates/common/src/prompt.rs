@@ -0,0 +1,415 @@
+use crate::{
+    config::Config,
+    errors::{PulpError, SimulationError, TemplateError},
+    registry::{PromptRegistry, BUILTIN_VERSION},
+    reply::{parse_reply, repair_prompt},
+    template::Template,
+};
+use pulpcalc_external::{
+    cassette::cassette_key,
+    chatgpt::{ChatMessage, ChatRequest, ChatRequestBuilder, ChatResponse, ResponseFormat},
+    errors::LlmError,
+};
+use schemars::{schema_for, JsonSchema};
+use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
+
+/// Sampling parameters sent with a prompt
+#[derive(Debug, Clone, PartialEq)]
+pub struct SamplingParams {
+    pub model: String,
+
+    pub max_tokens: i64,
+
+    pub temperature: f64,
+
+    pub top_p: f64,
+
+    pub frequency_penalty: f64,
+
+    pub presence_penalty: f64,
+}
+
+impl SamplingParams {
+    pub fn max_tokens(mut self, max_tokens: i64) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    pub fn temperature(mut self, temperature: f64) -> Self {
+        self.temperature = temperature;
+        self
+    }
+}
+
+impl Default for SamplingParams {
+    fn default() -> Self {
+        Self {
+            model: String::from("gpt-3.5-turbo"),
+            max_tokens: 800,
+            temperature: 0.7,
+            top_p: 1.0,
+            frequency_penalty: 0.0,
+            presence_penalty: 0.0,
+        }
+    }
+}
+
+/// A prompt template whose reply is parsed into `R`, declaring a new prompt is
+/// a `Prompt::new` with its name and template plus a `var` for each `{{placeholder}}`
+#[derive(Debug)]
+pub struct Prompt<R> {
+    /// Name of the prompt family, used when reporting
+    pub name: &'static str,
+
+    /// Revision of the template, `builtin` unless it was swapped in from the registry
+    pub version: String,
+
+    /// The parsed template, or why it failed to parse, which rendering reports
+    pub template: Result<Template, TemplateError>,
+
+    pub sampling: SamplingParams,
+
+    /// Placeholders of the template and the values bound to them
+    pub vars: Vec<(String, String)>,
+
+    /// System message sent ahead of the conversation, if any
+    pub system: Option<String>,
+
+    /// Earlier turns of the conversation, sent before the rendered template
+    pub history: Vec<ChatMessage>,
+
+    response: PhantomData<fn() -> R>,
+}
+
+impl<R> Clone for Prompt<R> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name,
+            version: self.version.clone(),
+            template: self.template.clone(),
+            sampling: self.sampling.clone(),
+            vars: self.vars.clone(),
+            system: self.system.clone(),
+            history: self.history.clone(),
+            response: PhantomData,
+        }
+    }
+}
+
+impl<R: DeserializeOwned + JsonSchema> Prompt<R> {
+    /// Builds a prompt from a built in template, a malformed template is returned as an error by `render`
+    /// and `send`
+    pub fn new(name: &'static str, template: &str) -> Self {
+        Self {
+            name,
+            version: BUILTIN_VERSION.to_string(),
+            template: Template::parse(template),
+            sampling: SamplingParams::default(),
+            vars: Vec::new(),
+            system: None,
+            history: Vec::new(),
+            response: PhantomData,
+        }
+    }
+
+    /// Builds a prompt from a template loaded at runtime, failing if the template is malformed
+    pub fn try_new(name: &'static str, template: &str) -> Result<Self, TemplateError> {
+        let prompt = Self::new(name, template);
+        prompt.template.clone()?;
+
+        Ok(prompt)
+    }
+
+    pub fn sampling(mut self, sampling: SamplingParams) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    pub fn system(mut self, system: String) -> Self {
+        self.system = Some(system);
+        self
+    }
+
+    pub fn history(mut self, history: Vec<ChatMessage>) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// Binds `value` to the `key` placeholder of the template
+    pub fn var<T: ToString>(mut self, key: &str, value: T) -> Self {
+        let value = value.to_string();
+
+        match self.vars.iter_mut().find(|(k, _)| k == key) {
+            Some((_, v)) => *v = value,
+            None => self.vars.push((key.to_string(), value)),
+        }
+
+        self
+    }
+
+    /// Swaps in the template registered under the name of this prompt, if any
+    pub fn resolve(mut self, registry: &PromptRegistry) -> Self {
+        if let Some(registered) = registry.get(self.name) {
+            self.version = registered.version.clone();
+            self.template = Ok(registered.template.clone());
+        }
+
+        self
+    }
+
+    /// The template with every placeholder filled
+    pub fn render(&self) -> Result<String, PulpError> {
+        let template = self.template.as_ref().map_err(|e| e.clone());
+
+        template.and_then(|t| t.render(&self.vars)).map_err(|e| {
+            PulpError::SimulationError(SimulationError::TemplateError(self.name.to_string(), e))
+        })
+    }
+
+    /// The system message, history and rendered prompt, constrained to replies matching the JSON schema of `R`
+    pub fn request(&self) -> Result<ChatRequest, PulpError> {
+        let schema = serde_json::to_value(schema_for!(R)).unwrap_or_default();
+
+        let mut builder = ChatRequestBuilder::new();
+        if let Some(system) = &self.system {
+            builder = builder.system(system.clone());
+        }
+
+        Ok(builder
+            .history(self.history.clone())
+            .user(self.render()?)
+            .model(self.sampling.model.clone())
+            .temperature(self.sampling.temperature)
+            .max_tokens(self.sampling.max_tokens)
+            .top_p(self.sampling.top_p)
+            .presence_penalty(self.sampling.presence_penalty)
+            .frequency_penalty(self.sampling.frequency_penalty)
+            .response_format(ResponseFormat::json_schema(self.name, schema))
+            .build())
+    }
+
+    /// Sends the prompt, as overridden by the prompt registry, and parses the reply into `R`
+    ///
+    /// A reply that does not parse, even after extracting its JSON object, is sent back
+    /// to the model with the parse error up to `llm_max_repairs` times
+    pub async fn send(&self, config: &Config) -> Result<R, PulpError> {
+        let prompt = self.clone().resolve(&config.prompts);
+        let stats = &config.llm_reply_stats;
+
+        let mut request = prompt.request()?;
+        if !config.llm_json_schema {
+            request.response_format = None;
+        }
+
+        let mut res = prompt.send_request(config, &request).await?;
+        let mut repairs = 0;
+
+        loop {
+            let e = match parse_reply::<R>(res.content(), stats) {
+                Ok(reply) => {
+                    if repairs > 0 {
+                        stats.record_repaired();
+                    }
+
+                    return Ok(reply);
+                }
+
+                Err(e) => e,
+            };
+
+            if repairs >= config.llm_max_repairs {
+                stats.record_failed();
+                println!(
+                    "failed to unmarshal {} ({}) reply: {:?}: {}",
+                    prompt.name,
+                    prompt.version,
+                    e,
+                    res.content()
+                );
+
+                return Err(PulpError::SimulationError(SimulationError::LLMError(
+                    LlmError::Decode(e.to_string()),
+                )));
+            }
+
+            repairs += 1;
+            stats.record_repair();
+
+            request
+                .messages
+                .push(ChatMessage::assistant(res.content().to_string()));
+            request.messages.push(ChatMessage::user(repair_prompt(&e)));
+
+            res = prompt.send_request(config, &request).await?;
+        }
+    }
+
+    /// Sends one request within the budget, unless the reply is cached, recording the usage reported with the reply
+    async fn send_request(
+        &self,
+        config: &Config,
+        request: &ChatRequest,
+    ) -> Result<ChatResponse, PulpError> {
+        config.llm_usage.check_budget(&config.llm_budget)?;
+
+        let cache = config
+            .llm_cache
+            .as_ref()
+            .filter(|_| !config.llm_cache_skip.iter().any(|name| name == self.name));
+        let key = cassette_key(request);
+
+        if let Some(res) = cache.and_then(|cache| cache.get(&key)) {
+            config
+                .llm_usage
+                .record_cached(config.debate_id.as_deref(), self.name, &request.model);
+
+            return Ok(res);
+        }
+
+        let res = request.send(config.llm.as_ref()).await?;
+        if let Some(cache) = cache {
+            cache.put(&key, &res);
+        }
+
+        let model = if res.model.is_empty() {
+            &request.model
+        } else {
+            &res.model
+        };
+        config.llm_usage.record(
+            config.debate_id.as_deref(),
+            self.name,
+            model,
+            &res.usage,
+            config.llm_prices.cost(model, &res.usage),
+        );
+
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::gpt_scoring::GrammarResponse;
+    use async_trait::async_trait;
+    use pulpcalc_external::{
+        chatgpt::{ChatChoice, ChatUsage},
+        provider::ChatProvider,
+    };
+    use std::sync::{Arc, Mutex};
+
+    /// Replies with `replies` in order, then with the last one, keeping every request it got
+    struct Scripted {
+        replies: Vec<&'static str>,
+
+        requests: Mutex<Vec<ChatRequest>>,
+    }
+
+    impl Scripted {
+        fn new(replies: &[&'static str]) -> Arc<Self> {
+            Arc::new(Self {
+                replies: replies.to_vec(),
+                requests: Mutex::new(Vec::new()),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl ChatProvider for Scripted {
+        fn name(&self) -> &str {
+            "scripted"
+        }
+
+        async fn send(&self, request: &ChatRequest) -> Result<ChatResponse, LlmError> {
+            let mut requests = self.requests.lock().unwrap();
+            requests.push(request.clone());
+
+            let content = self.replies[(requests.len() - 1).min(self.replies.len() - 1)];
+
+            Ok(ChatResponse {
+                choices: vec![ChatChoice {
+                    index: 0,
+                    message: ChatMessage::assistant(content.to_string()),
+                    finish_reason: Some(String::from("stop")),
+                }],
+                usage: ChatUsage::default(),
+                ..Default::default()
+            })
+        }
+    }
+
+    async fn scripted_config(provider: Arc<Scripted>, max_repairs: u32) -> Config {
+        let mut config = Config::default().await;
+        config.llm = provider;
+        config.llm_cache = None;
+        config.llm_max_repairs = max_repairs;
+
+        config
+    }
+
+    fn grammar() -> Prompt<GrammarResponse> {
+        Prompt::new("grammar", "Check {{content}}").var("content", "text")
+    }
+
+    #[tokio::test]
+    async fn an_unparsable_reply_is_repaired() {
+        let provider = Scripted::new(&["not json", "{\"grammar\": 0.5}"]);
+        let config = scripted_config(provider.clone(), 2).await;
+
+        let reply = grammar().send(&config).await.unwrap();
+
+        assert_eq!(reply.grammar, 0.5);
+        assert_eq!(config.llm_reply_stats.repairs(), 1);
+        assert_eq!(config.llm_reply_stats.repaired(), 1);
+        assert_eq!(config.llm_reply_stats.failed(), 0);
+
+        // The repair re-prompt carries the unparsable reply and the parse error
+        let requests = provider.requests.lock().unwrap();
+        let repair = &requests[1].messages;
+        assert_eq!(repair.len(), requests[0].messages.len() + 2);
+        assert_eq!(repair[repair.len() - 2].content, "not json");
+        assert!(repair[repair.len() - 1]
+            .content
+            .starts_with("Your previous answer could not be parsed"));
+    }
+
+    #[tokio::test]
+    async fn repairs_stop_at_llm_max_repairs() {
+        let provider = Scripted::new(&["not json"]);
+        let config = scripted_config(provider.clone(), 2).await;
+
+        let e = grammar().send(&config).await.unwrap_err();
+
+        assert!(matches!(
+            e,
+            PulpError::SimulationError(SimulationError::LLMError(LlmError::Decode(_)))
+        ));
+        assert_eq!(provider.requests.lock().unwrap().len(), 3);
+        assert_eq!(config.llm_reply_stats.repairs(), 2);
+        assert_eq!(config.llm_reply_stats.repaired(), 0);
+        assert_eq!(config.llm_reply_stats.failed(), 1);
+
+        // No repairs allowed sends the prompt once
+        let provider = Scripted::new(&["not json"]);
+        let config = scripted_config(provider.clone(), 0).await;
+
+        assert!(grammar().send(&config).await.is_err());
+        assert_eq!(provider.requests.lock().unwrap().len(), 1);
+        assert_eq!(config.llm_reply_stats.repairs(), 0);
+    }
+
+    #[test]
+    fn a_malformed_builtin_template_fails_to_render() {
+        let prompt: Prompt<GrammarResponse> =
+            Prompt::new("grammar", "Check {{content").var("content", "text");
+
+        assert!(matches!(
+            prompt.render(),
+            Err(PulpError::SimulationError(SimulationError::TemplateError(name, TemplateError::Unclosed(6))))
+                if name == "grammar"
+        ));
+        assert!(Prompt::<GrammarResponse>::try_new("grammar", "Check {{content").is_err());
+    }
+}
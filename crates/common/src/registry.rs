@@ -0,0 +1,351 @@
+use crate::{
+    errors::{PulpError, SimulationError},
+    template::Template,
+};
+use serde::Deserialize;
+use std::{collections::HashMap, fs, path::Path};
+
+/// Version reported for prompts that are not overridden by the registry
+pub const BUILTIN_VERSION: &str = "builtin";
+
+/// Every built in prompt with the placeholders its template uses, so a template loaded at runtime can
+/// be checked against the prompt it replaces before a simulation starts
+pub const BUILTIN_PROMPTS: &[(&str, &[&str])] = &[
+    ("relevance", &["topic", "content"]),
+    ("soundness", &["topic", "content"]),
+    ("grammar", &["content"]),
+    ("mastery_vocab", &["topic", "content"]),
+    ("persona_content", &PERSONA_CONTENT),
+    ("persona_content_with_reference", &PERSONA_CONTENT),
+    (
+        "persona_content_with_supported_references",
+        &PERSONA_CONTENT_WITH_REFERENCES,
+    ),
+    (
+        "persona_content_with_unsupported_references",
+        &PERSONA_CONTENT_WITH_REFERENCES,
+    ),
+    ("vote", &PERSONA_CONTENT),
+    (
+        "learned",
+        &[
+            "content",
+            "response",
+            "political_orientation",
+            "enneagram_type",
+            "gender",
+            "age",
+            "core_fear",
+            "core_desire",
+            "valid_vote_tendency",
+            "invalid_vote_tendency",
+            "abstain_vote_tendency",
+            "report_tendency",
+            "hide_tendency",
+        ],
+    ),
+    ("enneagram_tendency", &["topic"]),
+    (
+        "enneagram_response_content",
+        &[
+            "valid_vote_tendency",
+            "invalid_vote_tendency",
+            "abstain_vote_tendency",
+            "report_tendency",
+            "hide_tendency",
+            "topic",
+        ],
+    ),
+    (
+        "enneagram_reply_content",
+        &[
+            "valid_vote_tendency",
+            "invalid_vote_tendency",
+            "abstain_vote_tendency",
+            "report_tendency",
+            "hide_tendency",
+            "content",
+        ],
+    ),
+    ("generate_blog", &["topic"]),
+    ("nahco", &["topic"]),
+    ("persona_content_with_nahco_reference", &["topic"]),
+];
+
+const PERSONA_CONTENT: [&str; 7] = [
+    "content",
+    "political_orientation",
+    "enneagram_type",
+    "gender",
+    "age",
+    "core_fear",
+    "core_desire",
+];
+
+const PERSONA_CONTENT_WITH_REFERENCES: [&str; 8] = [
+    "content",
+    "political_orientation",
+    "enneagram_type",
+    "gender",
+    "age",
+    "core_fear",
+    "core_desire",
+    "supported_references",
+];
+
+/// The placeholders of the built in prompt called `name`, `None` when there is no such prompt
+pub fn builtin_variables(name: &str) -> Option<&'static [&'static str]> {
+    BUILTIN_PROMPTS
+        .iter()
+        .find(|(builtin, _)| *builtin == name)
+        .map(|(_, variables)| *variables)
+}
+
+/// Checks that `template` can replace the built in prompt called `name`, it has to use exactly the
+/// placeholders the prompt binds
+pub fn check_template(name: &str, template: &Template) -> Result<(), String> {
+    let builtin = match builtin_variables(name) {
+        Some(builtin) => builtin,
+
+        None => {
+            let names: Vec<&str> = BUILTIN_PROMPTS.iter().map(|(name, _)| *name).collect();
+
+            return Err(format!(
+                "unknown prompt {}, expected one of {}",
+                name,
+                names.join(", ")
+            ));
+        }
+    };
+
+    let variables = template.variables();
+    let missing: Vec<String> = builtin
+        .iter()
+        .filter(|variable| !variables.contains(variable))
+        .map(|variable| format!("{{{{{}}}}}", variable))
+        .collect();
+    let unknown: Vec<String> = variables
+        .iter()
+        .filter(|variable| !builtin.contains(variable))
+        .map(|variable| format!("{{{{{}}}}}", variable))
+        .collect();
+
+    let mut problems = Vec::new();
+    if !missing.is_empty() {
+        problems.push(format!("is missing {}", missing.join(", ")));
+    }
+    if !unknown.is_empty() {
+        problems.push(format!("uses unknown {}", unknown.join(", ")));
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("prompt {} {}", name, problems.join(" and ")))
+    }
+}
+
+/// A prompt template loaded at runtime
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegisteredPrompt {
+    pub version: String,
+
+    pub template: Template,
+}
+
+#[derive(Debug, Deserialize)]
+struct PromptEntry {
+    version: String,
+
+    template: String,
+}
+
+/// Named, versioned prompt templates overriding the built in ones
+///
+/// Templates are read from a TOML file, or from every `.toml` file of a directory,
+/// with one table per prompt name:
+///
+/// ```toml
+/// [persona_content]
+/// version = "2023-06-01"
+/// template = """..."""
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PromptRegistry {
+    prompts: HashMap<String, RegisteredPrompt>,
+}
+
+impl PromptRegistry {
+    /// Loads the templates at `path`, which is either a TOML file or a directory of them
+    pub fn load(path: &str) -> Result<Self, PulpError> {
+        let mut registry = Self::default();
+
+        if Path::new(path).is_dir() {
+            let entries = fs::read_dir(path).map_err(|e| registry_error(path, e))?;
+
+            let mut files = Vec::new();
+            for entry in entries {
+                let file = entry.map_err(|e| registry_error(path, e))?.path();
+                if file.extension().map_or(false, |ext| ext == "toml") {
+                    files.push(file);
+                }
+            }
+            files.sort();
+
+            for file in files {
+                registry.load_file(&file.to_string_lossy())?;
+            }
+        } else {
+            registry.load_file(path)?;
+        }
+
+        Ok(registry)
+    }
+
+    fn load_file(&mut self, path: &str) -> Result<(), PulpError> {
+        let source = fs::read_to_string(path).map_err(|e| registry_error(path, e))?;
+        let entries: HashMap<String, PromptEntry> =
+            toml::from_str(&source).map_err(|e| registry_error(path, e))?;
+
+        for (name, entry) in entries {
+            if self.prompts.contains_key(&name) {
+                return Err(registry_error(
+                    path,
+                    format!("prompt {} is defined twice", name),
+                ));
+            }
+
+            let template = Template::parse(&entry.template).map_err(|e| {
+                PulpError::SimulationError(SimulationError::TemplateError(name.clone(), e))
+            })?;
+            check_template(&name, &template).map_err(|e| registry_error(path, e))?;
+
+            self.prompts.insert(
+                name,
+                RegisteredPrompt {
+                    version: entry.version,
+                    template,
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&RegisteredPrompt> {
+        self.prompts.get(name)
+    }
+
+    /// The version of the `name` prompt, `builtin` unless it was loaded from a file
+    pub fn version(&self, name: &str) -> String {
+        match self.prompts.get(name) {
+            Some(prompt) => prompt.version.clone(),
+            None => BUILTIN_VERSION.to_string(),
+        }
+    }
+
+    /// Names of the loaded prompts, sorted
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.prompts.keys().map(|name| name.as_str()).collect();
+        names.sort();
+
+        names
+    }
+}
+
+fn registry_error<E: ToString>(path: &str, e: E) -> PulpError {
+    PulpError::SimulationError(SimulationError::PromptRegistryError(format!(
+        "{}: {}",
+        path,
+        e.to_string()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::gpt_scoring::{
+        GRAMMAR_PROMPT, MASTERY_VOCAB_PROMPT, RELEVANCE_PROMPT, SOUNDNESS_PROMPT,
+    };
+    use std::env;
+
+    /// Writes `source` to a registry file unique to `test` and loads it
+    fn load(test: &str, source: &str) -> Result<PromptRegistry, PulpError> {
+        let path = env::temp_dir().join(format!(
+            "pulpcalc-registry-{}-{}.toml",
+            test,
+            std::process::id()
+        ));
+        fs::write(&path, source).unwrap();
+
+        let registry = PromptRegistry::load(&path.to_string_lossy());
+        fs::remove_file(&path).unwrap();
+
+        registry
+    }
+
+    fn registry_message(e: PulpError) -> String {
+        match e {
+            PulpError::SimulationError(SimulationError::PromptRegistryError(message)) => message,
+            e => panic!("expected a prompt registry error, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn loads_a_template_with_the_builtin_placeholders() {
+        let registry = load(
+            "valid",
+            "[grammar]\nversion = \"v2\"\ntemplate = \"Check {{content}}\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(registry.names(), vec!["grammar"]);
+        assert_eq!(registry.version("grammar"), "v2");
+        assert_eq!(registry.version("relevance"), BUILTIN_VERSION);
+    }
+
+    #[test]
+    fn rejects_an_unknown_prompt_name() {
+        let e = load(
+            "unknown",
+            "[gramar]\nversion = \"v2\"\ntemplate = \"Check {{content}}\"\n",
+        )
+        .unwrap_err();
+
+        assert!(registry_message(e).contains("unknown prompt gramar"));
+    }
+
+    #[test]
+    fn rejects_a_template_missing_a_placeholder() {
+        let e = load(
+            "missing",
+            "[relevance]\nversion = \"v2\"\ntemplate = \"Rate {{content}}\"\n",
+        )
+        .unwrap_err();
+
+        assert!(registry_message(e).ends_with("prompt relevance is missing {{topic}}"));
+    }
+
+    #[test]
+    fn rejects_a_template_with_an_unknown_placeholder() {
+        let e = load(
+            "extra",
+            "[grammar]\nversion = \"v2\"\ntemplate = \"Check {{content}} by {{author}}\"\n",
+        )
+        .unwrap_err();
+
+        assert!(registry_message(e).ends_with("prompt grammar uses unknown {{author}}"));
+    }
+
+    #[test]
+    fn scoring_prompts_match_the_builtin_placeholders() {
+        for (name, template) in [
+            ("relevance", RELEVANCE_PROMPT),
+            ("soundness", SOUNDNESS_PROMPT),
+            ("grammar", GRAMMAR_PROMPT),
+            ("mastery_vocab", MASTERY_VOCAB_PROMPT),
+        ] {
+            check_template(name, &Template::parse(template).unwrap()).unwrap();
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use serde::de::DeserializeOwned;
+use serde_json::from_str;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Counters of the fallbacks used to parse LLM replies
+#[derive(Debug, Default)]
+pub struct ReplyStats {
+    extracted: AtomicU64,
+
+    repairs: AtomicU64,
+
+    repaired: AtomicU64,
+
+    failed: AtomicU64,
+}
+
+impl ReplyStats {
+    /// Number of replies parsed only after extracting the JSON object from the text around it
+    pub fn extracted(&self) -> u64 {
+        self.extracted.load(Ordering::Relaxed)
+    }
+
+    /// Number of repair re-prompts sent
+    pub fn repairs(&self) -> u64 {
+        self.repairs.load(Ordering::Relaxed)
+    }
+
+    /// Number of replies parsed after one or more repair re-prompts
+    pub fn repaired(&self) -> u64 {
+        self.repaired.load(Ordering::Relaxed)
+    }
+
+    /// Number of replies that could not be parsed at all
+    pub fn failed(&self) -> u64 {
+        self.failed.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn record_repair(&self) {
+        self.repairs.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_repaired(&self) {
+        self.repaired.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_failed(&self) {
+        self.failed.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Parses a reply as `R`, falling back to the first JSON object found in it
+pub fn parse_reply<R: DeserializeOwned>(
+    content: &str,
+    stats: &ReplyStats,
+) -> Result<R, serde_json::Error> {
+    let err = match from_str::<R>(content) {
+        Ok(reply) => return Ok(reply),
+        Err(e) => e,
+    };
+
+    match extract_json(content).map(|json| from_str::<R>(&json)) {
+        Some(Ok(reply)) => {
+            stats.extracted.fetch_add(1, Ordering::Relaxed);
+
+            Ok(reply)
+        }
+
+        Some(Err(e)) => Err(e),
+
+        None => Err(err),
+    }
+}
+
+/// The first balanced JSON object in `text`, without the markdown fences or prose
+/// around it, the `#` comments copied from the prompt schemas, or trailing commas
+pub fn extract_json(text: &str) -> Option<String> {
+    let start = text.find('{')?;
+
+    let mut json = String::new();
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut comment = false;
+
+    for c in text[start..].chars() {
+        if comment {
+            if c == '\n' {
+                comment = false;
+                json.push(c);
+            }
+
+            continue;
+        }
+
+        if in_string {
+            json.push(c);
+
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+
+            continue;
+        }
+
+        match c {
+            '"' => {
+                in_string = true;
+                json.push(c);
+            }
+
+            '#' => comment = true,
+
+            '{' | '[' => {
+                depth += 1;
+                json.push(c);
+            }
+
+            '}' | ']' => {
+                let trimmed = json.trim_end().len();
+                if json[..trimmed].ends_with(',') {
+                    json.truncate(trimmed - 1);
+                }
+
+                depth -= 1;
+                json.push(c);
+
+                if depth == 0 {
+                    return Some(json);
+                }
+            }
+
+            _ => json.push(c),
+        }
+    }
+
+    None
+}
+
+/// Follow up prompt asking the model to fix a reply that could not be parsed
+pub fn repair_prompt(error: &serde_json::Error) -> String {
+    format!(
+        "Your previous answer could not be parsed: {}. Return only the corrected JSON object using the schema from the first message, with no markdown, comments or other text.",
+        error
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Reply {
+        grammar: f64,
+
+        reason: String,
+    }
+
+    fn reply(reason: &str) -> Reply {
+        Reply {
+            grammar: 0.5,
+            reason: reason.to_string(),
+        }
+    }
+
+    #[test]
+    fn a_json_reply_parses_without_extracting() {
+        let stats = ReplyStats::default();
+
+        let parsed = parse_reply::<Reply>(r#"{"grammar": 0.5, "reason": "fine"}"#, &stats);
+
+        assert_eq!(parsed.unwrap(), reply("fine"));
+        assert_eq!(stats.extracted(), 0);
+    }
+
+    #[test]
+    fn json_is_extracted_from_a_markdown_fence() {
+        let stats = ReplyStats::default();
+        let fenced = "Here you go:\n```json\n{\"grammar\": 0.5, \"reason\": \"fine\"}\n```\n";
+
+        assert_eq!(parse_reply::<Reply>(fenced, &stats).unwrap(), reply("fine"));
+        assert_eq!(stats.extracted(), 1);
+    }
+
+    #[test]
+    fn json_is_extracted_from_before_trailing_prose() {
+        let text = r#"{"grammar": 0.5, "reason": "a {nested} brace"} I hope this helps {really}"#;
+
+        assert_eq!(
+            extract_json(text).unwrap(),
+            r#"{"grammar": 0.5, "reason": "a {nested} brace"}"#
+        );
+    }
+
+    #[test]
+    fn comments_and_trailing_commas_are_stripped_outside_strings() {
+        let text = r#"{
+    "grammar": 0.5, # from 0 to 1
+    "reason": "issue #4, \"see\" #5,",
+    "words": ["a", "b",],
+}"#;
+
+        let json = extract_json(text).unwrap();
+        let value: serde_json::Value = from_str(&json).unwrap();
+
+        assert_eq!(value["grammar"], 0.5);
+        assert_eq!(value["reason"], "issue #4, \"see\" #5,");
+        assert_eq!(value["words"], serde_json::json!(["a", "b"]));
+    }
+
+    #[test]
+    fn a_reply_without_an_object_fails_with_the_original_error() {
+        let stats = ReplyStats::default();
+
+        assert!(extract_json("no json here }").is_none());
+        assert!(extract_json("{\"grammar\": 0.5").is_none());
+
+        let e = parse_reply::<Reply>("no json here", &stats).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            from_str::<Reply>("no json here").unwrap_err().to_string()
+        );
+        assert_eq!(stats.extracted(), 0);
+    }
+}
@@ -0,0 +1,785 @@
+use crate::{
+    errors::{PulpError, SimulationError},
+    models::{Attributes, Engagements, Response, Score},
+    simulation::Action,
+    store::{Edge, Store},
+};
+use serde::Deserialize;
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+};
+
+/// Id of the policy responses are scored under when no policy file is given
+pub const DEFAULT_POLICY_ID: &str = "default";
+
+/// The weights, caps and decay responses are scored with, so alternative reward rules can be tried
+/// without code changes. The defaults are the built in formula
+///
+/// ```toml
+/// id = "reward-depth"
+///
+/// [content]
+/// relevance_weight = 2.0
+/// max_mastery_words = 10
+///
+/// [engagement]
+/// response = 300
+/// reply_half_life_ms = 30000
+/// max = 5000
+///
+/// [combine]
+/// engagement = 0.5
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScoringPolicy {
+    /// Recorded on every response scored under the policy
+    pub id: String,
+
+    #[serde(default)]
+    pub content: ContentPolicy,
+
+    #[serde(default)]
+    pub engagement: EngagementPolicy,
+
+    #[serde(default)]
+    pub combine: CombinePolicy,
+}
+
+/// Points of the content of a response, `(mastery words + invalid votes) * (relevance + soundness + grammar)`
+/// with every term weighted, plus the points of the metrics computed locally
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ContentPolicy {
+    pub mastery_weight: f64,
+
+    pub invalid_vote_weight: f64,
+
+    pub relevance_weight: f64,
+
+    pub soundness_weight: f64,
+
+    pub grammar_weight: f64,
+
+    /// Mastery vocabulary words counted at most
+    pub max_mastery_words: Option<i64>,
+
+    /// Points of every URL or citation
+    pub reference_points: i64,
+
+    /// Points of every number or statistic mentioned
+    pub statistic_points: i64,
+
+    /// Points of content using every word once, scaled down with its lexical diversity
+    pub diversity_points: i64,
+
+    /// Points of content at `target_grade`, falling to none `grade_range` grade levels away
+    pub readability_points: i64,
+
+    /// Flesch-Kincaid grade level content reads best at
+    pub target_grade: f64,
+
+    pub grade_range: f64,
+
+    /// Points taken off for every misspelled word
+    pub misspelling_penalty: i64,
+
+    /// Content points counted at most
+    pub max: Option<i64>,
+}
+
+impl Default for ContentPolicy {
+    fn default() -> Self {
+        Self {
+            mastery_weight: 1.0,
+            invalid_vote_weight: 1.0,
+            relevance_weight: 1.0,
+            soundness_weight: 1.0,
+            grammar_weight: 1.0,
+            max_mastery_words: None,
+            reference_points: 20,
+            statistic_points: 10,
+            diversity_points: 20,
+            readability_points: 20,
+            target_grade: 10.0,
+            grade_range: 8.0,
+            misspelling_penalty: 5,
+            max: None,
+        }
+    }
+}
+
+/// Points of every engagement with a response
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EngagementPolicy {
+    pub valid_vote_with_content: i64,
+
+    pub invalid_vote_with_content: i64,
+
+    pub valid_vote: i64,
+
+    pub invalid_vote: i64,
+
+    pub abstain_vote: i64,
+
+    /// Points of a direct reply, replies `depth` levels down earn `response / depth^reply_depth_exponent`
+    pub response: i64,
+
+    pub report: i64,
+
+    pub hide: i64,
+
+    pub reply_depth_exponent: f64,
+
+    /// Reply latency, in milliseconds, at which a direct reply earns half the timing points of an instant one
+    pub reply_half_life_ms: i64,
+
+    /// Engagement points counted at most, the stored breakdown keeps them all
+    pub max: Option<i64>,
+}
+
+impl Default for EngagementPolicy {
+    fn default() -> Self {
+        Self {
+            valid_vote_with_content: Action::ValidVoteWithContent.base_point_value() as i64,
+            invalid_vote_with_content: Action::InvalidVoteWithContent.base_point_value() as i64,
+            valid_vote: Action::ValidVote.base_point_value() as i64,
+            invalid_vote: Action::InvalidVote.base_point_value() as i64,
+            abstain_vote: Action::AbstainVote.base_point_value() as i64,
+            response: Action::Response.base_point_value() as i64,
+            report: Action::Report.base_point_value() as i64,
+            hide: Action::Hide.base_point_value() as i64,
+            reply_depth_exponent: 1.0,
+            reply_half_life_ms: 60_000,
+            max: None,
+        }
+    }
+}
+
+/// Weights the content and engagement points are added up with
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CombinePolicy {
+    pub content: f64,
+
+    pub engagement: f64,
+}
+
+impl Default for CombinePolicy {
+    fn default() -> Self {
+        Self {
+            content: 1.0,
+            engagement: 1.0,
+        }
+    }
+}
+
+impl Default for ScoringPolicy {
+    fn default() -> Self {
+        Self {
+            id: DEFAULT_POLICY_ID.to_string(),
+            content: ContentPolicy::default(),
+            engagement: EngagementPolicy::default(),
+            combine: CombinePolicy::default(),
+        }
+    }
+}
+
+impl ScoringPolicy {
+    /// Loads a policy from a TOML file, sections and keys left out keep their defaults
+    pub fn load(path: &str) -> Result<Self, PulpError> {
+        let source = fs::read_to_string(path).map_err(|e| policy_error(path, e))?;
+        let policy: Self = toml::from_str(&source).map_err(|e| policy_error(path, e))?;
+
+        policy.validate().map_err(|e| policy_error(path, e))?;
+
+        Ok(policy)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.id.trim().is_empty() {
+            return Err("id can't be empty".to_string());
+        }
+
+        for (name, weight) in [
+            ("content.mastery_weight", self.content.mastery_weight),
+            (
+                "content.invalid_vote_weight",
+                self.content.invalid_vote_weight,
+            ),
+            ("content.relevance_weight", self.content.relevance_weight),
+            ("content.soundness_weight", self.content.soundness_weight),
+            ("content.grammar_weight", self.content.grammar_weight),
+            ("content.target_grade", self.content.target_grade),
+            (
+                "engagement.reply_depth_exponent",
+                self.engagement.reply_depth_exponent,
+            ),
+            ("combine.content", self.combine.content),
+            ("combine.engagement", self.combine.engagement),
+        ] {
+            if !weight.is_finite() || weight < 0.0 {
+                return Err(format!("{} should be a positive number", name));
+            }
+        }
+
+        if !self.content.grade_range.is_finite() || self.content.grade_range <= 0.0 {
+            return Err("content.grade_range should be above 0".to_string());
+        }
+
+        if self.engagement.reply_half_life_ms <= 0 {
+            return Err("engagement.reply_half_life_ms should be above 0".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Points of a single `action`
+    pub fn points(&self, action: &Action) -> i64 {
+        let engagement = &self.engagement;
+
+        match action {
+            Action::ValidVoteWithContent => engagement.valid_vote_with_content,
+            Action::InvalidVoteWithContent => engagement.invalid_vote_with_content,
+            Action::ValidVote => engagement.valid_vote,
+            Action::InvalidVote => engagement.invalid_vote,
+            Action::AbstainVote => engagement.abstain_vote,
+            Action::Response => engagement.response,
+            Action::Report => engagement.report,
+            Action::Hide => engagement.hide,
+        }
+    }
+
+    /// Points of a reply `depth` levels below the response, 1 for a direct reply
+    pub fn reply_points(&self, depth: u32) -> i64 {
+        let decay = (depth.max(1) as f64).powf(self.engagement.reply_depth_exponent);
+
+        (self.points(&Action::Response) as f64 / decay) as i64
+    }
+
+    /// Timing points of a direct reply that came `latency` milliseconds after the response
+    pub fn timing_points(&self, latency: i64) -> i64 {
+        let half_life = self.engagement.reply_half_life_ms;
+
+        self.points(&Action::Response) * half_life / (half_life + latency.max(0))
+    }
+
+    /// Weighted content points of a response with `attributes` that got `invalid_votes`
+    pub fn content_score(&self, attributes: &Attributes, invalid_votes: i64) -> i64 {
+        let content = &self.content;
+
+        let mastery_words = match content.max_mastery_words {
+            Some(max) => attributes.mastery_words().min(max),
+            None => attributes.mastery_words(),
+        };
+
+        let words = content.mastery_weight * mastery_words as f64
+            + content.invalid_vote_weight * invalid_votes as f64;
+        let quality = (content.relevance_weight * attributes.relevance
+            + content.soundness_weight * attributes.soundness
+            + content.grammar_weight * attributes.grammar)
+            .trunc();
+
+        let points = (words * quality) as i64 + self.metric_points(attributes);
+        let points = cap(points.max(0), content.max);
+
+        (self.combine.content * points as f64) as i64
+    }
+
+    /// Points of the metrics computed locally, no LLM involved
+    pub fn metric_points(&self, attributes: &Attributes) -> i64 {
+        let content = &self.content;
+
+        // Responses scored before the metrics were computed have no words counted
+        if attributes.word_count == 0 {
+            return 0;
+        }
+
+        let off_target = (attributes.readability - content.target_grade).abs();
+        let readability = (1.0 - off_target / content.grade_range).max(0.0);
+
+        content.reference_points * attributes.references
+            + content.statistic_points * attributes.statistics
+            + (content.diversity_points as f64 * attributes.lexical_diversity) as i64
+            + (content.readability_points as f64 * readability) as i64
+            - content.misspelling_penalty * attributes.spelling_errors
+    }
+
+    /// Weighted engagement points of a response with the `engagements` breakdown
+    pub fn engagement_score(&self, engagements: &Engagements) -> i64 {
+        let points = cap(engagements.total(), self.engagement.max);
+
+        (self.combine.engagement * points as f64) as i64
+    }
+
+    /// Engagement breakdown of the stored `response` from the votes, reports and hides it got and
+    /// the replies below it
+    pub async fn engagements(
+        &self,
+        store: &dyn Store,
+        response: &Response,
+    ) -> Result<Engagements, PulpError> {
+        // Reports aren't recorded by reason, so every one counts as harmful to others
+        let mut engagements = Engagements {
+            report_harmful_to_others: response.report_count * self.points(&Action::Report),
+            hide: response.hide_count * self.points(&Action::Hide),
+            ..Default::default()
+        };
+
+        for vote in store.votes(&response.id).await? {
+            let action = Action::from(&vote.vote_type);
+            let points = self.points(&action);
+
+            match action {
+                Action::ValidVoteWithContent | Action::InvalidVoteWithContent => {
+                    engagements.vote_condfidence += points
+                }
+
+                _ => engagements.vote_validity += points,
+            }
+        }
+
+        let mut depth = 1;
+        let mut thread = vec![response.id.clone()];
+        while !thread.is_empty() {
+            let mut next = Vec::new();
+            for id in thread {
+                for reply in store.replies(&id).await? {
+                    engagements.response_distance += self.reply_points(depth);
+
+                    // Responses stored before they were timestamped have no latency
+                    if depth == 1 && response.created_at > 0 && reply.created_at > 0 {
+                        engagements.response_timing +=
+                            self.timing_points(reply.created_at - response.created_at);
+                    }
+
+                    next.push(reply.id);
+                }
+            }
+
+            thread = next;
+            depth += 1;
+        }
+
+        Ok(engagements)
+    }
+
+    /// Scores of every response and debate the run with `id` wrote, recomputed from the stored graph
+    /// alone, each with the score stored during the run. Content points use the stored attributes,
+    /// so no prompt is sent, and a debate scores the sum of the responses in its threads
+    pub async fn score_run(
+        &self,
+        store: &dyn Store,
+        id: &str,
+    ) -> Result<Vec<(Score, i64)>, PulpError> {
+        let mut scored = Vec::new();
+        let mut responses = HashMap::new();
+
+        for response_id in store.run_entities(id, "Response").await? {
+            let response = store.get_response(&response_id).await?;
+
+            let content = self.content_score(&response.attributes, response.invalid_vote_count);
+            let engagement = self.engagement_score(&self.engagements(store, &response).await?);
+
+            let score = Score {
+                entity_id: response.id.clone(),
+                label: "Response".to_string(),
+                content,
+                engagement,
+                score: content + engagement,
+            };
+
+            responses.insert(response.id.clone(), (score.clone(), response.score));
+            scored.push((score, response.score));
+        }
+
+        for debate_id in store.run_entities(id, "Debate").await? {
+            let mut debate = Score {
+                entity_id: debate_id.clone(),
+                label: "Debate".to_string(),
+                ..Default::default()
+            };
+            let mut stored = 0;
+
+            let mut seen = HashSet::new();
+            let mut thread = store.incoming(&debate_id, Edge::Response).await?;
+            while !thread.is_empty() {
+                let mut next = Vec::new();
+                for response_id in thread {
+                    if !seen.insert(response_id.clone()) {
+                        continue;
+                    }
+
+                    if let Some((score, stored_score)) = responses.get(&response_id) {
+                        debate.content += score.content;
+                        debate.engagement += score.engagement;
+                        debate.score += score.score;
+                        stored += stored_score;
+                    }
+
+                    next.extend(store.outgoing(&response_id, Edge::Replied).await?);
+                }
+
+                thread = next;
+            }
+
+            scored.push((debate, stored));
+        }
+
+        Ok(scored)
+    }
+}
+
+fn cap(points: i64, max: Option<i64>) -> i64 {
+    match max {
+        Some(max) => points.min(max),
+        None => points,
+    }
+}
+
+fn policy_error<E: ToString>(path: &str, e: E) -> PulpError {
+    PulpError::SimulationError(SimulationError::SimError(format!(
+        "scoring policy {}: {}",
+        path,
+        e.to_string()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::Config,
+        models::{vote::VoteType, Vote},
+        store::{MemoryStore, Relationship},
+    };
+    use std::{env, sync::Arc};
+
+    /// When the scored response was written, replies are timed from it
+    const CREATED_AT: i64 = 1_000_000;
+
+    /// The default policy with points for reports and hides, which are worth none by default
+    fn policy() -> ScoringPolicy {
+        let mut policy = ScoringPolicy::default();
+        policy.engagement.report = 7;
+        policy.engagement.hide = 3;
+
+        policy
+    }
+
+    async fn respond(store: &MemoryStore, created_at: i64) -> String {
+        store
+            .create_response(&Response {
+                created_at,
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+    }
+
+    async fn reply(store: &MemoryStore, to: &str, created_at: i64) -> String {
+        let id = respond(store, created_at).await;
+        store
+            .relate(Relationship::Replied {
+                response_id: to.to_string(),
+                reply_id: id.clone(),
+            })
+            .await
+            .unwrap();
+
+        id
+    }
+
+    async fn vote(store: &MemoryStore, on: &str, vote_type: VoteType) {
+        store
+            .create_vote(&Vote {
+                vote_type,
+                response_id: on.to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+
+    /// A response reported twice and hidden 4 times, with 4 votes, a vote on one of its replies, and
+    /// replies 1, 2 and 3 levels below it
+    async fn engaged(store: &MemoryStore) -> Response {
+        let id = store
+            .create_response(&Response {
+                created_at: CREATED_AT,
+                report_count: 2,
+                hide_count: 4,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        vote(store, &id, VoteType::Valid(Some("sourced".to_string()))).await;
+        vote(store, &id, VoteType::Invalid(None)).await;
+        vote(store, &id, VoteType::Valid(None)).await;
+        vote(store, &id, VoteType::Abstain(Some("unsure".to_string()))).await;
+
+        let late = reply(store, &id, CREATED_AT + 60_000).await;
+        reply(store, &id, CREATED_AT).await;
+        let below = reply(store, &late, CREATED_AT + 1).await;
+        reply(store, &below, CREATED_AT + 2).await;
+
+        vote(store, &late, VoteType::Valid(None)).await;
+
+        store.get_response(&id).await.unwrap()
+    }
+
+    /// Loads the policy TOML `source` through a file named after `name`
+    fn load(name: &str, source: &str) -> Result<ScoringPolicy, PulpError> {
+        let path = env::temp_dir().join(format!(
+            "pulpcalc-policy-{}-{}.toml",
+            name,
+            std::process::id()
+        ));
+        fs::write(&path, source).unwrap();
+
+        let policy = ScoringPolicy::load(path.to_str().unwrap());
+        fs::remove_file(&path).unwrap();
+
+        policy
+    }
+
+    /// Content of 100 words, 2 references, 3 statistics, half the words distinct, 4 grade levels above
+    /// the target and a misspelling
+    fn measured() -> Attributes {
+        Attributes {
+            word_count: 100,
+            references: 2,
+            statistics: 3,
+            lexical_diversity: 0.5,
+            readability: 14.0,
+            spelling_errors: 1,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn a_partial_policy_keeps_the_defaults_it_leaves_out() {
+        let policy = load(
+            "partial",
+            r#"
+id = "partial"
+
+[content]
+relevance_weight = 2.0
+
+[engagement]
+report = 7
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(policy.id, "partial");
+        assert_eq!(policy.content.relevance_weight, 2.0);
+        assert_eq!(policy.engagement.report, 7);
+
+        assert_eq!(
+            policy.content,
+            ContentPolicy {
+                relevance_weight: 2.0,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            policy.engagement,
+            EngagementPolicy {
+                report: 7,
+                ..Default::default()
+            }
+        );
+        assert_eq!(policy.combine, CombinePolicy::default());
+    }
+
+    #[test]
+    fn a_policy_with_an_unknown_key_is_rejected() {
+        let e = load(
+            "unknown",
+            "id = \"typo\"\n\n[content]\nrelevence_weight = 2.0\n",
+        )
+        .unwrap_err();
+
+        assert!(e.to_string().contains("relevence_weight"), "{}", e);
+    }
+
+    #[test]
+    fn a_policy_with_out_of_range_values_is_rejected() {
+        for (name, source, error) in [
+            (
+                "negative",
+                "id = \"negative\"\n\n[combine]\ncontent = -1.0\n",
+                "combine.content should be a positive number",
+            ),
+            (
+                "grade-range",
+                "id = \"grade-range\"\n\n[content]\ngrade_range = 0.0\n",
+                "content.grade_range should be above 0",
+            ),
+            (
+                "half-life",
+                "id = \"half-life\"\n\n[engagement]\nreply_half_life_ms = 0\n",
+                "engagement.reply_half_life_ms should be above 0",
+            ),
+            ("no-id", "id = \" \"\n", "id can't be empty"),
+        ] {
+            let e = load(name, source).unwrap_err();
+
+            assert!(e.to_string().contains(error), "{}: {}", name, e);
+        }
+    }
+
+    #[test]
+    fn content_is_scored_on_capped_mastery_words_and_weighted() {
+        let mut policy = policy();
+        policy.content.max_mastery_words = Some(2);
+
+        let attributes = Attributes {
+            relevance: 2.5,
+            soundness: 3.0,
+            grammar: 1.8,
+            mastery_vocab_words: ["ubiquitous", "esoteric", "pernicious", ""]
+                .map(String::from)
+                .to_vec(),
+            ..Default::default()
+        };
+
+        // (2 mastery words + 1 invalid vote) * trunc(2.5 + 3.0 + 1.8), no metrics without a word count
+        assert_eq!(policy.content_score(&attributes, 1), 21);
+
+        policy.content.max = Some(15);
+        policy.combine.content = 2.0;
+        assert_eq!(policy.content_score(&attributes, 1), 30);
+
+        policy.content.max = None;
+        let attributes = Attributes {
+            mastery_vocab_words: attributes.mastery_vocab_words,
+            ..measured()
+        };
+        assert_eq!(policy.content_score(&attributes, 0), 2 * 85);
+    }
+
+    #[test]
+    fn metrics_earn_points_without_an_llm() {
+        let policy = policy();
+
+        // 2 * 20 references + 3 * 10 statistics + 20 * 0.5 diversity + 20 * (1 - 4 / 8) readability - 5
+        assert_eq!(policy.metric_points(&measured()), 40 + 30 + 10 + 10 - 5);
+
+        // Content too far off the target grade reads for nothing
+        let unreadable = Attributes {
+            readability: 20.0,
+            ..measured()
+        };
+        assert_eq!(policy.metric_points(&unreadable), 40 + 30 + 10 - 5);
+
+        // Misspellings can take the points below 0, the content score stops at 0
+        let misspelt = Attributes {
+            word_count: 10,
+            spelling_errors: 4,
+            ..Default::default()
+        };
+        assert_eq!(policy.metric_points(&misspelt), -20);
+        assert_eq!(policy.content_score(&misspelt, 0), 0);
+
+        assert_eq!(policy.metric_points(&Attributes::default()), 0);
+    }
+
+    #[tokio::test]
+    async fn engagements_add_up_votes_reports_hides_and_replies() {
+        let store = MemoryStore::new();
+        let response = engaged(&store).await;
+
+        let engagements = policy().engagements(&store, &response).await.unwrap();
+
+        assert_eq!(engagements.report_harmful_to_others, 2 * 7);
+        assert_eq!(engagements.report_abuseof_platform, 0);
+        assert_eq!(engagements.hide, 4 * 3);
+
+        // Votes with a reason count towards confidence, the rest towards validity
+        assert_eq!(engagements.vote_condfidence, 100);
+        assert_eq!(engagements.vote_validity, 50 + 50);
+
+        // Two direct replies, then one 2 and one 3 levels down
+        assert_eq!(engagements.response_distance, 150 + 150 + 150 / 2 + 150 / 3);
+
+        // Only direct replies are timed, an instant one earns all the points, one a half life later half
+        assert_eq!(engagements.response_timing, 150 + 75);
+
+        assert_eq!(
+            policy().engagement_score(&engagements),
+            14 + 12 + 100 + 100 + 425 + 225
+        );
+    }
+
+    #[tokio::test]
+    async fn engagements_of_a_response_nobody_engaged_with_are_none() {
+        let store = MemoryStore::new();
+        let id = respond(&store, CREATED_AT).await;
+        let response = store.get_response(&id).await.unwrap();
+
+        let engagements = policy().engagements(&store, &response).await.unwrap();
+
+        assert_eq!(engagements.total(), 0);
+    }
+
+    #[test]
+    fn replies_earn_less_the_deeper_and_later_they_are() {
+        let mut policy = policy();
+        policy.engagement.reply_depth_exponent = 2.0;
+
+        assert_eq!(policy.reply_points(0), 150);
+        assert_eq!(policy.reply_points(1), 150);
+        assert_eq!(policy.reply_points(2), 150 / 4);
+
+        assert_eq!(policy.timing_points(-5), 150);
+        assert_eq!(policy.timing_points(0), 150);
+        assert_eq!(policy.timing_points(60_000), 75);
+        assert_eq!(policy.timing_points(180_000), 37);
+    }
+
+    #[test]
+    fn engagement_is_capped_and_weighted() {
+        let mut policy = policy();
+        policy.engagement.max = Some(200);
+        policy.combine.engagement = 0.5;
+
+        let engagements = Engagements {
+            vote_validity: 150,
+            response_distance: 150,
+            ..Default::default()
+        };
+
+        assert_eq!(policy.engagement_score(&engagements), 100);
+    }
+
+    #[tokio::test]
+    async fn responses_are_scored_on_their_stored_engagement() {
+        let store = Arc::new(MemoryStore::new());
+        let mut config = Config::default().await;
+        config.store = store.clone();
+        config.scoring = Arc::new(ScoringPolicy {
+            id: "test".to_string(),
+            ..policy()
+        });
+
+        let mut response = engaged(&store).await;
+        let score = response.calculate_engagement_score(&config).await.unwrap();
+
+        assert_eq!(score, 876);
+        assert_eq!(response.engagements.total(), 876);
+        assert_eq!(response.scoring_policy, "test");
+
+        // A response that was never stored has nothing to be scored on
+        let mut unstored = Response::default();
+        assert_eq!(
+            unstored.calculate_engagement_score(&config).await.unwrap(),
+            0
+        );
+    }
+}
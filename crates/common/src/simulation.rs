@@ -1,3 +1,34 @@
+use crate::models::vote::VoteType;
+use std::sync::atomic::{AtomicI64, Ordering};
+
+/// Simulated milliseconds between two responses a simulation writes
+pub const TICK_MS: i64 = 1_000;
+
+/// Logical time responses are stamped with as a simulation writes them. It moves a tick per response
+/// rather than with the wall clock, so the latency of every reply, and the timing points it earns, is
+/// the same each time a seed is run
+#[derive(Debug, Default)]
+pub struct SimulationClock {
+    now: AtomicI64,
+}
+
+impl SimulationClock {
+    pub fn new(start: i64) -> Self {
+        Self {
+            now: AtomicI64::new(start),
+        }
+    }
+
+    /// Moves the clock a tick forward and returns the time it moved to
+    pub fn tick(&self) -> i64 {
+        self.now.fetch_add(TICK_MS, Ordering::SeqCst) + TICK_MS
+    }
+
+    pub fn now(&self) -> i64 {
+        self.now.load(Ordering::SeqCst)
+    }
+}
+
 pub enum SimulationType {
     Enneagram,
     Age,
@@ -40,3 +71,17 @@ impl Action {
         }
     }
 }
+
+impl From<&VoteType> for Action {
+    fn from(vote_type: &VoteType) -> Self {
+        let with_content = vote_type.reason().is_some_and(|r| !r.is_empty());
+
+        match vote_type {
+            VoteType::Valid(_) if with_content => Action::ValidVoteWithContent,
+            VoteType::Invalid(_) if with_content => Action::InvalidVoteWithContent,
+            VoteType::Valid(_) => Action::ValidVote,
+            VoteType::Invalid(_) => Action::InvalidVote,
+            VoteType::Abstain(_) => Action::AbstainVote,
+        }
+    }
+}
@@ -0,0 +1,606 @@
+use super::{created_at, new_id, Edge, Relationship, Store};
+use crate::{
+    errors::{PulpError, SimulationError},
+    models::{
+        Attributes, Blog, Debate, Engagements, Learned, PersonasUser, Reference, Response, Score,
+        ScoreVersion, SimulationRun, User, Vote,
+    },
+};
+use async_trait::async_trait;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex,
+};
+
+/// Writes buffered before a flush when `STORE_BATCH_SIZE` is unset
+pub const DEFAULT_BATCH_SIZE: usize = 500;
+
+/// An entity written as a whole
+#[derive(Debug, Clone)]
+pub enum Entity {
+    Debate(Debate),
+    User(User),
+    Response(Response),
+    Reference(Reference),
+    Vote(Vote),
+    Blog(Blog),
+    PersonasUser(PersonasUser),
+    Learned(Learned),
+}
+
+/// A write buffered by `BatchedStore` until its batch is flushed
+#[derive(Debug, Clone)]
+pub enum Write {
+    /// Creates the entity under the id already set on it
+    Create(Entity),
+
+    Update(Entity),
+
+    DebateCommenters {
+        id: String,
+        count: i64,
+    },
+
+    DebateVoters {
+        id: String,
+        count: i64,
+    },
+
+    DebateResponses {
+        id: String,
+        count: i64,
+    },
+
+    ResponseScore {
+        id: String,
+        score: i64,
+        policy: String,
+    },
+
+    ResponseVotes {
+        id: String,
+        valid: i64,
+        invalid: i64,
+        abstain: i64,
+    },
+
+    ResponseAppeals {
+        id: String,
+        ethos: f64,
+        pathos: f64,
+        logos: f64,
+    },
+
+    ResponseAttributes {
+        id: String,
+        attributes: Attributes,
+    },
+
+    ResponseEngagements {
+        id: String,
+        engagements: Engagements,
+    },
+
+    Relate(Relationship),
+}
+
+impl Write {
+    /// When the write is applied within its batch, entities are created before votes are cast on them,
+    /// updated once they exist and linked last
+    pub fn stage(&self) -> u8 {
+        match self {
+            Write::Create(Entity::Vote(_)) => 1,
+            Write::Create(_) => 0,
+            Write::Relate(_) => 3,
+            _ => 2,
+        }
+    }
+
+    /// Applies the write through the single entity methods of `store`
+    pub async fn apply<S: Store + ?Sized>(self, store: &S) -> Result<(), PulpError> {
+        match self {
+            Write::Create(entity) => {
+                match entity {
+                    Entity::Debate(debate) => store.create_debate(&debate).await?,
+                    Entity::User(user) => store.create_user(&user).await?,
+                    Entity::Response(response) => store.create_response(&response).await?,
+                    Entity::Reference(reference) => store.create_reference(&reference).await?,
+                    Entity::Vote(vote) => store.create_vote(&vote).await?,
+                    Entity::Blog(blog) => store.create_blog(&blog).await?,
+                    Entity::PersonasUser(user) => store.create_personas_user(&user).await?,
+                    Entity::Learned(learned) => store.create_learned(&learned).await?,
+                };
+
+                Ok(())
+            }
+
+            Write::Update(entity) => match entity {
+                Entity::Debate(debate) => store.update_debate(&debate).await,
+                Entity::User(user) => store.update_user(&user).await,
+                Entity::Response(response) => store.update_response(&response).await,
+                Entity::Reference(reference) => store.update_reference(&reference).await,
+                Entity::Vote(vote) => Err(PulpError::SimulationError(SimulationError::StoreError(
+                    format!("vote {} can't be updated", vote.id),
+                ))),
+                Entity::Blog(blog) => store.update_blog(&blog).await,
+                Entity::PersonasUser(user) => store.update_personas_user(&user).await,
+                Entity::Learned(learned) => store.update_learned(&learned).await,
+            },
+
+            Write::DebateCommenters { id, count } => {
+                store.update_debate_commenters(&id, count).await
+            }
+
+            Write::DebateVoters { id, count } => store.update_debate_voters(&id, count).await,
+
+            Write::DebateResponses { id, count } => store.update_debate_responses(&id, count).await,
+
+            Write::ResponseScore { id, score, policy } => {
+                store.update_response_score(&id, score, &policy).await
+            }
+
+            Write::ResponseVotes {
+                id,
+                valid,
+                invalid,
+                abstain,
+            } => {
+                store
+                    .update_response_votes(&id, valid, invalid, abstain)
+                    .await
+            }
+
+            Write::ResponseAppeals {
+                id,
+                ethos,
+                pathos,
+                logos,
+            } => {
+                store
+                    .update_response_appeals(&id, ethos, pathos, logos)
+                    .await
+            }
+
+            Write::ResponseAttributes { id, attributes } => {
+                store.update_response_attributes(&id, &attributes).await
+            }
+
+            Write::ResponseEngagements { id, engagements } => {
+                store.update_response_engagements(&id, &engagements).await
+            }
+
+            Write::Relate(relationship) => store.relate(relationship).await,
+        }
+    }
+}
+
+/// Buffers the writes made to another store and sends them in batches of `batch_size`.
+///
+/// Created entities get their id straight away, so they can be linked before they are written. Reads and
+/// deletes flush the buffer first, so they always see every earlier write. Errors of a buffered write are
+/// returned by the call that flushes it.
+pub struct BatchedStore {
+    inner: Arc<dyn Store>,
+
+    batch_size: usize,
+
+    pending: Mutex<Vec<Write>>,
+
+    /// Held while a batch is written, so batches reach the store in the order they were taken
+    flushing: tokio::sync::Mutex<()>,
+
+    /// Number of batches sent to the store
+    batches: AtomicU64,
+}
+
+impl BatchedStore {
+    pub fn new(inner: Arc<dyn Store>, batch_size: usize) -> Self {
+        Self {
+            inner,
+            batch_size: batch_size.max(1),
+            pending: Mutex::new(Vec::new()),
+            flushing: tokio::sync::Mutex::new(()),
+            batches: AtomicU64::new(0),
+        }
+    }
+
+    /// Number of batches sent to the store so far, flushing an empty buffer sends none
+    pub fn batches(&self) -> u64 {
+        self.batches.load(Ordering::Relaxed)
+    }
+
+    /// Buffers `write`, flushing once the batch is full
+    async fn push(&self, write: Write) -> Result<(), PulpError> {
+        let full = {
+            let mut pending = self.pending.lock().unwrap();
+            pending.push(write);
+
+            pending.len() >= self.batch_size
+        };
+
+        if full {
+            self.flush().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Buffers the creation of `entity` under the id `with_id` gives it
+    async fn create(
+        &self,
+        id: &str,
+        with_id: impl FnOnce(String) -> Entity,
+    ) -> Result<String, PulpError> {
+        let id = new_id(id);
+
+        self.push(Write::Create(with_id(id.clone()))).await?;
+
+        Ok(id)
+    }
+}
+
+#[async_trait]
+impl Store for BatchedStore {
+    async fn create_debate(&self, debate: &Debate) -> Result<String, PulpError> {
+        self.create(&debate.id, |id| {
+            Entity::Debate(Debate {
+                id,
+                ..debate.clone()
+            })
+        })
+        .await
+    }
+
+    async fn get_debate(&self, id: &str) -> Result<Debate, PulpError> {
+        self.flush().await?;
+        self.inner.get_debate(id).await
+    }
+
+    async fn update_debate(&self, debate: &Debate) -> Result<(), PulpError> {
+        self.push(Write::Update(Entity::Debate(debate.clone())))
+            .await
+    }
+
+    async fn update_debate_commenters(&self, id: &str, count: i64) -> Result<(), PulpError> {
+        self.push(Write::DebateCommenters {
+            id: id.to_string(),
+            count,
+        })
+        .await
+    }
+
+    async fn update_debate_voters(&self, id: &str, count: i64) -> Result<(), PulpError> {
+        self.push(Write::DebateVoters {
+            id: id.to_string(),
+            count,
+        })
+        .await
+    }
+
+    async fn update_debate_responses(&self, id: &str, count: i64) -> Result<(), PulpError> {
+        self.push(Write::DebateResponses {
+            id: id.to_string(),
+            count,
+        })
+        .await
+    }
+
+    async fn delete_debate(&self, id: &str) -> Result<(), PulpError> {
+        self.flush().await?;
+        self.inner.delete_debate(id).await
+    }
+
+    async fn create_user(&self, user: &User) -> Result<String, PulpError> {
+        self.create(&user.id, |id| Entity::User(User { id, ..user.clone() }))
+            .await
+    }
+
+    async fn get_user(&self, id: &str) -> Result<User, PulpError> {
+        self.flush().await?;
+        self.inner.get_user(id).await
+    }
+
+    async fn get_all_users(&self) -> Result<Vec<User>, PulpError> {
+        self.flush().await?;
+        self.inner.get_all_users().await
+    }
+
+    async fn update_user(&self, user: &User) -> Result<(), PulpError> {
+        self.push(Write::Update(Entity::User(user.clone()))).await
+    }
+
+    async fn delete_user(&self, id: &str) -> Result<(), PulpError> {
+        self.flush().await?;
+        self.inner.delete_user(id).await
+    }
+
+    async fn create_response(&self, response: &Response) -> Result<String, PulpError> {
+        self.create(&response.id, |id| {
+            Entity::Response(Response {
+                id,
+                created_at: created_at(response.created_at),
+                ..response.clone()
+            })
+        })
+        .await
+    }
+
+    async fn get_response(&self, id: &str) -> Result<Response, PulpError> {
+        self.flush().await?;
+        self.inner.get_response(id).await
+    }
+
+    async fn update_response(&self, response: &Response) -> Result<(), PulpError> {
+        self.push(Write::Update(Entity::Response(response.clone())))
+            .await
+    }
+
+    async fn update_response_score(
+        &self,
+        id: &str,
+        score: i64,
+        policy: &str,
+    ) -> Result<(), PulpError> {
+        self.push(Write::ResponseScore {
+            id: id.to_string(),
+            score,
+            policy: policy.to_string(),
+        })
+        .await
+    }
+
+    async fn update_response_votes(
+        &self,
+        id: &str,
+        valid: i64,
+        invalid: i64,
+        abstain: i64,
+    ) -> Result<(), PulpError> {
+        self.push(Write::ResponseVotes {
+            id: id.to_string(),
+            valid,
+            invalid,
+            abstain,
+        })
+        .await
+    }
+
+    async fn update_response_appeals(
+        &self,
+        id: &str,
+        ethos: f64,
+        pathos: f64,
+        logos: f64,
+    ) -> Result<(), PulpError> {
+        self.push(Write::ResponseAppeals {
+            id: id.to_string(),
+            ethos,
+            pathos,
+            logos,
+        })
+        .await
+    }
+
+    async fn update_response_attributes(
+        &self,
+        id: &str,
+        attributes: &Attributes,
+    ) -> Result<(), PulpError> {
+        self.push(Write::ResponseAttributes {
+            id: id.to_string(),
+            attributes: attributes.clone(),
+        })
+        .await
+    }
+
+    async fn update_response_engagements(
+        &self,
+        id: &str,
+        engagements: &Engagements,
+    ) -> Result<(), PulpError> {
+        self.push(Write::ResponseEngagements {
+            id: id.to_string(),
+            engagements: engagements.clone(),
+        })
+        .await
+    }
+
+    async fn delete_response(&self, id: &str) -> Result<(), PulpError> {
+        self.flush().await?;
+        self.inner.delete_response(id).await
+    }
+
+    async fn create_reference(&self, reference: &Reference) -> Result<String, PulpError> {
+        self.create(&reference.id, |id| {
+            Entity::Reference(Reference {
+                id,
+                ..reference.clone()
+            })
+        })
+        .await
+    }
+
+    async fn get_reference(&self, id: &str) -> Result<Reference, PulpError> {
+        self.flush().await?;
+        self.inner.get_reference(id).await
+    }
+
+    async fn update_reference(&self, reference: &Reference) -> Result<(), PulpError> {
+        self.push(Write::Update(Entity::Reference(reference.clone())))
+            .await
+    }
+
+    async fn delete_reference(&self, id: &str) -> Result<(), PulpError> {
+        self.flush().await?;
+        self.inner.delete_reference(id).await
+    }
+
+    async fn create_vote(&self, vote: &Vote) -> Result<String, PulpError> {
+        self.create(&vote.id, |id| Entity::Vote(Vote { id, ..vote.clone() }))
+            .await
+    }
+
+    async fn votes(&self, id: &str) -> Result<Vec<Vote>, PulpError> {
+        self.flush().await?;
+        self.inner.votes(id).await
+    }
+
+    async fn create_blog(&self, blog: &Blog) -> Result<String, PulpError> {
+        self.create(&blog.id, |id| Entity::Blog(Blog { id, ..blog.clone() }))
+            .await
+    }
+
+    async fn get_blog(&self, id: &str) -> Result<Blog, PulpError> {
+        self.flush().await?;
+        self.inner.get_blog(id).await
+    }
+
+    async fn update_blog(&self, blog: &Blog) -> Result<(), PulpError> {
+        self.push(Write::Update(Entity::Blog(blog.clone()))).await
+    }
+
+    async fn delete_blog(&self, id: &str) -> Result<(), PulpError> {
+        self.flush().await?;
+        self.inner.delete_blog(id).await
+    }
+
+    async fn create_personas_user(&self, user: &PersonasUser) -> Result<String, PulpError> {
+        self.create(&user.base_user.id, |id| {
+            let mut user = user.clone();
+            user.base_user.id = id;
+
+            Entity::PersonasUser(user)
+        })
+        .await
+    }
+
+    async fn get_personas_user(&self, id: &str) -> Result<PersonasUser, PulpError> {
+        self.flush().await?;
+        self.inner.get_personas_user(id).await
+    }
+
+    async fn get_all_personas_users(&self) -> Result<Vec<PersonasUser>, PulpError> {
+        self.flush().await?;
+        self.inner.get_all_personas_users().await
+    }
+
+    async fn update_personas_user(&self, user: &PersonasUser) -> Result<(), PulpError> {
+        self.push(Write::Update(Entity::PersonasUser(user.clone())))
+            .await
+    }
+
+    async fn delete_personas_user(&self, id: &str) -> Result<(), PulpError> {
+        self.flush().await?;
+        self.inner.delete_personas_user(id).await
+    }
+
+    async fn create_learned(&self, learned: &Learned) -> Result<String, PulpError> {
+        self.create(&learned.id, |id| {
+            Entity::Learned(Learned {
+                id,
+                ..learned.clone()
+            })
+        })
+        .await
+    }
+
+    async fn get_learned(&self, id: &str) -> Result<Learned, PulpError> {
+        self.flush().await?;
+        self.inner.get_learned(id).await
+    }
+
+    async fn update_learned(&self, learned: &Learned) -> Result<(), PulpError> {
+        self.push(Write::Update(Entity::Learned(learned.clone())))
+            .await
+    }
+
+    async fn delete_learned(&self, id: &str) -> Result<(), PulpError> {
+        self.flush().await?;
+        self.inner.delete_learned(id).await
+    }
+
+    async fn start_run(&self, run: &SimulationRun) -> Result<(), PulpError> {
+        self.flush().await?;
+        self.inner.start_run(run).await
+    }
+
+    async fn get_run(&self, id: &str) -> Result<SimulationRun, PulpError> {
+        self.flush().await?;
+        self.inner.get_run(id).await
+    }
+
+    async fn get_all_runs(&self) -> Result<Vec<SimulationRun>, PulpError> {
+        self.flush().await?;
+        self.inner.get_all_runs().await
+    }
+
+    async fn run_counts(&self, id: &str) -> Result<Vec<(String, i64)>, PulpError> {
+        self.flush().await?;
+        self.inner.run_counts(id).await
+    }
+
+    async fn delete_run(&self, id: &str) -> Result<(), PulpError> {
+        self.flush().await?;
+        self.inner.delete_run(id).await
+    }
+
+    async fn run_entities(&self, id: &str, label: &str) -> Result<Vec<String>, PulpError> {
+        self.flush().await?;
+        self.inner.run_entities(id, label).await
+    }
+
+    async fn create_score_version(
+        &self,
+        version: &ScoreVersion,
+        scores: &[Score],
+    ) -> Result<String, PulpError> {
+        self.flush().await?;
+        self.inner.create_score_version(version, scores).await
+    }
+
+    async fn get_score_versions(&self, id: &str) -> Result<Vec<ScoreVersion>, PulpError> {
+        self.flush().await?;
+        self.inner.get_score_versions(id).await
+    }
+
+    async fn get_scores(&self, id: &str) -> Result<Vec<Score>, PulpError> {
+        self.flush().await?;
+        self.inner.get_scores(id).await
+    }
+
+    async fn relate(&self, relationship: Relationship) -> Result<(), PulpError> {
+        self.push(Write::Relate(relationship)).await
+    }
+
+    async fn outgoing(&self, id: &str, edge: Edge) -> Result<Vec<String>, PulpError> {
+        self.flush().await?;
+        self.inner.outgoing(id, edge).await
+    }
+
+    async fn incoming(&self, id: &str, edge: Edge) -> Result<Vec<String>, PulpError> {
+        self.flush().await?;
+        self.inner.incoming(id, edge).await
+    }
+
+    async fn write(&self, writes: Vec<Write>) -> Result<(), PulpError> {
+        for write in writes {
+            self.push(write).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn flush(&self) -> Result<(), PulpError> {
+        let _flushing = self.flushing.lock().await;
+
+        let mut writes = std::mem::take(&mut *self.pending.lock().unwrap());
+        if writes.is_empty() {
+            return Ok(());
+        }
+
+        writes.sort_by_key(Write::stage);
+        self.batches.fetch_add(1, Ordering::Relaxed);
+
+        self.inner.write(writes).await
+    }
+}
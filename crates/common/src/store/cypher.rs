@@ -0,0 +1,389 @@
+use super::Edge;
+use neo4rs::Query;
+use std::fmt;
+
+/// A node pattern, i.e. `(d:Debate {id: $id})`
+#[derive(Debug, Clone, Default)]
+pub struct Pattern {
+    var: String,
+
+    label: Option<String>,
+
+    /// Properties and the expressions they are matched against, i.e. `("id", "$id")`
+    properties: Vec<(String, String)>,
+}
+
+impl Pattern {
+    pub fn new(var: &str) -> Self {
+        Self {
+            var: var.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    /// Matches the node whose `id` is the `param` parameter
+    pub fn id(self, param: &str) -> Self {
+        self.property("id", param)
+    }
+
+    /// Matches the node whose `id` is `expression`, i.e. `row.id` of an unwound list
+    pub fn id_from(mut self, expression: &str) -> Self {
+        self.properties
+            .push(("id".to_string(), expression.to_string()));
+        self
+    }
+
+    /// Matches the node whose `property` is the `param` parameter
+    pub fn property(mut self, property: &str, param: &str) -> Self {
+        self.properties
+            .push((property.to_string(), format!("${}", param)));
+        self
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut node = self.var.clone();
+
+        if let Some(label) = &self.label {
+            node = format!("{}:{}", node, label);
+        }
+
+        if !self.properties.is_empty() {
+            let properties = format!("{{{}}}", map(&self.properties));
+            node = if node.is_empty() {
+                properties
+            } else {
+                format!("{} {}", node, properties)
+            };
+        }
+
+        write!(f, "({})", node)
+    }
+}
+
+/// A Cypher query built clause by clause, so patterns are always balanced and
+/// every property is bound to the parameter of the same name
+#[derive(Debug, Clone, Default)]
+pub struct Cypher {
+    clauses: Vec<String>,
+}
+
+impl Cypher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `UNWIND $param AS var`, running the rest of the query once per element of the list
+    pub fn unwind(mut self, param: &str, var: &str) -> Self {
+        self.clauses.push(format!("UNWIND ${} AS {}", param, var));
+        self
+    }
+
+    /// `MATCH (var:Label {id: $param})`
+    pub fn matching(mut self, pattern: Pattern) -> Self {
+        self.clauses.push(format!("MATCH {}", pattern));
+        self
+    }
+
+    /// `MERGE (var:Label {id: $param})`, matching the node or creating it when there is none
+    pub fn merging(mut self, pattern: Pattern) -> Self {
+        self.clauses.push(format!("MERGE {}", pattern));
+        self
+    }
+
+    /// `MATCH (from)-[:EDGE]->(to)`
+    pub fn matching_edge(mut self, from: Pattern, edge: Edge, to: Pattern) -> Self {
+        self.clauses
+            .push(format!("MATCH {}-[:{}]->{}", from, edge.as_str(), to));
+        self
+    }
+
+    /// `MATCH (from)-[var:EDGE]->(to)`, binding the relationship to `var`
+    pub fn matching_named_edge(
+        mut self,
+        from: Pattern,
+        var: &str,
+        edge: Edge,
+        to: Pattern,
+    ) -> Self {
+        self.clauses.push(format!(
+            "MATCH {}-[{}:{}]->{}",
+            from,
+            var,
+            edge.as_str(),
+            to
+        ));
+        self
+    }
+
+    /// `MATCH ()-[var {property: $param}]->()`, relationships of any type
+    pub fn matching_relationships(mut self, var: &str, property: &str, param: &str) -> Self {
+        self.clauses.push(format!(
+            "MATCH ()-[{} {{{}: ${}}}]->()",
+            var, property, param
+        ));
+        self
+    }
+
+    /// `CREATE (var:Label {p: $p, ...})`
+    pub fn create(mut self, var: &str, label: &str, properties: &[&str]) -> Self {
+        let properties = properties
+            .iter()
+            .map(|p| format!("{}: ${}", p, p))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        self.clauses
+            .push(format!("CREATE ({}:{} {{{}}})", var, label, properties));
+        self
+    }
+
+    /// `CREATE (from)-[:EDGE {p: expression, ...}]->(to)` between two matched nodes
+    pub fn link_with(
+        mut self,
+        from: &str,
+        edge: Edge,
+        to: &str,
+        properties: &[(&str, &str)],
+    ) -> Self {
+        self.clauses.push(format!(
+            "CREATE ({})-[:{} {{{}}}]->({})",
+            from,
+            edge.as_str(),
+            map(properties),
+            to
+        ));
+        self
+    }
+
+    /// `CREATE (var:Label)`, leaving the properties to `assign`
+    pub fn create_node(mut self, var: &str, label: &str) -> Self {
+        self.clauses.push(format!("CREATE ({}:{})", var, label));
+        self
+    }
+
+    /// `CREATE (from)-[:EDGE]->(to)` between two matched nodes
+    pub fn link(mut self, from: &str, edge: Edge, to: &str) -> Self {
+        self.clauses
+            .push(format!("CREATE ({})-[:{}]->({})", from, edge.as_str(), to));
+        self
+    }
+
+    /// `SET var.p = $p, ...`
+    pub fn set(mut self, var: &str, properties: &[&str]) -> Self {
+        let properties = properties
+            .iter()
+            .map(|p| format!("{}.{} = ${}", var, p, p))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        self.clauses.push(format!("SET {}", properties));
+        self
+    }
+
+    /// `SET var = map`, replacing every property of `var` with the entries of `map`
+    pub fn assign(mut self, var: &str, map: &str) -> Self {
+        self.clauses.push(format!("SET {} = {}", var, map));
+        self
+    }
+
+    /// `SET var += map`, overwriting only the properties of `var` that are in `map`
+    pub fn merge(mut self, var: &str, map: &str) -> Self {
+        self.clauses.push(format!("SET {} += {}", var, map));
+        self
+    }
+
+    /// `WHERE var.p IS a string`, the only values equal to their own string form
+    pub fn where_string(mut self, var: &str, property: &str) -> Self {
+        self.clauses.push(format!(
+            "WHERE toString({}.{}) = {}.{}",
+            var, property, var, property
+        ));
+        self
+    }
+
+    /// `AND function(var.p) IS NOT NULL`, after a `WHERE`, the values `function` converts
+    pub fn and_converts(mut self, var: &str, property: &str, function: &str) -> Self {
+        self.clauses.push(format!(
+            "AND {}({}.{}) IS NOT NULL",
+            function, var, property
+        ));
+        self
+    }
+
+    /// `AND function(var.p) IS NULL`, after a `WHERE`, the values `function` can't convert
+    pub fn and_not_converts(mut self, var: &str, property: &str, function: &str) -> Self {
+        self.clauses
+            .push(format!("AND {}({}.{}) IS NULL", function, var, property));
+        self
+    }
+
+    /// `SET var.p = function(var.p)`
+    pub fn convert(mut self, var: &str, property: &str, function: &str) -> Self {
+        let value = format!("{}.{}", var, property);
+        self.clauses
+            .push(format!("SET {} = {}({})", value, function, value));
+        self
+    }
+
+    /// `DELETE var`, for relationships and nodes without any
+    pub fn delete(mut self, var: &str) -> Self {
+        self.clauses.push(format!("DELETE {}", var));
+        self
+    }
+
+    pub fn detach_delete(mut self, var: &str) -> Self {
+        self.clauses.push(format!("DETACH DELETE {}", var));
+        self
+    }
+
+    pub fn returning(mut self, expression: &str) -> Self {
+        self.clauses.push(format!("RETURN {}", expression));
+        self
+    }
+
+    pub fn query(&self) -> Query {
+        Query::new(self.to_string())
+    }
+}
+
+impl fmt::Display for Cypher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.clauses.join(" "))
+    }
+}
+
+/// `p: expression, ...`, the inside of a property map
+fn map<P: AsRef<str>, E: AsRef<str>>(properties: &[(P, E)]) -> String {
+    properties
+        .iter()
+        .map(|(p, e)| format!("{}: {}", p.as_ref(), e.as_ref()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn create_binds_every_property_to_its_parameter() {
+        let cypher = Cypher::new()
+            .create("n", "Debate", &["id", "topic", "run_id"])
+            .returning("n.id");
+
+        assert_eq!(
+            cypher.to_string(),
+            "CREATE (n:Debate {id: $id, topic: $topic, run_id: $run_id}) RETURN n.id"
+        );
+    }
+
+    #[test]
+    fn update_matches_the_node_by_id_and_sets_each_property() {
+        let cypher = Cypher::new()
+            .matching(Pattern::new("n").label("Response").id("id"))
+            .set("n", &["score", "scoring_policy"]);
+
+        assert_eq!(
+            cypher.to_string(),
+            "MATCH (n:Response {id: $id}) SET n.score = $score, n.scoring_policy = $scoring_policy"
+        );
+    }
+
+    #[test]
+    fn match_builds_node_and_edge_patterns() {
+        let by_property = Cypher::new()
+            .matching(
+                Pattern::new("s")
+                    .label("SchemaVersion")
+                    .property("id", "version_id"),
+            )
+            .returning("s.version AS version");
+        assert_eq!(
+            by_property.to_string(),
+            "MATCH (s:SchemaVersion {id: $version_id}) RETURN s.version AS version"
+        );
+
+        let by_label = Cypher::new()
+            .matching(Pattern::new("n").label("Learned"))
+            .returning("n");
+        assert_eq!(by_label.to_string(), "MATCH (n:Learned) RETURN n");
+
+        let edge = Cypher::new()
+            .matching_edge(Pattern::new("").id("id"), Edge::Replied, Pattern::new("n"))
+            .returning("n.id");
+        assert_eq!(
+            edge.to_string(),
+            "MATCH ({id: $id})-[:REPLIED]->(n) RETURN n.id"
+        );
+
+        let named = Cypher::new()
+            .matching_named_edge(
+                Pattern::new("v").label("ScoreVersion").id("id"),
+                "s",
+                Edge::Scored,
+                Pattern::new("r"),
+            )
+            .returning("r.id, s.score");
+        assert_eq!(
+            named.to_string(),
+            "MATCH (v:ScoreVersion {id: $id})-[s:SCORED]->(r) RETURN r.id, s.score"
+        );
+    }
+
+    #[test]
+    fn unwind_reads_each_node_and_property_from_the_row() {
+        let link = Cypher::new()
+            .unwind("rows", "row")
+            .matching(Pattern::new("a").label("Response").id_from("row.from"))
+            .matching(Pattern::new("b").label("Response").id_from("row.to"))
+            .link_with("a", Edge::Replied, "b", &[("run_id", "row.run_id")]);
+        assert_eq!(
+            link.to_string(),
+            "UNWIND $rows AS row MATCH (a:Response {id: row.from}) MATCH (b:Response {id: row.to}) CREATE (a)-[:REPLIED {run_id: row.run_id}]->(b)"
+        );
+
+        let create = Cypher::new()
+            .unwind("rows", "row")
+            .create_node("n", "Learned")
+            .assign("n", "row");
+        assert_eq!(
+            create.to_string(),
+            "UNWIND $rows AS row CREATE (n:Learned) SET n = row"
+        );
+
+        let update = Cypher::new()
+            .unwind("rows", "row")
+            .matching(Pattern::new("n").label("Debate").id_from("row.id"))
+            .merge("n", "row");
+        assert_eq!(
+            update.to_string(),
+            "UNWIND $rows AS row MATCH (n:Debate {id: row.id}) SET n += row"
+        );
+    }
+
+    #[test]
+    fn delete_detaches_matched_nodes() {
+        let cypher = Cypher::new()
+            .matching_relationships("e", "run_id", "run")
+            .delete("e");
+        assert_eq!(
+            cypher.to_string(),
+            "MATCH ()-[e {run_id: $run}]->() DELETE e"
+        );
+
+        let cypher = Cypher::new()
+            .matching(Pattern::new("n").label("Blog").id("id"))
+            .detach_delete("n");
+        assert_eq!(
+            cypher.to_string(),
+            "MATCH (n:Blog {id: $id}) DETACH DELETE n"
+        );
+    }
+}
@@ -0,0 +1,660 @@
+use super::{created_at, new_id, Edge, Relationship, Store};
+use crate::{
+    errors::{PulpError, SimulationError},
+    models::{
+        Attributes, Blog, Debate, Engagements, Learned, PersonasUser, Reference, Response, Score,
+        ScoreVersion, SimulationRun, User, Vote,
+    },
+};
+use async_trait::async_trait;
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard},
+};
+
+/// Keeps every entity and edge in memory, for tests and runs that need no database
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    graph: Mutex<MemoryGraph>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryGraph {
+    debates: HashMap<String, Debate>,
+
+    users: HashMap<String, User>,
+
+    responses: HashMap<String, Response>,
+
+    references: HashMap<String, Reference>,
+
+    votes: HashMap<String, Vote>,
+
+    blogs: HashMap<String, Blog>,
+
+    personas: HashMap<String, PersonasUser>,
+
+    learned: HashMap<String, Learned>,
+
+    /// `(from, edge, to)` ids of every relationship
+    edges: Vec<(String, Edge, String)>,
+
+    runs: HashMap<String, SimulationRun>,
+
+    score_versions: HashMap<String, ScoreVersion>,
+
+    /// Scores of every score version, by version id
+    scores: HashMap<String, Vec<Score>>,
+
+    /// The run everything is written in, if one was started
+    run: Option<String>,
+
+    /// Run each entity was written in, by entity id
+    entity_runs: HashMap<String, String>,
+
+    /// Run each relationship was written in, by `(from, edge, to)`
+    edge_runs: HashMap<(String, Edge, String), String>,
+}
+
+impl MemoryGraph {
+    /// Label of the entity with `id`, the one it would have in Neo4j
+    fn label(&self, id: &str) -> Option<&'static str> {
+        if self.debates.contains_key(id) {
+            Some("Debate")
+        } else if self.users.contains_key(id) {
+            Some("User")
+        } else if self.responses.contains_key(id) {
+            Some("Response")
+        } else if self.references.contains_key(id) {
+            Some("Reference")
+        } else if self.votes.contains_key(id) {
+            Some("Vote")
+        } else if self.blogs.contains_key(id) {
+            Some("Blog")
+        } else if self.personas.contains_key(id) {
+            Some("PersonaUser")
+        } else if self.learned.contains_key(id) {
+            Some("Learned")
+        } else if self.score_versions.contains_key(id) {
+            Some("ScoreVersion")
+        } else {
+            None
+        }
+    }
+
+    /// Removes every edge from or to `id`, like a `DETACH DELETE`
+    fn detach(&mut self, id: &str) {
+        self.edges.retain(|(from, _, to)| from != id && to != id);
+    }
+
+    /// Removes the entity with `id` whatever its label, along with its edges
+    fn remove(&mut self, id: &str) {
+        self.debates.remove(id);
+        self.users.remove(id);
+        self.responses.remove(id);
+        self.references.remove(id);
+        self.votes.remove(id);
+        self.blogs.remove(id);
+        self.personas.remove(id);
+        self.learned.remove(id);
+        self.score_versions.remove(id);
+        self.scores.remove(id);
+        self.entity_runs.remove(id);
+        self.detach(id);
+    }
+
+    /// Tags the entity with `id` with the current run
+    fn tag(&mut self, id: &str) {
+        if let Some(run) = &self.run {
+            self.entity_runs.insert(id.to_string(), run.clone());
+        }
+    }
+
+    /// Adds an edge tagged with the current run
+    fn link(&mut self, edge: (String, Edge, String)) {
+        if let Some(run) = &self.run {
+            self.edge_runs.insert(edge.clone(), run.clone());
+        }
+
+        self.edges.push(edge);
+    }
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn graph(&self) -> MutexGuard<'_, MemoryGraph> {
+        self.graph.lock().unwrap()
+    }
+}
+
+#[async_trait]
+impl Store for MemoryStore {
+    async fn create_debate(&self, debate: &Debate) -> Result<String, PulpError> {
+        let mut debate = debate.clone();
+        debate.id = new_id(&debate.id);
+
+        let id = debate.id.clone();
+        let mut graph = self.graph();
+        graph.debates.insert(id.clone(), debate);
+        graph.tag(&id);
+
+        Ok(id)
+    }
+
+    async fn get_debate(&self, id: &str) -> Result<Debate, PulpError> {
+        get(&self.graph().debates, "debate", id)
+    }
+
+    async fn update_debate(&self, debate: &Debate) -> Result<(), PulpError> {
+        replace(&mut self.graph().debates, &debate.id, debate);
+
+        Ok(())
+    }
+
+    async fn update_debate_commenters(&self, id: &str, count: i64) -> Result<(), PulpError> {
+        if let Some(debate) = self.graph().debates.get_mut(id) {
+            debate.commenters = count;
+        }
+
+        Ok(())
+    }
+
+    async fn update_debate_voters(&self, id: &str, count: i64) -> Result<(), PulpError> {
+        if let Some(debate) = self.graph().debates.get_mut(id) {
+            debate.voters = count;
+        }
+
+        Ok(())
+    }
+
+    async fn update_debate_responses(&self, id: &str, count: i64) -> Result<(), PulpError> {
+        if let Some(debate) = self.graph().debates.get_mut(id) {
+            debate.responses = count;
+        }
+
+        Ok(())
+    }
+
+    async fn delete_debate(&self, id: &str) -> Result<(), PulpError> {
+        let mut graph = self.graph();
+        graph.debates.remove(id);
+        graph.detach(id);
+
+        Ok(())
+    }
+
+    async fn create_user(&self, user: &User) -> Result<String, PulpError> {
+        let mut user = user.clone();
+        user.id = new_id(&user.id);
+
+        let id = user.id.clone();
+        let mut graph = self.graph();
+        graph.users.insert(id.clone(), user);
+        graph.tag(&id);
+
+        Ok(id)
+    }
+
+    async fn get_user(&self, id: &str) -> Result<User, PulpError> {
+        get(&self.graph().users, "user", id)
+    }
+
+    async fn get_all_users(&self) -> Result<Vec<User>, PulpError> {
+        Ok(self.graph().users.values().cloned().collect())
+    }
+
+    async fn update_user(&self, user: &User) -> Result<(), PulpError> {
+        replace(&mut self.graph().users, &user.id, user);
+
+        Ok(())
+    }
+
+    async fn delete_user(&self, id: &str) -> Result<(), PulpError> {
+        let mut graph = self.graph();
+        graph.users.remove(id);
+        graph.detach(id);
+
+        Ok(())
+    }
+
+    async fn create_response(&self, response: &Response) -> Result<String, PulpError> {
+        let mut response = response.clone();
+        response.id = new_id(&response.id);
+        response.created_at = created_at(response.created_at);
+
+        let id = response.id.clone();
+        let mut graph = self.graph();
+        graph.responses.insert(id.clone(), response);
+        graph.tag(&id);
+
+        Ok(id)
+    }
+
+    async fn get_response(&self, id: &str) -> Result<Response, PulpError> {
+        get(&self.graph().responses, "response", id)
+    }
+
+    async fn update_response(&self, response: &Response) -> Result<(), PulpError> {
+        replace(&mut self.graph().responses, &response.id, response);
+
+        Ok(())
+    }
+
+    async fn update_response_score(
+        &self,
+        id: &str,
+        score: i64,
+        policy: &str,
+    ) -> Result<(), PulpError> {
+        if let Some(response) = self.graph().responses.get_mut(id) {
+            response.score = score;
+            response.scoring_policy = policy.to_string();
+        }
+
+        Ok(())
+    }
+
+    async fn update_response_votes(
+        &self,
+        id: &str,
+        valid: i64,
+        invalid: i64,
+        abstain: i64,
+    ) -> Result<(), PulpError> {
+        if let Some(response) = self.graph().responses.get_mut(id) {
+            response.valid_vote_count = valid;
+            response.invalid_vote_count = invalid;
+            response.abstain_vote_count = abstain;
+        }
+
+        Ok(())
+    }
+
+    async fn update_response_appeals(
+        &self,
+        id: &str,
+        ethos: f64,
+        pathos: f64,
+        logos: f64,
+    ) -> Result<(), PulpError> {
+        if let Some(response) = self.graph().responses.get_mut(id) {
+            response.ethos = ethos;
+            response.pathos = pathos;
+            response.logos = logos;
+        }
+
+        Ok(())
+    }
+
+    async fn update_response_attributes(
+        &self,
+        id: &str,
+        attributes: &Attributes,
+    ) -> Result<(), PulpError> {
+        if let Some(response) = self.graph().responses.get_mut(id) {
+            response.attributes = attributes.clone();
+        }
+
+        Ok(())
+    }
+
+    async fn update_response_engagements(
+        &self,
+        id: &str,
+        engagements: &Engagements,
+    ) -> Result<(), PulpError> {
+        if let Some(response) = self.graph().responses.get_mut(id) {
+            response.engagements = engagements.clone();
+        }
+
+        Ok(())
+    }
+
+    async fn delete_response(&self, id: &str) -> Result<(), PulpError> {
+        let mut graph = self.graph();
+        graph.responses.remove(id);
+        graph.detach(id);
+
+        Ok(())
+    }
+
+    async fn create_reference(&self, reference: &Reference) -> Result<String, PulpError> {
+        let mut reference = reference.clone();
+        reference.id = new_id(&reference.id);
+
+        let id = reference.id.clone();
+        let mut graph = self.graph();
+        graph.references.insert(id.clone(), reference);
+        graph.tag(&id);
+
+        Ok(id)
+    }
+
+    async fn get_reference(&self, id: &str) -> Result<Reference, PulpError> {
+        get(&self.graph().references, "reference", id)
+    }
+
+    async fn update_reference(&self, reference: &Reference) -> Result<(), PulpError> {
+        replace(&mut self.graph().references, &reference.id, reference);
+
+        Ok(())
+    }
+
+    async fn delete_reference(&self, id: &str) -> Result<(), PulpError> {
+        let mut graph = self.graph();
+        graph.references.remove(id);
+        graph.detach(id);
+
+        Ok(())
+    }
+
+    async fn create_vote(&self, vote: &Vote) -> Result<String, PulpError> {
+        let mut vote = vote.clone();
+        vote.id = new_id(&vote.id);
+
+        let id = vote.id.clone();
+        let mut graph = self.graph();
+
+        // Like the Neo4j store, a vote on a missing response is not kept
+        if graph.responses.contains_key(&vote.response_id) {
+            graph.link((id.clone(), Edge::VotedOn, vote.response_id.clone()));
+            graph.votes.insert(id.clone(), vote);
+            graph.tag(&id);
+        }
+
+        Ok(id)
+    }
+
+    async fn votes(&self, id: &str) -> Result<Vec<Vote>, PulpError> {
+        let graph = self.graph();
+
+        let votes = graph
+            .edges
+            .iter()
+            .filter(|(_, edge, to)| to == id && *edge == Edge::VotedOn)
+            .filter_map(|(from, _, _)| graph.votes.get(from).cloned())
+            .collect();
+
+        Ok(votes)
+    }
+
+    async fn create_blog(&self, blog: &Blog) -> Result<String, PulpError> {
+        let mut blog = blog.clone();
+        blog.id = new_id(&blog.id);
+
+        let id = blog.id.clone();
+        let mut graph = self.graph();
+        graph.blogs.insert(id.clone(), blog);
+        graph.tag(&id);
+
+        Ok(id)
+    }
+
+    async fn get_blog(&self, id: &str) -> Result<Blog, PulpError> {
+        get(&self.graph().blogs, "blog", id)
+    }
+
+    async fn update_blog(&self, blog: &Blog) -> Result<(), PulpError> {
+        replace(&mut self.graph().blogs, &blog.id, blog);
+
+        Ok(())
+    }
+
+    async fn delete_blog(&self, id: &str) -> Result<(), PulpError> {
+        let mut graph = self.graph();
+        graph.blogs.remove(id);
+        graph.detach(id);
+
+        Ok(())
+    }
+
+    async fn create_personas_user(&self, user: &PersonasUser) -> Result<String, PulpError> {
+        let mut user = user.clone();
+        user.base_user.id = new_id(&user.base_user.id);
+
+        let id = user.base_user.id.clone();
+        let mut graph = self.graph();
+        graph.personas.insert(id.clone(), user);
+        graph.tag(&id);
+
+        Ok(id)
+    }
+
+    async fn get_personas_user(&self, id: &str) -> Result<PersonasUser, PulpError> {
+        get(&self.graph().personas, "persona", id)
+    }
+
+    async fn get_all_personas_users(&self) -> Result<Vec<PersonasUser>, PulpError> {
+        Ok(self.graph().personas.values().cloned().collect())
+    }
+
+    async fn update_personas_user(&self, user: &PersonasUser) -> Result<(), PulpError> {
+        replace(&mut self.graph().personas, &user.base_user.id, user);
+
+        Ok(())
+    }
+
+    async fn delete_personas_user(&self, id: &str) -> Result<(), PulpError> {
+        let mut graph = self.graph();
+        graph.personas.remove(id);
+        graph.detach(id);
+
+        Ok(())
+    }
+
+    async fn create_learned(&self, learned: &Learned) -> Result<String, PulpError> {
+        let mut learned = learned.clone();
+        learned.id = new_id(&learned.id);
+
+        let id = learned.id.clone();
+        let mut graph = self.graph();
+        graph.learned.insert(id.clone(), learned);
+        graph.tag(&id);
+
+        Ok(id)
+    }
+
+    async fn get_learned(&self, id: &str) -> Result<Learned, PulpError> {
+        get(&self.graph().learned, "learned", id)
+    }
+
+    async fn update_learned(&self, learned: &Learned) -> Result<(), PulpError> {
+        replace(&mut self.graph().learned, &learned.id, learned);
+
+        Ok(())
+    }
+
+    async fn delete_learned(&self, id: &str) -> Result<(), PulpError> {
+        let mut graph = self.graph();
+        graph.learned.remove(id);
+        graph.detach(id);
+
+        Ok(())
+    }
+
+    async fn start_run(&self, run: &SimulationRun) -> Result<(), PulpError> {
+        let mut graph = self.graph();
+        graph.runs.insert(run.id.clone(), run.clone());
+        graph.run = Some(run.id.clone());
+
+        Ok(())
+    }
+
+    async fn get_run(&self, id: &str) -> Result<SimulationRun, PulpError> {
+        get(&self.graph().runs, "run", id)
+    }
+
+    async fn get_all_runs(&self) -> Result<Vec<SimulationRun>, PulpError> {
+        let mut runs = self.graph().runs.values().cloned().collect::<Vec<_>>();
+        runs.sort_by_key(|run| run.started_at);
+
+        Ok(runs)
+    }
+
+    async fn run_counts(&self, id: &str) -> Result<Vec<(String, i64)>, PulpError> {
+        let graph = self.graph();
+
+        let mut counts: Vec<(String, i64)> = Vec::new();
+        for (entity, _) in graph.entity_runs.iter().filter(|(_, run)| *run == id) {
+            if let Some(label) = graph.label(entity) {
+                match counts.iter_mut().find(|(l, _)| l == label) {
+                    Some((_, count)) => *count += 1,
+                    None => counts.push((label.to_string(), 1)),
+                }
+            }
+        }
+        counts.sort();
+
+        let edges = graph
+            .edges
+            .iter()
+            .filter(|edge| graph.edge_runs.get(*edge).map(String::as_str) == Some(id))
+            .count();
+        let scores = graph
+            .score_versions
+            .values()
+            .filter(|version| version.run_id == id)
+            .filter_map(|version| graph.scores.get(&version.id))
+            .map(Vec::len)
+            .sum::<usize>();
+        counts.push(("relationships".to_string(), (edges + scores) as i64));
+
+        Ok(counts)
+    }
+
+    async fn delete_run(&self, id: &str) -> Result<(), PulpError> {
+        let mut graph = self.graph();
+
+        let entities = graph
+            .entity_runs
+            .iter()
+            .filter(|(_, run)| *run == id)
+            .map(|(entity, _)| entity.clone())
+            .collect::<Vec<_>>();
+        for entity in entities {
+            graph.remove(&entity);
+        }
+
+        let MemoryGraph {
+            edges, edge_runs, ..
+        } = &mut *graph;
+        edges.retain(|edge| edge_runs.get(edge).map(String::as_str) != Some(id));
+        edge_runs.retain(|_, run| run != id);
+
+        graph.runs.remove(id);
+        if graph.run.as_deref() == Some(id) {
+            graph.run = None;
+        }
+
+        Ok(())
+    }
+
+    async fn run_entities(&self, id: &str, label: &str) -> Result<Vec<String>, PulpError> {
+        let graph = self.graph();
+
+        let mut ids = graph
+            .entity_runs
+            .iter()
+            .filter(|(entity, run)| *run == id && graph.label(entity) == Some(label))
+            .map(|(entity, _)| entity.clone())
+            .collect::<Vec<_>>();
+        ids.sort();
+
+        Ok(ids)
+    }
+
+    async fn create_score_version(
+        &self,
+        version: &ScoreVersion,
+        scores: &[Score],
+    ) -> Result<String, PulpError> {
+        let mut version = version.clone();
+        version.id = new_id(&version.id);
+
+        let mut graph = self.graph();
+        graph
+            .entity_runs
+            .insert(version.id.clone(), version.run_id.clone());
+        graph.scores.insert(version.id.clone(), scores.to_vec());
+        graph
+            .score_versions
+            .insert(version.id.clone(), version.clone());
+
+        Ok(version.id)
+    }
+
+    async fn get_score_versions(&self, id: &str) -> Result<Vec<ScoreVersion>, PulpError> {
+        let mut versions = self
+            .graph()
+            .score_versions
+            .values()
+            .filter(|version| version.run_id == id)
+            .cloned()
+            .collect::<Vec<_>>();
+        versions.sort_by_key(|version| version.scored_at);
+
+        Ok(versions)
+    }
+
+    async fn get_scores(&self, id: &str) -> Result<Vec<Score>, PulpError> {
+        Ok(self.graph().scores.get(id).cloned().unwrap_or_default())
+    }
+
+    async fn relate(&self, relationship: Relationship) -> Result<(), PulpError> {
+        let (from, to) = relationship.ends();
+        let (from_label, to_label) = relationship.labels();
+        let mut graph = self.graph();
+
+        // Like the Neo4j store, which matches both ends by label, an edge between entities with other
+        // labels is not kept
+        if graph.label(from) == Some(from_label) && graph.label(to) == Some(to_label) {
+            graph.link((from.to_string(), relationship.edge(), to.to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn outgoing(&self, id: &str, edge: Edge) -> Result<Vec<String>, PulpError> {
+        let ids = self
+            .graph()
+            .edges
+            .iter()
+            .filter(|(from, e, _)| from == id && *e == edge)
+            .map(|(_, _, to)| to.clone())
+            .collect();
+
+        Ok(ids)
+    }
+
+    async fn incoming(&self, id: &str, edge: Edge) -> Result<Vec<String>, PulpError> {
+        let ids = self
+            .graph()
+            .edges
+            .iter()
+            .filter(|(_, e, to)| to == id && *e == edge)
+            .map(|(from, _, _)| from.clone())
+            .collect();
+
+        Ok(ids)
+    }
+}
+
+fn get<T: Clone>(entities: &HashMap<String, T>, label: &str, id: &str) -> Result<T, PulpError> {
+    match entities.get(id) {
+        Some(entity) => Ok(entity.clone()),
+
+        None => Err(PulpError::SimulationError(SimulationError::StoreError(
+            format!("{} {} not found", label, id),
+        ))),
+    }
+}
+
+/// Replaces the entity stored under `id`, nothing is stored when there is none
+fn replace<T: Clone>(entities: &mut HashMap<String, T>, id: &str, entity: &T) {
+    if let Some(stored) = entities.get_mut(id) {
+        *stored = entity.clone();
+    }
+}
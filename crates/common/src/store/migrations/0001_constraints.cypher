@@ -0,0 +1,10 @@
+// Unique ids, each constraint is backed by an index so matching a node by id is a lookup rather than a label scan
+
+CREATE CONSTRAINT debate_id IF NOT EXISTS FOR (n:Debate) REQUIRE n.id IS UNIQUE;
+CREATE CONSTRAINT response_id IF NOT EXISTS FOR (n:Response) REQUIRE n.id IS UNIQUE;
+CREATE CONSTRAINT user_id IF NOT EXISTS FOR (n:User) REQUIRE n.id IS UNIQUE;
+CREATE CONSTRAINT reference_id IF NOT EXISTS FOR (n:Reference) REQUIRE n.id IS UNIQUE;
+CREATE CONSTRAINT learned_id IF NOT EXISTS FOR (n:Learned) REQUIRE n.id IS UNIQUE;
+CREATE CONSTRAINT blog_id IF NOT EXISTS FOR (n:Blog) REQUIRE n.id IS UNIQUE;
+CREATE CONSTRAINT persona_user_id IF NOT EXISTS FOR (n:PersonaUser) REQUIRE n.id IS UNIQUE;
+CREATE CONSTRAINT vote_id IF NOT EXISTS FOR (n:Vote) REQUIRE n.id IS UNIQUE;
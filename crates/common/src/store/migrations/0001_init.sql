@@ -0,0 +1,157 @@
+-- Entities
+
+CREATE TABLE debates (
+    id TEXT PRIMARY KEY,
+    score INTEGER NOT NULL DEFAULT 0,
+    topic TEXT NOT NULL DEFAULT '',
+    category TEXT NOT NULL DEFAULT '',
+    registered_speakers INTEGER NOT NULL DEFAULT 0,
+    commenters INTEGER NOT NULL DEFAULT 0,
+    voters INTEGER NOT NULL DEFAULT 0,
+    comments INTEGER NOT NULL DEFAULT 0,
+    inactive_participants INTEGER NOT NULL DEFAULT 0,
+    responses INTEGER NOT NULL DEFAULT 0,
+    init_timestamp INTEGER NOT NULL DEFAULT 0,
+    registration_start INTEGER NOT NULL DEFAULT 0,
+    registration_end INTEGER NOT NULL DEFAULT 0,
+    competition_start INTEGER NOT NULL DEFAULT 0,
+    competition_end INTEGER NOT NULL DEFAULT 0,
+    rewards_start INTEGER NOT NULL DEFAULT 0,
+    rewards_end INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE users (
+    id TEXT PRIMARY KEY,
+    simulation_data TEXT NOT NULL DEFAULT ''
+);
+
+CREATE TABLE responses (
+    id TEXT PRIMARY KEY,
+    content TEXT NOT NULL DEFAULT '',
+    confidence REAL NOT NULL DEFAULT 0,
+    score INTEGER NOT NULL DEFAULT 0,
+    valid_vote_count INTEGER NOT NULL DEFAULT 0,
+    invalid_vote_count INTEGER NOT NULL DEFAULT 0,
+    abstain_vote_count INTEGER NOT NULL DEFAULT 0,
+    report_count INTEGER NOT NULL DEFAULT 0,
+    hide_count INTEGER NOT NULL DEFAULT 0,
+    topic_of_response TEXT NOT NULL DEFAULT '',
+    ethos REAL NOT NULL DEFAULT 0,
+    pathos REAL NOT NULL DEFAULT 0,
+    logos REAL NOT NULL DEFAULT 0,
+    relevance REAL NOT NULL DEFAULT 0,
+    soundness REAL NOT NULL DEFAULT 0,
+    reference_count INTEGER NOT NULL DEFAULT 0,
+    word_count INTEGER NOT NULL DEFAULT 0,
+    -- JSON array of words
+    mastery_vocab_words TEXT NOT NULL DEFAULT '[]',
+    report_harmful_to_others INTEGER NOT NULL DEFAULT 0,
+    report_abuse_of_platform INTEGER NOT NULL DEFAULT 0,
+    hide INTEGER NOT NULL DEFAULT 0,
+    vote_validity INTEGER NOT NULL DEFAULT 0,
+    vote_confidence INTEGER NOT NULL DEFAULT 0,
+    response_distance INTEGER NOT NULL DEFAULT 0,
+    response_timing INTEGER NOT NULL DEFAULT 0,
+    prompt_name TEXT NOT NULL DEFAULT '',
+    prompt_version TEXT NOT NULL DEFAULT ''
+);
+
+CREATE TABLE "references" (
+    id TEXT PRIMARY KEY,
+    internal INTEGER NOT NULL DEFAULT 0,
+    trust INTEGER NOT NULL DEFAULT 0,
+    distrust INTEGER NOT NULL DEFAULT 0,
+    content TEXT NOT NULL DEFAULT ''
+);
+
+CREATE TABLE votes (
+    id TEXT PRIMARY KEY,
+    vote_type TEXT NOT NULL,
+    reason TEXT,
+    debate_id TEXT NOT NULL DEFAULT '',
+    response_id TEXT NOT NULL,
+    prompt_name TEXT NOT NULL DEFAULT '',
+    prompt_version TEXT NOT NULL DEFAULT ''
+);
+
+CREATE INDEX votes_response_id ON votes (response_id);
+
+CREATE TABLE blogs (
+    id TEXT PRIMARY KEY,
+    title TEXT NOT NULL DEFAULT '',
+    body TEXT NOT NULL DEFAULT '',
+    author TEXT NOT NULL DEFAULT '',
+    topic TEXT NOT NULL DEFAULT '',
+    category TEXT NOT NULL DEFAULT '',
+    publish_timestamp INTEGER NOT NULL DEFAULT 0,
+    ethos INTEGER NOT NULL DEFAULT 0,
+    pathos INTEGER NOT NULL DEFAULT 0,
+    logos INTEGER NOT NULL DEFAULT 0,
+    score INTEGER NOT NULL DEFAULT 0,
+    engagements INTEGER NOT NULL DEFAULT 0,
+    impressions INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE personas (
+    id TEXT PRIMARY KEY,
+    simulation_data TEXT NOT NULL DEFAULT '',
+    age INTEGER NOT NULL DEFAULT 0,
+    gender TEXT NOT NULL DEFAULT 'other',
+    political_orientation TEXT NOT NULL DEFAULT 'center',
+    -- JSON arrays of influences
+    vote_valid_influence TEXT NOT NULL DEFAULT '[]',
+    vote_invalid_influence TEXT NOT NULL DEFAULT '[]',
+    vote_abstain_influence TEXT NOT NULL DEFAULT '[]',
+    knowledge_references REAL NOT NULL DEFAULT 0,
+    followers INTEGER NOT NULL DEFAULT 0,
+    following INTEGER NOT NULL DEFAULT 0,
+    impressions INTEGER NOT NULL DEFAULT 0,
+    engagements INTEGER NOT NULL DEFAULT 0,
+    input REAL NOT NULL DEFAULT 0,
+    output REAL NOT NULL DEFAULT 0,
+    core_fear TEXT NOT NULL DEFAULT '',
+    enneagram INTEGER NOT NULL DEFAULT 0,
+    core_desire TEXT NOT NULL DEFAULT '',
+    valid_voting_tendency INTEGER NOT NULL DEFAULT 0,
+    invalid_voting_tendency INTEGER NOT NULL DEFAULT 0,
+    abstain_voting_tendency INTEGER NOT NULL DEFAULT 0,
+    hide_tendency REAL NOT NULL DEFAULT 0,
+    report_tendency REAL NOT NULL DEFAULT 0,
+    engagement_type INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE learned (
+    id TEXT PRIMARY KEY,
+    learned_content TEXT NOT NULL DEFAULT '',
+    reason TEXT NOT NULL DEFAULT ''
+);
+
+-- Edges
+
+-- (Response)-[:REPLIED]->(Response)
+CREATE TABLE replies (
+    response_id TEXT NOT NULL,
+    reply_id TEXT NOT NULL,
+    PRIMARY KEY (response_id, reply_id)
+);
+
+CREATE INDEX replies_reply_id ON replies (reply_id);
+
+-- (User)-[:RESPONDED]->(Response) and (PersonaUser)-[:RESPONDED]->(Response)
+CREATE TABLE authorships (
+    author_id TEXT NOT NULL,
+    response_id TEXT NOT NULL,
+    PRIMARY KEY (author_id, response_id)
+);
+
+CREATE INDEX authorships_response_id ON authorships (response_id);
+
+-- Every other relationship, named after its graph edge
+CREATE TABLE edges (
+    from_id TEXT NOT NULL,
+    edge TEXT NOT NULL,
+    to_id TEXT NOT NULL,
+    PRIMARY KEY (from_id, edge, to_id)
+);
+
+CREATE INDEX edges_to_id ON edges (to_id, edge);
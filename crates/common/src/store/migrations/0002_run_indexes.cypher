@@ -0,0 +1,12 @@
+// Simulation runs, every node a run writes carries its id in `run_id`
+
+CREATE CONSTRAINT simulation_run_id IF NOT EXISTS FOR (n:SimulationRun) REQUIRE n.id IS UNIQUE;
+
+CREATE INDEX debate_run_id IF NOT EXISTS FOR (n:Debate) ON (n.run_id);
+CREATE INDEX response_run_id IF NOT EXISTS FOR (n:Response) ON (n.run_id);
+CREATE INDEX user_run_id IF NOT EXISTS FOR (n:User) ON (n.run_id);
+CREATE INDEX reference_run_id IF NOT EXISTS FOR (n:Reference) ON (n.run_id);
+CREATE INDEX learned_run_id IF NOT EXISTS FOR (n:Learned) ON (n.run_id);
+CREATE INDEX blog_run_id IF NOT EXISTS FOR (n:Blog) ON (n.run_id);
+CREATE INDEX persona_user_run_id IF NOT EXISTS FOR (n:PersonaUser) ON (n.run_id);
+CREATE INDEX vote_run_id IF NOT EXISTS FOR (n:Vote) ON (n.run_id);
@@ -0,0 +1,34 @@
+-- Simulation runs, every entity and relationship written during one carries its id
+
+CREATE TABLE runs (
+    id TEXT PRIMARY KEY,
+    simulation TEXT NOT NULL DEFAULT '',
+    config_file TEXT NOT NULL DEFAULT '',
+    config_hash TEXT NOT NULL DEFAULT '',
+    seed INTEGER NOT NULL DEFAULT 0,
+    started_at INTEGER NOT NULL DEFAULT 0
+);
+
+ALTER TABLE debates ADD COLUMN run_id TEXT;
+ALTER TABLE users ADD COLUMN run_id TEXT;
+ALTER TABLE responses ADD COLUMN run_id TEXT;
+ALTER TABLE "references" ADD COLUMN run_id TEXT;
+ALTER TABLE votes ADD COLUMN run_id TEXT;
+ALTER TABLE blogs ADD COLUMN run_id TEXT;
+ALTER TABLE personas ADD COLUMN run_id TEXT;
+ALTER TABLE learned ADD COLUMN run_id TEXT;
+ALTER TABLE replies ADD COLUMN run_id TEXT;
+ALTER TABLE authorships ADD COLUMN run_id TEXT;
+ALTER TABLE edges ADD COLUMN run_id TEXT;
+
+CREATE INDEX debates_run_id ON debates (run_id);
+CREATE INDEX users_run_id ON users (run_id);
+CREATE INDEX responses_run_id ON responses (run_id);
+CREATE INDEX references_run_id ON "references" (run_id);
+CREATE INDEX votes_run_id ON votes (run_id);
+CREATE INDEX blogs_run_id ON blogs (run_id);
+CREATE INDEX personas_run_id ON personas (run_id);
+CREATE INDEX learned_run_id ON learned (run_id);
+CREATE INDEX replies_run_id ON replies (run_id);
+CREATE INDEX authorships_run_id ON authorships (run_id);
+CREATE INDEX edges_run_id ON edges (run_id);
@@ -0,0 +1,3 @@
+-- Time each response was stored at, in milliseconds, the latency of a reply is the gap between it and its parent
+
+ALTER TABLE responses ADD COLUMN created_at INTEGER NOT NULL DEFAULT 0;
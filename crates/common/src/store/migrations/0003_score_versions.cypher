@@ -0,0 +1,5 @@
+// Score versions, the scores of a run recomputed under a scoring policy
+
+CREATE CONSTRAINT score_version_id IF NOT EXISTS FOR (n:ScoreVersion) REQUIRE n.id IS UNIQUE;
+
+CREATE INDEX score_version_run_id IF NOT EXISTS FOR (n:ScoreVersion) ON (n.run_id);
@@ -0,0 +1,3 @@
+-- Id of the scoring policy each response was last scored under
+
+ALTER TABLE responses ADD COLUMN scoring_policy TEXT NOT NULL DEFAULT '';
@@ -0,0 +1,23 @@
+-- Scores of a run recomputed under a scoring policy, one version per rescoring, kept side by side
+
+CREATE TABLE score_versions (
+    id TEXT PRIMARY KEY,
+    run_id TEXT NOT NULL,
+    policy TEXT NOT NULL DEFAULT '',
+    scored_at INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE scores (
+    version_id TEXT NOT NULL,
+    entity_id TEXT NOT NULL,
+    label TEXT NOT NULL,
+    content INTEGER NOT NULL DEFAULT 0,
+    engagement INTEGER NOT NULL DEFAULT 0,
+    score INTEGER NOT NULL DEFAULT 0,
+    run_id TEXT,
+    PRIMARY KEY (version_id, entity_id)
+);
+
+CREATE INDEX score_versions_run_id ON score_versions (run_id);
+CREATE INDEX scores_entity_id ON scores (entity_id);
+CREATE INDEX scores_run_id ON scores (run_id);
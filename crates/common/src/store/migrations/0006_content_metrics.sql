@@ -0,0 +1,7 @@
+-- Content metrics computed locally, next to the LLM relevance and soundness
+
+ALTER TABLE responses ADD COLUMN sentence_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE responses ADD COLUMN readability REAL NOT NULL DEFAULT 0;
+ALTER TABLE responses ADD COLUMN lexical_diversity REAL NOT NULL DEFAULT 0;
+ALTER TABLE responses ADD COLUMN statistic_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE responses ADD COLUMN spelling_errors INTEGER NOT NULL DEFAULT 0;
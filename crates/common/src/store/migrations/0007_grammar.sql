@@ -0,0 +1,4 @@
+-- Grammar of each response, and the LLM sub-scorers whose prompt failed when it was scored
+
+ALTER TABLE responses ADD COLUMN grammar REAL NOT NULL DEFAULT 0;
+ALTER TABLE responses ADD COLUMN failed_scorers TEXT NOT NULL DEFAULT '[]';
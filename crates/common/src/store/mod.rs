@@ -0,0 +1,428 @@
+use crate::{
+    errors::PulpError,
+    models::{
+        Attributes, Blog, Debate, Engagements, Learned, PersonasUser, Reference, Response, Score,
+        ScoreVersion, SimulationRun, User, Vote,
+    },
+};
+use async_trait::async_trait;
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+pub mod batch;
+pub mod cypher;
+pub mod memory;
+pub mod neo4j;
+pub mod node;
+pub mod sqlite;
+
+pub use batch::{BatchedStore, Write};
+pub use memory::MemoryStore;
+pub use neo4j::{Neo4jStore, PropertyMigration};
+pub use node::{FromRow, NodeEntity};
+pub use sqlite::SqliteStore;
+
+/// Type of the edge between two stored entities
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Edge {
+    Participated,
+    Responded,
+    Replied,
+    Response,
+    HasReference,
+    Referenced,
+    VotedOn,
+    Learned,
+    LearnedIn,
+    LearnedFrom,
+    Scored,
+}
+
+impl Edge {
+    /// Name of the edge in the graph, i.e. `HAS_REFERENCE`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Edge::Participated => "PARTICIPATED",
+            Edge::Responded => "RESPONDED",
+            Edge::Replied => "REPLIED",
+            Edge::Response => "RESPONSE",
+            Edge::HasReference => "HAS_REFERENCE",
+            Edge::Referenced => "REFERENCED",
+            Edge::VotedOn => "VOTED_ON",
+            Edge::Learned => "LEARNED",
+            Edge::LearnedIn => "LEARNED_IN",
+            Edge::LearnedFrom => "LEARNED_FROM",
+            Edge::Scored => "SCORED",
+        }
+    }
+}
+
+/// A relationship between two stored entities, named after the edge it creates
+#[derive(Debug, Clone, PartialEq)]
+pub enum Relationship {
+    /// `(User)-[:PARTICIPATED]->(Debate)`
+    Participated { user_id: String, debate_id: String },
+
+    /// `(Debate)-[:PARTICIPATED]->(PersonaUser)`
+    PersonaParticipated {
+        persona_id: String,
+        debate_id: String,
+    },
+
+    /// `(User)-[:RESPONDED]->(Response)`
+    Responded {
+        user_id: String,
+        response_id: String,
+    },
+
+    /// `(PersonaUser)-[:RESPONDED]->(Response)`
+    PersonaResponded {
+        persona_id: String,
+        response_id: String,
+    },
+
+    /// `(Response)-[:REPLIED]->(Response)`
+    Replied {
+        response_id: String,
+        reply_id: String,
+    },
+
+    /// `(Response)-[:RESPONSE]->(Debate)`
+    DebateResponse {
+        response_id: String,
+        debate_id: String,
+    },
+
+    /// `(Response)-[:HAS_REFERENCE]->(Reference)`
+    HasReference {
+        response_id: String,
+        reference_id: String,
+    },
+
+    /// `(Response)-[:REFERENCED]->(Reference)`
+    Referenced {
+        response_id: String,
+        reference_id: String,
+    },
+
+    /// `(PersonaUser)-[:LEARNED]->(Learned)`
+    Learned {
+        persona_id: String,
+        learned_id: String,
+    },
+
+    /// `(Learned)-[:LEARNED_IN]->(Debate)`
+    LearnedIn {
+        learned_id: String,
+        debate_id: String,
+    },
+
+    /// `(Learned)-[:LEARNED_FROM]->(Response)`
+    LearnedFrom {
+        learned_id: String,
+        response_id: String,
+    },
+}
+
+impl Relationship {
+    pub fn edge(&self) -> Edge {
+        match self {
+            Relationship::Participated { .. } | Relationship::PersonaParticipated { .. } => {
+                Edge::Participated
+            }
+            Relationship::Responded { .. } | Relationship::PersonaResponded { .. } => {
+                Edge::Responded
+            }
+            Relationship::Replied { .. } => Edge::Replied,
+            Relationship::DebateResponse { .. } => Edge::Response,
+            Relationship::HasReference { .. } => Edge::HasReference,
+            Relationship::Referenced { .. } => Edge::Referenced,
+            Relationship::Learned { .. } => Edge::Learned,
+            Relationship::LearnedIn { .. } => Edge::LearnedIn,
+            Relationship::LearnedFrom { .. } => Edge::LearnedFrom,
+        }
+    }
+
+    /// Ids of the entities the edge goes from and to
+    pub fn ends(&self) -> (&str, &str) {
+        match self {
+            Relationship::Participated { user_id, debate_id } => (user_id, debate_id),
+            Relationship::PersonaParticipated {
+                persona_id,
+                debate_id,
+            } => (debate_id, persona_id),
+            Relationship::Responded {
+                user_id,
+                response_id,
+            } => (user_id, response_id),
+            Relationship::PersonaResponded {
+                persona_id,
+                response_id,
+            } => (persona_id, response_id),
+            Relationship::Replied {
+                response_id,
+                reply_id,
+            } => (response_id, reply_id),
+            Relationship::DebateResponse {
+                response_id,
+                debate_id,
+            } => (response_id, debate_id),
+            Relationship::HasReference {
+                response_id,
+                reference_id,
+            }
+            | Relationship::Referenced {
+                response_id,
+                reference_id,
+            } => (response_id, reference_id),
+            Relationship::Learned {
+                persona_id,
+                learned_id,
+            } => (persona_id, learned_id),
+            Relationship::LearnedIn {
+                learned_id,
+                debate_id,
+            } => (learned_id, debate_id),
+            Relationship::LearnedFrom {
+                learned_id,
+                response_id,
+            } => (learned_id, response_id),
+        }
+    }
+
+    /// Labels the entities the edge goes from and to must have, an edge between entities with other
+    /// labels is not stored
+    pub fn labels(&self) -> (&'static str, &'static str) {
+        match self {
+            Relationship::Participated { .. } => (User::LABEL, Debate::LABEL),
+            Relationship::PersonaParticipated { .. } => (Debate::LABEL, PersonasUser::LABEL),
+            Relationship::Responded { .. } => (User::LABEL, Response::LABEL),
+            Relationship::PersonaResponded { .. } => (PersonasUser::LABEL, Response::LABEL),
+            Relationship::Replied { .. } => (Response::LABEL, Response::LABEL),
+            Relationship::DebateResponse { .. } => (Response::LABEL, Debate::LABEL),
+            Relationship::HasReference { .. } | Relationship::Referenced { .. } => {
+                (Response::LABEL, Reference::LABEL)
+            }
+            Relationship::Learned { .. } => (PersonasUser::LABEL, Learned::LABEL),
+            Relationship::LearnedIn { .. } => (Learned::LABEL, Debate::LABEL),
+            Relationship::LearnedFrom { .. } => (Learned::LABEL, Response::LABEL),
+        }
+    }
+}
+
+/// Where simulations persist debates, the responses to them and everything hanging off of them.
+///
+/// `create_*` methods return the id of the new entity, a new one unless the entity passed in already has one.
+/// Responses are stored with the time they were created at, unless the response passed in already has one.
+#[async_trait]
+pub trait Store: Send + Sync {
+    async fn create_debate(&self, debate: &Debate) -> Result<String, PulpError>;
+
+    async fn get_debate(&self, id: &str) -> Result<Debate, PulpError>;
+
+    async fn update_debate(&self, debate: &Debate) -> Result<(), PulpError>;
+
+    async fn update_debate_commenters(&self, id: &str, count: i64) -> Result<(), PulpError>;
+
+    async fn update_debate_voters(&self, id: &str, count: i64) -> Result<(), PulpError>;
+
+    async fn update_debate_responses(&self, id: &str, count: i64) -> Result<(), PulpError>;
+
+    async fn delete_debate(&self, id: &str) -> Result<(), PulpError>;
+
+    async fn create_user(&self, user: &User) -> Result<String, PulpError>;
+
+    async fn get_user(&self, id: &str) -> Result<User, PulpError>;
+
+    async fn get_all_users(&self) -> Result<Vec<User>, PulpError>;
+
+    async fn update_user(&self, user: &User) -> Result<(), PulpError>;
+
+    async fn delete_user(&self, id: &str) -> Result<(), PulpError>;
+
+    async fn create_response(&self, response: &Response) -> Result<String, PulpError>;
+
+    async fn get_response(&self, id: &str) -> Result<Response, PulpError>;
+
+    async fn update_response(&self, response: &Response) -> Result<(), PulpError>;
+
+    /// Sets the score of a response and the id of the scoring policy it was scored under
+    async fn update_response_score(
+        &self,
+        id: &str,
+        score: i64,
+        policy: &str,
+    ) -> Result<(), PulpError>;
+
+    /// Sets the valid, invalid and abstain vote counts of a response
+    async fn update_response_votes(
+        &self,
+        id: &str,
+        valid: i64,
+        invalid: i64,
+        abstain: i64,
+    ) -> Result<(), PulpError>;
+
+    /// Sets the ethos, pathos and logos of a response
+    async fn update_response_appeals(
+        &self,
+        id: &str,
+        ethos: f64,
+        pathos: f64,
+        logos: f64,
+    ) -> Result<(), PulpError>;
+
+    /// Sets the attributes of a response, its LLM scores and content metrics
+    async fn update_response_attributes(
+        &self,
+        id: &str,
+        attributes: &Attributes,
+    ) -> Result<(), PulpError>;
+
+    /// Sets the engagement breakdown of a response
+    async fn update_response_engagements(
+        &self,
+        id: &str,
+        engagements: &Engagements,
+    ) -> Result<(), PulpError>;
+
+    async fn delete_response(&self, id: &str) -> Result<(), PulpError>;
+
+    async fn create_reference(&self, reference: &Reference) -> Result<String, PulpError>;
+
+    async fn get_reference(&self, id: &str) -> Result<Reference, PulpError>;
+
+    async fn update_reference(&self, reference: &Reference) -> Result<(), PulpError>;
+
+    async fn delete_reference(&self, id: &str) -> Result<(), PulpError>;
+
+    /// Creates the vote and links it to the response it was cast upon
+    async fn create_vote(&self, vote: &Vote) -> Result<String, PulpError>;
+
+    /// Votes cast upon the response with `id`
+    async fn votes(&self, id: &str) -> Result<Vec<Vote>, PulpError>;
+
+    async fn create_blog(&self, blog: &Blog) -> Result<String, PulpError>;
+
+    async fn get_blog(&self, id: &str) -> Result<Blog, PulpError>;
+
+    async fn update_blog(&self, blog: &Blog) -> Result<(), PulpError>;
+
+    async fn delete_blog(&self, id: &str) -> Result<(), PulpError>;
+
+    async fn create_personas_user(&self, user: &PersonasUser) -> Result<String, PulpError>;
+
+    async fn get_personas_user(&self, id: &str) -> Result<PersonasUser, PulpError>;
+
+    async fn get_all_personas_users(&self) -> Result<Vec<PersonasUser>, PulpError>;
+
+    async fn update_personas_user(&self, user: &PersonasUser) -> Result<(), PulpError>;
+
+    async fn delete_personas_user(&self, id: &str) -> Result<(), PulpError>;
+
+    async fn create_learned(&self, learned: &Learned) -> Result<String, PulpError>;
+
+    async fn get_learned(&self, id: &str) -> Result<Learned, PulpError>;
+
+    async fn update_learned(&self, learned: &Learned) -> Result<(), PulpError>;
+
+    async fn delete_learned(&self, id: &str) -> Result<(), PulpError>;
+
+    /// Records `run` and tags every entity and relationship written from now on with its id
+    async fn start_run(&self, run: &SimulationRun) -> Result<(), PulpError>;
+
+    async fn get_run(&self, id: &str) -> Result<SimulationRun, PulpError>;
+
+    /// Every recorded run, oldest first
+    async fn get_all_runs(&self) -> Result<Vec<SimulationRun>, PulpError>;
+
+    /// How many entities of each label the run with `id` wrote, i.e. `("Response", 40)`, sorted by label
+    /// and followed by the number of `relationships` it wrote
+    async fn run_counts(&self, id: &str) -> Result<Vec<(String, i64)>, PulpError>;
+
+    /// Deletes the run with `id` along with every entity and relationship it wrote, entities of other runs
+    /// only lose their relationships to the deleted ones
+    async fn delete_run(&self, id: &str) -> Result<(), PulpError>;
+
+    /// Ids of the entities with `label`, i.e. `Debate`, the run with `id` wrote
+    async fn run_entities(&self, id: &str, label: &str) -> Result<Vec<String>, PulpError>;
+
+    /// Records `scores` as a new version of the scores of a run, side by side with the scores written
+    /// during the run and every earlier version, and returns its id
+    async fn create_score_version(
+        &self,
+        version: &ScoreVersion,
+        scores: &[Score],
+    ) -> Result<String, PulpError>;
+
+    /// Every score version of the run with `id`, oldest first
+    async fn get_score_versions(&self, id: &str) -> Result<Vec<ScoreVersion>, PulpError>;
+
+    /// Scores of the score version with `id`
+    async fn get_scores(&self, id: &str) -> Result<Vec<Score>, PulpError>;
+
+    /// Links two existing entities, nothing is linked when either of them does not exist
+    async fn relate(&self, relationship: Relationship) -> Result<(), PulpError>;
+
+    /// Ids of the entities `id` has an `edge` to
+    async fn outgoing(&self, id: &str, edge: Edge) -> Result<Vec<String>, PulpError>;
+
+    /// Ids of the entities with an `edge` to `id`
+    async fn incoming(&self, id: &str, edge: Edge) -> Result<Vec<String>, PulpError>;
+
+    /// Applies `writes` in order, stores able to send them in bulk override it
+    async fn write(&self, writes: Vec<Write>) -> Result<(), PulpError> {
+        for write in writes {
+            write.apply(self).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Sends the writes still buffered to the database, a no-op for stores that write straight away
+    async fn flush(&self) -> Result<(), PulpError> {
+        Ok(())
+    }
+
+    /// Direct replies to the response with `id`
+    async fn replies(&self, id: &str) -> Result<Vec<Response>, PulpError> {
+        let mut replies = Vec::new();
+        for reply_id in self.outgoing(id, Edge::Replied).await? {
+            replies.push(self.get_response(&reply_id).await?);
+        }
+
+        Ok(replies)
+    }
+
+    /// Top level responses of the debate with `id`
+    async fn debate_responses(&self, id: &str) -> Result<Vec<Response>, PulpError> {
+        let mut responses = Vec::new();
+        for response_id in self.incoming(id, Edge::Response).await? {
+            responses.push(self.get_response(&response_id).await?);
+        }
+
+        Ok(responses)
+    }
+}
+
+/// The id to create an entity under, `id` when the caller already picked one
+pub(crate) fn new_id(id: &str) -> String {
+    if id.is_empty() {
+        Uuid::new_v4().to_string()
+    } else {
+        id.to_string()
+    }
+}
+
+/// The time, in milliseconds, to create a response at, `at` when the caller already picked one, as
+/// simulations do from their clock, and the wall clock otherwise
+pub(crate) fn created_at(at: i64) -> i64 {
+    if at > 0 {
+        return at;
+    }
+
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
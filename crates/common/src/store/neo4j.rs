@@ -0,0 +1,1306 @@
+use super::{
+    batch::{Entity, Write},
+    created_at,
+    cypher::{Cypher, Pattern},
+    new_id,
+    node::{FromRow, NodeEntity},
+    Edge, Relationship, Store,
+};
+use crate::{
+    errors::{PulpError, SimulationError},
+    models::{
+        vote::VoteType, Attributes, Blog, Debate, Engagements, Learned, PersonasUser, Reference,
+        Response, Score, ScoreVersion, SimulationRun, User, Vote,
+    },
+};
+use async_trait::async_trait;
+use neo4rs::{BoltMap, BoltType, Graph, Query, Row};
+use std::sync::{Arc, Mutex};
+
+/// Schema migrations in the order they are applied, `;` separated statements each run in a transaction of
+/// their own. The `version` of the `SchemaVersion` node is the number applied so far
+const MIGRATIONS: &[&str] = &[
+    include_str!("migrations/0001_constraints.cypher"),
+    include_str!("migrations/0002_run_indexes.cypher"),
+    include_str!("migrations/0003_score_versions.cypher"),
+];
+
+/// Label, and id, of the single node recording the schema version
+const SCHEMA_VERSION: &str = "SchemaVersion";
+
+/// Label of every node a run can write
+const RUN_LABELS: &[&str] = &[
+    Debate::LABEL,
+    User::LABEL,
+    Response::LABEL,
+    Reference::LABEL,
+    "Vote",
+    Blog::LABEL,
+    PersonasUser::LABEL,
+    Learned::LABEL,
+    ScoreVersion::LABEL,
+];
+
+/// What converting one string typed property did
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyMigration {
+    /// `Label.property`
+    pub property: String,
+
+    /// Number of nodes whose value was converted to its native type
+    pub converted: i64,
+
+    /// Ids of the nodes whose value doesn't parse and is still a string, reading them fails
+    pub unconvertible: Vec<String>,
+}
+
+/// Stores entities as nodes of a Neo4j graph
+#[derive(Clone)]
+pub struct Neo4jStore {
+    graph: Arc<Graph>,
+
+    /// Id of the run every node and relationship is written in, if one was started
+    run: Arc<Mutex<Option<String>>>,
+}
+
+impl Neo4jStore {
+    pub fn new(graph: Arc<Graph>) -> Self {
+        Self {
+            graph,
+            run: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub async fn connect(uri: &str, user: &str, password: &str) -> Result<Self, PulpError> {
+        let graph = Graph::new(uri, user, password).await.map_err(neo4j_error)?;
+
+        Ok(Self::new(Arc::new(graph)))
+    }
+
+    pub fn graph(&self) -> Arc<Graph> {
+        self.graph.clone()
+    }
+
+    /// Number of schema migrations applied to the graph, 0 for a graph that was never initialised
+    pub async fn schema_version(&self) -> Result<usize, PulpError> {
+        let q = Cypher::new()
+            .matching(Pattern::new("s").label(SCHEMA_VERSION).id("id"))
+            .returning("s.version AS version")
+            .query()
+            .param("id", SCHEMA_VERSION);
+
+        let version = self
+            .rows(q)
+            .await?
+            .first()
+            .and_then(|row| row.get::<i64>("version"))
+            .unwrap_or_default();
+
+        Ok(version as usize)
+    }
+
+    /// Applies the schema migrations the graph is missing in order and returns their versions,
+    /// nothing is applied to a graph that is up to date
+    pub async fn init_schema(&self) -> Result<Vec<usize>, PulpError> {
+        let applied = self.schema_version().await?;
+
+        if applied > MIGRATIONS.len() {
+            return Err(neo4j_error(format!(
+                "graph schema version {} is newer than the {} known migrations",
+                applied,
+                MIGRATIONS.len()
+            )));
+        }
+
+        let mut versions = Vec::new();
+        for (i, migration) in MIGRATIONS.iter().enumerate().skip(applied) {
+            let version = i + 1;
+
+            // Neo4j doesn't mix schema changes with other writes in one transaction
+            for statement in statements(migration) {
+                let tx = self.graph.start_txn().await.map_err(neo4j_error)?;
+                tx.execute(Query::new(statement))
+                    .await
+                    .map_err(|e| neo4j_error(format!("migration {} failed: {}", version, e)))?;
+                tx.commit().await.map_err(neo4j_error)?;
+            }
+
+            let q = Cypher::new()
+                .merging(Pattern::new("s").label(SCHEMA_VERSION).id("id"))
+                .set("s", &["version"])
+                .query()
+                .param("id", SCHEMA_VERSION)
+                .param("version", version as i64);
+
+            self.run(q).await?;
+
+            versions.push(version);
+        }
+
+        Ok(versions)
+    }
+
+    /// Converts numeric and boolean properties older versions wrote as strings to their native types,
+    /// returning for each `Label.property` how many nodes were converted and which could not be
+    pub async fn migrate_property_types(&self) -> Result<Vec<PropertyMigration>, PulpError> {
+        let mut migrated = Vec::new();
+
+        migrated.extend(self.migrate_property_types_of::<Debate>().await?);
+        migrated.extend(self.migrate_property_types_of::<User>().await?);
+        migrated.extend(self.migrate_property_types_of::<Response>().await?);
+        migrated.extend(self.migrate_property_types_of::<Reference>().await?);
+        migrated.extend(self.migrate_property_types_of::<Blog>().await?);
+        migrated.extend(self.migrate_property_types_of::<PersonasUser>().await?);
+        migrated.extend(self.migrate_property_types_of::<Learned>().await?);
+
+        Ok(migrated)
+    }
+
+    fn run_id(&self) -> Option<String> {
+        self.run.lock().unwrap().clone()
+    }
+
+    /// Runs `q` in its own transaction
+    async fn run(&self, q: Query) -> Result<(), PulpError> {
+        let tx = self.graph.start_txn().await.map_err(neo4j_error)?;
+
+        tx.execute(q).await.map_err(neo4j_error)?;
+
+        tx.commit().await.map_err(neo4j_error)
+    }
+
+    /// Runs every query in a single transaction
+    async fn run_all(&self, queries: Vec<Query>) -> Result<(), PulpError> {
+        let tx = self.graph.start_txn().await.map_err(neo4j_error)?;
+
+        for q in queries {
+            tx.execute(q).await.map_err(neo4j_error)?;
+        }
+
+        tx.commit().await.map_err(neo4j_error)
+    }
+
+    /// Runs `q` in its own transaction and collects the rows it returns
+    async fn rows(&self, q: Query) -> Result<Vec<Row>, PulpError> {
+        let tx = self.graph.start_txn().await.map_err(neo4j_error)?;
+
+        let mut rows = Vec::new();
+        let mut res = tx.execute(q).await.map_err(neo4j_error)?;
+        while let Some(row) = res.next().await.map_err(neo4j_error)? {
+            rows.push(row);
+        }
+
+        tx.commit().await.map_err(neo4j_error)?;
+
+        Ok(rows)
+    }
+
+    /// Creates `entity` and returns its id
+    async fn create<T: NodeEntity>(&self, entity: &T) -> Result<String, PulpError> {
+        let id = new_id(entity.id());
+
+        let run = self.run_id();
+
+        let mut properties = vec!["id"];
+        properties.extend(T::PROPERTIES);
+        if run.is_some() {
+            properties.push("run_id");
+        }
+
+        let mut q = Cypher::new()
+            .create("n", T::LABEL, &properties)
+            .returning("n.id")
+            .query();
+        if let Some(run) = run {
+            q = q.param("run_id", run);
+        }
+
+        self.run(entity.bind(q).param("id", id.clone())).await?;
+
+        Ok(id)
+    }
+
+    /// The `T` node with `id`, not finding it is an error
+    async fn get<T: NodeEntity>(&self, name: &str, id: &str) -> Result<T, PulpError> {
+        let q = Cypher::new()
+            .matching(Pattern::new("n").label(T::LABEL).id("id"))
+            .returning("n")
+            .query()
+            .param("id", id);
+
+        match self.rows(q).await?.first() {
+            Some(row) => T::from_row(row, "n"),
+
+            None => Err(PulpError::SimulationError(SimulationError::StoreError(
+                format!("{} {} not found", name, id),
+            ))),
+        }
+    }
+
+    async fn get_all<T: NodeEntity>(&self) -> Result<Vec<T>, PulpError> {
+        let q = Cypher::new()
+            .matching(Pattern::new("n").label(T::LABEL))
+            .returning("n")
+            .query();
+
+        self.rows(q)
+            .await?
+            .iter()
+            .map(|row| T::from_row(row, "n"))
+            .collect()
+    }
+
+    /// Overwrites every property of the stored `entity`
+    async fn update<T: NodeEntity>(&self, entity: &T) -> Result<(), PulpError> {
+        let q = Cypher::new()
+            .matching(Pattern::new("n").label(T::LABEL).id("id"))
+            .set("n", T::PROPERTIES)
+            .query();
+
+        self.run(entity.bind(q).param("id", entity.id())).await
+    }
+
+    /// Overwrites some of the properties of the `T` node with `id`
+    async fn update_properties<T: NodeEntity>(
+        &self,
+        id: &str,
+        properties: Vec<(&str, BoltType)>,
+    ) -> Result<(), PulpError> {
+        let names = properties.iter().map(|(p, _)| *p).collect::<Vec<_>>();
+        debug_assert!(
+            names.iter().all(|p| T::PROPERTIES.contains(p)),
+            "{} has no property among {:?}",
+            T::LABEL,
+            names
+        );
+
+        let q = Cypher::new()
+            .matching(Pattern::new("n").label(T::LABEL).id("id"))
+            .set("n", &names)
+            .query()
+            .param("id", id);
+
+        let q = properties
+            .into_iter()
+            .fold(q, |q, (property, value)| q.param(property, value));
+
+        self.run(q).await
+    }
+
+    async fn delete<T: NodeEntity>(&self, id: &str) -> Result<(), PulpError> {
+        let q = Cypher::new()
+            .matching(Pattern::new("n").label(T::LABEL).id("id"))
+            .detach_delete("n")
+            .query()
+            .param("id", id);
+
+        self.run(q).await
+    }
+
+    /// Converts the properties of every `T` node older versions wrote as strings to their native type
+    async fn migrate_property_types_of<T: NodeEntity>(
+        &self,
+    ) -> Result<Vec<PropertyMigration>, PulpError> {
+        let mut migrated = Vec::new();
+
+        for (property, cast) in T::string_casts() {
+            let converted = self
+                .rows(converting::<T>(property, cast).query())
+                .await?
+                .first()
+                .and_then(|row| row.get::<i64>("converted"))
+                .unwrap_or_default();
+
+            let unconvertible = self
+                .rows(unconvertible::<T>(property, cast).query())
+                .await?
+                .iter()
+                .filter_map(|row| row.get::<String>("id"))
+                .collect();
+
+            migrated.push(PropertyMigration {
+                property: format!("{}.{}", T::LABEL, property),
+                converted,
+                unconvertible,
+            });
+        }
+
+        Ok(migrated)
+    }
+
+    /// Ids of the `n` nodes on the far end of `edge` from the node with `id`
+    async fn linked(
+        &self,
+        id: &str,
+        from: Pattern,
+        edge: Edge,
+        to: Pattern,
+    ) -> Result<Vec<String>, PulpError> {
+        let q = Cypher::new()
+            .matching_edge(from, edge, to)
+            .returning("n.id")
+            .query()
+            .param("id", id);
+
+        Ok(self
+            .rows(q)
+            .await?
+            .into_iter()
+            .filter_map(|row| row.get::<String>("n.id"))
+            .collect())
+    }
+}
+
+#[async_trait]
+impl Store for Neo4jStore {
+    async fn create_debate(&self, debate: &Debate) -> Result<String, PulpError> {
+        self.create(debate).await
+    }
+
+    async fn get_debate(&self, id: &str) -> Result<Debate, PulpError> {
+        self.get("debate", id).await
+    }
+
+    async fn update_debate(&self, debate: &Debate) -> Result<(), PulpError> {
+        self.update(debate).await
+    }
+
+    async fn update_debate_commenters(&self, id: &str, count: i64) -> Result<(), PulpError> {
+        self.update_properties::<Debate>(id, vec![("commenters", count.into())])
+            .await
+    }
+
+    async fn update_debate_voters(&self, id: &str, count: i64) -> Result<(), PulpError> {
+        self.update_properties::<Debate>(id, vec![("voters", count.into())])
+            .await
+    }
+
+    async fn update_debate_responses(&self, id: &str, count: i64) -> Result<(), PulpError> {
+        self.update_properties::<Debate>(id, vec![("responses", count.into())])
+            .await
+    }
+
+    async fn delete_debate(&self, id: &str) -> Result<(), PulpError> {
+        self.delete::<Debate>(id).await
+    }
+
+    async fn create_user(&self, user: &User) -> Result<String, PulpError> {
+        self.create(user).await
+    }
+
+    async fn get_user(&self, id: &str) -> Result<User, PulpError> {
+        self.get("user", id).await
+    }
+
+    async fn get_all_users(&self) -> Result<Vec<User>, PulpError> {
+        self.get_all().await
+    }
+
+    async fn update_user(&self, user: &User) -> Result<(), PulpError> {
+        self.update(user).await
+    }
+
+    async fn delete_user(&self, id: &str) -> Result<(), PulpError> {
+        self.delete::<User>(id).await
+    }
+
+    async fn create_response(&self, response: &Response) -> Result<String, PulpError> {
+        self.create(&Response {
+            created_at: created_at(response.created_at),
+            ..response.clone()
+        })
+        .await
+    }
+
+    async fn get_response(&self, id: &str) -> Result<Response, PulpError> {
+        self.get("response", id).await
+    }
+
+    async fn update_response(&self, response: &Response) -> Result<(), PulpError> {
+        self.update(response).await
+    }
+
+    async fn update_response_score(
+        &self,
+        id: &str,
+        score: i64,
+        policy: &str,
+    ) -> Result<(), PulpError> {
+        self.update_properties::<Response>(
+            id,
+            vec![("score", score.into()), ("scoring_policy", policy.into())],
+        )
+        .await
+    }
+
+    async fn update_response_votes(
+        &self,
+        id: &str,
+        valid: i64,
+        invalid: i64,
+        abstain: i64,
+    ) -> Result<(), PulpError> {
+        self.update_properties::<Response>(
+            id,
+            vec![
+                ("valid_vote_count", valid.into()),
+                ("invalid_vote_count", invalid.into()),
+                ("abstain_vote_count", abstain.into()),
+            ],
+        )
+        .await
+    }
+
+    async fn update_response_appeals(
+        &self,
+        id: &str,
+        ethos: f64,
+        pathos: f64,
+        logos: f64,
+    ) -> Result<(), PulpError> {
+        self.update_properties::<Response>(
+            id,
+            vec![
+                ("ethos", ethos.into()),
+                ("pathos", pathos.into()),
+                ("logos", logos.into()),
+            ],
+        )
+        .await
+    }
+
+    async fn update_response_attributes(
+        &self,
+        id: &str,
+        attributes: &Attributes,
+    ) -> Result<(), PulpError> {
+        self.update_properties::<Response>(id, attribute_properties(attributes))
+            .await
+    }
+
+    async fn update_response_engagements(
+        &self,
+        id: &str,
+        engagements: &Engagements,
+    ) -> Result<(), PulpError> {
+        self.update_properties::<Response>(id, engagement_properties(engagements))
+            .await
+    }
+
+    async fn delete_response(&self, id: &str) -> Result<(), PulpError> {
+        self.delete::<Response>(id).await
+    }
+
+    async fn create_reference(&self, reference: &Reference) -> Result<String, PulpError> {
+        self.create(reference).await
+    }
+
+    async fn get_reference(&self, id: &str) -> Result<Reference, PulpError> {
+        self.get("reference", id).await
+    }
+
+    async fn update_reference(&self, reference: &Reference) -> Result<(), PulpError> {
+        self.update(reference).await
+    }
+
+    async fn delete_reference(&self, id: &str) -> Result<(), PulpError> {
+        self.delete::<Reference>(id).await
+    }
+
+    async fn create_vote(&self, vote: &Vote) -> Result<String, PulpError> {
+        let id = new_id(&vote.id);
+        let run = self.run_id();
+
+        let mut properties = vec![
+            "id",
+            "vote_type",
+            "reason",
+            "debate_id",
+            "prompt_name",
+            "prompt_version",
+        ];
+        let cypher =
+            Cypher::new().matching(Pattern::new("r").label(Response::LABEL).id("response_id"));
+        let cypher = match run {
+            Some(_) => {
+                properties.push("run_id");
+                cypher.create("v", "Vote", &properties).link_with(
+                    "v",
+                    Edge::VotedOn,
+                    "r",
+                    &[("run_id", "$run_id")],
+                )
+            }
+            None => cypher
+                .create("v", "Vote", &properties)
+                .link("v", Edge::VotedOn, "r"),
+        };
+
+        let mut q = cypher
+            .query()
+            .param("id", id.clone())
+            .param("vote_type", vote.vote_type.to_string())
+            .param("reason", vote.vote_type.reason().unwrap_or_default())
+            .param("debate_id", vote.debate_id.clone())
+            .param("response_id", vote.response_id.clone())
+            .param("prompt_name", vote.prompt_name.clone())
+            .param("prompt_version", vote.prompt_version.clone());
+        if let Some(run) = run {
+            q = q.param("run_id", run);
+        }
+
+        self.run(q).await?;
+
+        Ok(id)
+    }
+
+    async fn votes(&self, id: &str) -> Result<Vec<Vote>, PulpError> {
+        let q = Cypher::new()
+            .matching_edge(
+                Pattern::new("v").label("Vote"),
+                Edge::VotedOn,
+                Pattern::new("r").label(Response::LABEL).id("id"),
+            )
+            .returning("v.id AS id, v.vote_type AS vote_type, v.reason AS reason, v.debate_id AS debate_id, v.prompt_name AS prompt_name, v.prompt_version AS prompt_version")
+            .query()
+            .param("id", id);
+
+        Ok(self
+            .rows(q)
+            .await?
+            .into_iter()
+            .map(|row| {
+                let column = |name: &str| row.get::<String>(name).unwrap_or_default();
+
+                Vote::new(
+                    column("id"),
+                    VoteType::with_reason(&column("vote_type"), row.get("reason")),
+                    column("debate_id"),
+                    id.to_string(),
+                    column("prompt_name"),
+                    column("prompt_version"),
+                )
+            })
+            .collect())
+    }
+
+    async fn create_blog(&self, blog: &Blog) -> Result<String, PulpError> {
+        self.create(blog).await
+    }
+
+    async fn get_blog(&self, id: &str) -> Result<Blog, PulpError> {
+        self.get("blog", id).await
+    }
+
+    async fn update_blog(&self, blog: &Blog) -> Result<(), PulpError> {
+        self.update(blog).await
+    }
+
+    async fn delete_blog(&self, id: &str) -> Result<(), PulpError> {
+        self.delete::<Blog>(id).await
+    }
+
+    async fn create_personas_user(&self, user: &PersonasUser) -> Result<String, PulpError> {
+        self.create(user).await
+    }
+
+    async fn get_personas_user(&self, id: &str) -> Result<PersonasUser, PulpError> {
+        self.get("persona", id).await
+    }
+
+    async fn get_all_personas_users(&self) -> Result<Vec<PersonasUser>, PulpError> {
+        self.get_all().await
+    }
+
+    async fn update_personas_user(&self, user: &PersonasUser) -> Result<(), PulpError> {
+        self.update(user).await
+    }
+
+    async fn delete_personas_user(&self, id: &str) -> Result<(), PulpError> {
+        self.delete::<PersonasUser>(id).await
+    }
+
+    async fn create_learned(&self, learned: &Learned) -> Result<String, PulpError> {
+        self.create(learned).await
+    }
+
+    async fn get_learned(&self, id: &str) -> Result<Learned, PulpError> {
+        self.get("learned", id).await
+    }
+
+    async fn update_learned(&self, learned: &Learned) -> Result<(), PulpError> {
+        self.update(learned).await
+    }
+
+    async fn delete_learned(&self, id: &str) -> Result<(), PulpError> {
+        self.delete::<Learned>(id).await
+    }
+
+    async fn start_run(&self, run: &SimulationRun) -> Result<(), PulpError> {
+        let mut properties = vec!["id"];
+        properties.extend(SimulationRun::PROPERTIES);
+
+        let q = Cypher::new()
+            .create("r", SimulationRun::LABEL, &properties)
+            .query();
+
+        self.run(run.bind(q).param("id", run.id.clone())).await?;
+
+        *self.run.lock().unwrap() = Some(run.id.clone());
+
+        Ok(())
+    }
+
+    async fn get_run(&self, id: &str) -> Result<SimulationRun, PulpError> {
+        self.get("run", id).await
+    }
+
+    async fn get_all_runs(&self) -> Result<Vec<SimulationRun>, PulpError> {
+        let mut runs = self.get_all::<SimulationRun>().await?;
+        runs.sort_by_key(|run| run.started_at);
+
+        Ok(runs)
+    }
+
+    async fn run_counts(&self, id: &str) -> Result<Vec<(String, i64)>, PulpError> {
+        let mut counts = Vec::new();
+        for label in RUN_LABELS {
+            let q = Cypher::new()
+                .matching(Pattern::new("n").label(label).property("run_id", "id"))
+                .returning("count(n) AS count")
+                .query()
+                .param("id", id);
+
+            let count = self
+                .rows(q)
+                .await?
+                .first()
+                .and_then(|row| row.get::<i64>("count"))
+                .unwrap_or_default();
+
+            if count > 0 {
+                counts.push((label.to_string(), count));
+            }
+        }
+        counts.sort();
+
+        let q = Cypher::new()
+            .matching_relationships("r", "run_id", "id")
+            .returning("count(r) AS count")
+            .query()
+            .param("id", id);
+
+        let relationships = self
+            .rows(q)
+            .await?
+            .first()
+            .and_then(|row| row.get::<i64>("count"))
+            .unwrap_or_default();
+        counts.push(("relationships".to_string(), relationships));
+
+        Ok(counts)
+    }
+
+    async fn delete_run(&self, id: &str) -> Result<(), PulpError> {
+        let mut queries = vec![Cypher::new()
+            .matching_relationships("r", "run_id", "id")
+            .delete("r")
+            .query()
+            .param("id", id)];
+
+        for label in RUN_LABELS {
+            queries.push(
+                Cypher::new()
+                    .matching(Pattern::new("n").label(label).property("run_id", "id"))
+                    .detach_delete("n")
+                    .query()
+                    .param("id", id),
+            );
+        }
+
+        queries.push(
+            Cypher::new()
+                .matching(Pattern::new("r").label(SimulationRun::LABEL).id("id"))
+                .detach_delete("r")
+                .query()
+                .param("id", id),
+        );
+
+        self.run_all(queries).await?;
+
+        let mut run = self.run.lock().unwrap();
+        if run.as_deref() == Some(id) {
+            *run = None;
+        }
+
+        Ok(())
+    }
+
+    async fn run_entities(&self, id: &str, label: &str) -> Result<Vec<String>, PulpError> {
+        let q = Cypher::new()
+            .matching(Pattern::new("n").label(label).property("run_id", "id"))
+            .returning("n.id AS id")
+            .query()
+            .param("id", id);
+
+        Ok(self
+            .rows(q)
+            .await?
+            .iter()
+            .filter_map(|row| row.get::<String>("id"))
+            .collect())
+    }
+
+    /// Creates the `ScoreVersion` node and a `SCORED` relationship, holding the score, to every debate
+    /// and response scored, all in one transaction
+    async fn create_score_version(
+        &self,
+        version: &ScoreVersion,
+        scores: &[Score],
+    ) -> Result<String, PulpError> {
+        let id = new_id(&version.id);
+
+        let mut properties = vec!["id"];
+        properties.extend(ScoreVersion::PROPERTIES);
+
+        let mut queries = vec![version
+            .bind(
+                Cypher::new()
+                    .create("v", ScoreVersion::LABEL, &properties)
+                    .query(),
+            )
+            .param("id", id.clone())];
+
+        for label in [Debate::LABEL, Response::LABEL] {
+            let rows = scores
+                .iter()
+                .filter(|score| score.label == label)
+                .map(|score| {
+                    row(
+                        vec![
+                            ("id", score.entity_id.clone().into()),
+                            ("content", score.content.into()),
+                            ("engagement", score.engagement.into()),
+                            ("score", score.score.into()),
+                        ],
+                        Some(&version.run_id),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            if rows.is_empty() {
+                continue;
+            }
+
+            let q = Cypher::new()
+                .matching(Pattern::new("v").label(ScoreVersion::LABEL).id("id"))
+                .unwind("rows", "row")
+                .matching(Pattern::new("n").label(label).id_from("row.id"))
+                .link_with(
+                    "v",
+                    Edge::Scored,
+                    "n",
+                    &[
+                        ("content", "row.content"),
+                        ("engagement", "row.engagement"),
+                        ("score", "row.score"),
+                        ("run_id", "row.run_id"),
+                    ],
+                )
+                .query()
+                .param("id", id.clone())
+                .param("rows", rows);
+
+            queries.push(q);
+        }
+
+        self.run_all(queries).await?;
+
+        Ok(id)
+    }
+
+    async fn get_score_versions(&self, id: &str) -> Result<Vec<ScoreVersion>, PulpError> {
+        let q = Cypher::new()
+            .matching(
+                Pattern::new("v")
+                    .label(ScoreVersion::LABEL)
+                    .property("run_id", "id"),
+            )
+            .returning("v")
+            .query()
+            .param("id", id);
+
+        let mut versions = self
+            .rows(q)
+            .await?
+            .iter()
+            .map(|row| ScoreVersion::from_row(row, "v"))
+            .collect::<Result<Vec<_>, _>>()?;
+        versions.sort_by_key(|version| version.scored_at);
+
+        Ok(versions)
+    }
+
+    async fn get_scores(&self, id: &str) -> Result<Vec<Score>, PulpError> {
+        let q = Cypher::new()
+            .matching_named_edge(
+                Pattern::new("v").label(ScoreVersion::LABEL).id("id"),
+                "s",
+                Edge::Scored,
+                Pattern::new("n"),
+            )
+            .returning("n.id AS entity_id, labels(n)[0] AS label, s.content AS content, s.engagement AS engagement, s.score AS score")
+            .query()
+            .param("id", id);
+
+        Ok(self
+            .rows(q)
+            .await?
+            .iter()
+            .map(|row| Score {
+                entity_id: row.get("entity_id").unwrap_or_default(),
+                label: row.get("label").unwrap_or_default(),
+                content: row.get("content").unwrap_or_default(),
+                engagement: row.get("engagement").unwrap_or_default(),
+                score: row.get("score").unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    async fn relate(&self, relationship: Relationship) -> Result<(), PulpError> {
+        let (from, to) = relationship.ends();
+        let (from_label, to_label) = relationship.labels();
+
+        let cypher = Cypher::new()
+            .matching(Pattern::new("a").label(from_label).id("from"))
+            .matching(Pattern::new("b").label(to_label).id("to"));
+
+        let q = match self.run_id() {
+            Some(run) => cypher
+                .link_with("a", relationship.edge(), "b", &[("run_id", "$run_id")])
+                .query()
+                .param("run_id", run),
+            None => cypher.link("a", relationship.edge(), "b").query(),
+        };
+
+        self.run(q.param("from", from).param("to", to)).await
+    }
+
+    /// Groups the writes sharing a query into a single `UNWIND` over their rows and runs the
+    /// groups in one transaction, in the order their first write came in
+    async fn write(&self, writes: Vec<Write>) -> Result<(), PulpError> {
+        let run = self.run_id();
+        let mut groups: Vec<(String, Vec<BoltType>)> = Vec::new();
+
+        for write in writes {
+            let (cypher, row) = unwound(write, run.as_deref())?;
+            let cypher = cypher.to_string();
+
+            match groups.iter_mut().find(|(c, _)| *c == cypher) {
+                Some((_, rows)) => rows.push(row),
+                None => groups.push((cypher, vec![row])),
+            }
+        }
+
+        let queries = groups
+            .into_iter()
+            .map(|(cypher, rows)| Query::new(cypher).param("rows", rows))
+            .collect();
+
+        self.run_all(queries).await
+    }
+
+    async fn outgoing(&self, id: &str, edge: Edge) -> Result<Vec<String>, PulpError> {
+        self.linked(id, Pattern::new("").id("id"), edge, Pattern::new("n"))
+            .await
+    }
+
+    async fn incoming(&self, id: &str, edge: Edge) -> Result<Vec<String>, PulpError> {
+        self.linked(id, Pattern::new("n"), edge, Pattern::new("").id("id"))
+            .await
+    }
+}
+
+/// Converts the string `property` of every `T` node that `cast` can convert, counting the nodes converted
+fn converting<T: NodeEntity>(property: &str, cast: &str) -> Cypher {
+    Cypher::new()
+        .matching(Pattern::new("n").label(T::LABEL))
+        .where_string("n", property)
+        .and_converts("n", property, cast)
+        .convert("n", property, cast)
+        .returning("count(n) AS converted")
+}
+
+/// Ids of the `T` nodes whose string `property` `cast` can't convert
+fn unconvertible<T: NodeEntity>(property: &str, cast: &str) -> Cypher {
+    Cypher::new()
+        .matching(Pattern::new("n").label(T::LABEL))
+        .where_string("n", property)
+        .and_not_converts("n", property, cast)
+        .returning("n.id AS id")
+}
+
+/// The query applying `write` once for every `row` of the unwound `$rows` list, and the row of `write`
+/// tagged with the `run` it is written in
+fn unwound(write: Write, run: Option<&str>) -> Result<(Cypher, BoltType), PulpError> {
+    let unwind = Cypher::new().unwind("rows", "row");
+
+    let written = match write {
+        Write::Create(Entity::Vote(vote)) => {
+            let properties = row(
+                vec![
+                    ("id", vote.id.clone().into()),
+                    ("vote_type", vote.vote_type.to_string().into()),
+                    ("reason", vote.vote_type.reason().unwrap_or_default().into()),
+                    ("debate_id", vote.debate_id.into()),
+                    ("prompt_name", vote.prompt_name.into()),
+                    ("prompt_version", vote.prompt_version.into()),
+                ],
+                run,
+            );
+
+            (
+                unwind
+                    .matching(
+                        Pattern::new("r")
+                            .label(Response::LABEL)
+                            .id_from("row.response_id"),
+                    )
+                    .create_node("v", "Vote")
+                    .assign("v", "row.properties")
+                    .link_with("v", Edge::VotedOn, "r", &[("run_id", "row.run_id")]),
+                row(
+                    vec![
+                        ("response_id", vote.response_id.into()),
+                        ("properties", properties),
+                    ],
+                    run,
+                ),
+            )
+        }
+
+        Write::Create(entity) => match entity {
+            Entity::Debate(debate) => created(unwind, &debate, run),
+            Entity::User(user) => created(unwind, &user, run),
+            Entity::Response(response) => created(
+                unwind,
+                &Response {
+                    created_at: created_at(response.created_at),
+                    ..response
+                },
+                run,
+            ),
+            Entity::Reference(reference) => created(unwind, &reference, run),
+            Entity::Blog(blog) => created(unwind, &blog, run),
+            Entity::PersonasUser(user) => created(unwind, &user, run),
+            Entity::Learned(learned) => created(unwind, &learned, run),
+            Entity::Vote(_) => unreachable!("votes are created above"),
+        },
+
+        Write::Update(entity) => match entity {
+            Entity::Debate(debate) => updated::<Debate>(unwind, debate.id(), debate.properties()),
+            Entity::User(user) => updated::<User>(unwind, user.id(), user.properties()),
+            Entity::Response(response) => {
+                updated::<Response>(unwind, response.id(), response.properties())
+            }
+            Entity::Reference(reference) => {
+                updated::<Reference>(unwind, reference.id(), reference.properties())
+            }
+            Entity::Blog(blog) => updated::<Blog>(unwind, blog.id(), blog.properties()),
+            Entity::PersonasUser(user) => {
+                updated::<PersonasUser>(unwind, user.id(), user.properties())
+            }
+            Entity::Learned(learned) => {
+                updated::<Learned>(unwind, learned.id(), learned.properties())
+            }
+            Entity::Vote(vote) => {
+                return Err(PulpError::SimulationError(SimulationError::StoreError(
+                    format!("vote {} can't be updated", vote.id),
+                )))
+            }
+        },
+
+        Write::DebateCommenters { id, count } => {
+            updated::<Debate>(unwind, &id, vec![("commenters", count.into())])
+        }
+
+        Write::DebateVoters { id, count } => {
+            updated::<Debate>(unwind, &id, vec![("voters", count.into())])
+        }
+
+        Write::DebateResponses { id, count } => {
+            updated::<Debate>(unwind, &id, vec![("responses", count.into())])
+        }
+
+        Write::ResponseScore { id, score, policy } => updated::<Response>(
+            unwind,
+            &id,
+            vec![("score", score.into()), ("scoring_policy", policy.into())],
+        ),
+
+        Write::ResponseVotes {
+            id,
+            valid,
+            invalid,
+            abstain,
+        } => updated::<Response>(
+            unwind,
+            &id,
+            vec![
+                ("valid_vote_count", valid.into()),
+                ("invalid_vote_count", invalid.into()),
+                ("abstain_vote_count", abstain.into()),
+            ],
+        ),
+
+        Write::ResponseAppeals {
+            id,
+            ethos,
+            pathos,
+            logos,
+        } => updated::<Response>(
+            unwind,
+            &id,
+            vec![
+                ("ethos", ethos.into()),
+                ("pathos", pathos.into()),
+                ("logos", logos.into()),
+            ],
+        ),
+
+        Write::ResponseAttributes { id, attributes } => {
+            updated::<Response>(unwind, &id, attribute_properties(&attributes))
+        }
+
+        Write::ResponseEngagements { id, engagements } => {
+            updated::<Response>(unwind, &id, engagement_properties(&engagements))
+        }
+
+        Write::Relate(relationship) => {
+            let (from, to) = relationship.ends();
+            let (from_label, to_label) = relationship.labels();
+
+            (
+                unwind
+                    .matching(Pattern::new("a").label(from_label).id_from("row.from"))
+                    .matching(Pattern::new("b").label(to_label).id_from("row.to"))
+                    .link_with("a", relationship.edge(), "b", &[("run_id", "row.run_id")]),
+                row(vec![("from", from.into()), ("to", to.into())], run),
+            )
+        }
+    };
+
+    Ok(written)
+}
+
+/// `CREATE` of a `T` node with every property of the row
+fn created<T: NodeEntity>(unwind: Cypher, entity: &T, run: Option<&str>) -> (Cypher, BoltType) {
+    let mut properties = vec![("id", entity.id().into())];
+    properties.extend(entity.properties());
+
+    (
+        unwind.create_node("n", T::LABEL).assign("n", "row"),
+        row(properties, run),
+    )
+}
+
+/// `SET` of the `properties` of the `T` node with `id`, leaving the others as they are
+fn updated<T: NodeEntity>(
+    unwind: Cypher,
+    id: &str,
+    properties: Vec<(&'static str, BoltType)>,
+) -> (Cypher, BoltType) {
+    debug_assert!(
+        properties.iter().all(|(p, _)| T::PROPERTIES.contains(p)),
+        "{} has no property among {:?}",
+        T::LABEL,
+        properties
+    );
+
+    let mut row_properties = vec![("id", id.into())];
+    row_properties.extend(properties);
+
+    (
+        unwind
+            .matching(Pattern::new("n").label(T::LABEL).id_from("row.id"))
+            .merge("n", "row"),
+        row(row_properties, None),
+    )
+}
+
+/// A map of `properties`, one element of an unwound list, with the `run_id` of the `run` if there is one
+fn row(properties: Vec<(&str, BoltType)>, run: Option<&str>) -> BoltType {
+    let mut map = BoltMap::new();
+    for (property, value) in properties {
+        map.put(property.into(), value);
+    }
+
+    if let Some(run) = run {
+        map.put("run_id".into(), run.into());
+    }
+
+    BoltType::Map(map)
+}
+
+/// The statements of a migration, leaving out `//` comments
+fn statements(migration: &str) -> Vec<String> {
+    let code = migration
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("//"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    code.split(';')
+        .map(|statement| statement.trim().to_string())
+        .filter(|statement| !statement.is_empty())
+        .collect()
+}
+
+/// The attributes of a response, under the property names it is stored with
+fn attribute_properties(attributes: &Attributes) -> Vec<(&'static str, BoltType)> {
+    vec![
+        ("relevance", attributes.relevance.into()),
+        ("soundness", attributes.soundness.into()),
+        ("grammar", attributes.grammar.into()),
+        ("reference_count", attributes.references.into()),
+        ("word_count", attributes.word_count.into()),
+        (
+            "mastery_vocab_words",
+            attributes.mastery_vocab_words.clone().into(),
+        ),
+        ("sentence_count", attributes.sentence_count.into()),
+        ("readability", attributes.readability.into()),
+        ("lexical_diversity", attributes.lexical_diversity.into()),
+        ("statistic_count", attributes.statistics.into()),
+        ("spelling_errors", attributes.spelling_errors.into()),
+        ("failed_scorers", attributes.failed_scorers.clone().into()),
+    ]
+}
+
+/// The engagement breakdown of a response, under the property names it is stored with
+fn engagement_properties(engagements: &Engagements) -> Vec<(&'static str, BoltType)> {
+    vec![
+        (
+            "report_harmful_to_others",
+            engagements.report_harmful_to_others.into(),
+        ),
+        (
+            "report_abuse_of_platform",
+            engagements.report_abuseof_platform.into(),
+        ),
+        ("hide", engagements.hide.into()),
+        ("vote_validity", engagements.vote_validity.into()),
+        ("vote_confidence", engagements.vote_condfidence.into()),
+        ("response_distance", engagements.response_distance.into()),
+        ("response_timing", engagements.response_timing.into()),
+    ]
+}
+
+fn neo4j_error<E: ToString>(e: E) -> PulpError {
+    PulpError::SimulationError(SimulationError::Neo4jError(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn creates_are_unwound_into_nodes_with_the_row_as_properties() {
+        let learned = Learned {
+            id: "l1".to_string(),
+            learned_content: "cite sources".to_string(),
+            reason: "downvoted".to_string(),
+        };
+
+        let (cypher, row_) = unwound(
+            Write::Create(Entity::Learned(learned.clone())),
+            Some("run1"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            cypher.to_string(),
+            "UNWIND $rows AS row CREATE (n:Learned) SET n = row"
+        );
+        assert_eq!(
+            row_,
+            row(
+                vec![
+                    ("id", "l1".into()),
+                    ("learned_content", "cite sources".into()),
+                    ("reason", "downvoted".into()),
+                ],
+                Some("run1"),
+            )
+        );
+    }
+
+    #[test]
+    fn updates_are_unwound_into_a_merge_of_the_changed_properties() {
+        let (cypher, row_) = unwound(
+            Write::ResponseScore {
+                id: "r1".to_string(),
+                score: 7,
+                policy: "default".to_string(),
+            },
+            Some("run1"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            cypher.to_string(),
+            "UNWIND $rows AS row MATCH (n:Response {id: row.id}) SET n += row"
+        );
+        // Updates don't move a node to another run
+        assert_eq!(
+            row_,
+            row(
+                vec![
+                    ("id", "r1".into()),
+                    ("score", 7i64.into()),
+                    ("scoring_policy", "default".into()),
+                ],
+                None,
+            )
+        );
+    }
+
+    #[test]
+    fn relationships_are_unwound_between_nodes_matched_by_label() {
+        let relationship = Relationship::PersonaResponded {
+            persona_id: "p1".to_string(),
+            response_id: "r1".to_string(),
+        };
+
+        let (cypher, row_) = unwound(Write::Relate(relationship), Some("run1")).unwrap();
+
+        assert_eq!(
+            cypher.to_string(),
+            "UNWIND $rows AS row MATCH (a:PersonaUser {id: row.from}) MATCH (b:Response {id: row.to}) CREATE (a)-[:RESPONDED {run_id: row.run_id}]->(b)"
+        );
+        assert_eq!(
+            row_,
+            row(
+                vec![("from", "p1".into()), ("to", "r1".into())],
+                Some("run1")
+            )
+        );
+    }
+
+    #[test]
+    fn migrations_convert_only_the_values_that_parse() {
+        assert_eq!(
+            converting::<Debate>("score", "toInteger").to_string(),
+            "MATCH (n:Debate) WHERE toString(n.score) = n.score AND toInteger(n.score) IS NOT NULL SET n.score = toInteger(n.score) RETURN count(n) AS converted"
+        );
+        assert_eq!(
+            unconvertible::<Debate>("score", "toInteger").to_string(),
+            "MATCH (n:Debate) WHERE toString(n.score) = n.score AND toInteger(n.score) IS NULL RETURN n.id AS id"
+        );
+    }
+
+    #[test]
+    fn votes_can_not_be_updated() {
+        assert!(unwound(Write::Update(Entity::Vote(Vote::default())), None).is_err());
+    }
+}
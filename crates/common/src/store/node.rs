@@ -0,0 +1,518 @@
+use crate::{
+    errors::{PulpError, SimulationError},
+    models::{
+        personas_user::PoliticalOrientation, Blog, Debate, Gender, Learned, PersonasUser,
+        Reference, Response, ScoreVersion, SimulationRun, User,
+    },
+};
+use neo4rs::{BoltType, Node, Query, Row};
+
+/// A value stored as a single node property
+pub trait Property: Sized {
+    /// Bolt type the value is stored as, i.e. `an integer`
+    const KIND: &'static str;
+
+    /// Cypher function converting the string form of the value back, for values older versions wrote as strings
+    const FROM_STRING: Option<&'static str> = None;
+
+    fn to_bolt(&self) -> BoltType;
+
+    fn from_bolt(value: BoltType) -> Option<Self>;
+}
+
+macro_rules! bolt_property {
+    ($($t:ty: $kind:literal, $from_string:expr);+ $(;)?) => {
+        $(
+            impl Property for $t {
+                const KIND: &'static str = $kind;
+
+                const FROM_STRING: Option<&'static str> = $from_string;
+
+                fn to_bolt(&self) -> BoltType {
+                    self.clone().into()
+                }
+
+                fn from_bolt(value: BoltType) -> Option<Self> {
+                    <$t>::try_from(value).ok()
+                }
+            }
+        )+
+    };
+}
+
+bolt_property!(
+    i64: "an integer", Some("toInteger");
+    f64: "a float", Some("toFloat");
+    bool: "a boolean", Some("toBoolean");
+    String: "a string", None;
+    Vec<f64>: "a list of floats", None;
+    Vec<String>: "a list of strings", None;
+);
+
+impl Property for Gender {
+    const KIND: &'static str = "a string";
+
+    fn to_bolt(&self) -> BoltType {
+        self.to_string().into()
+    }
+
+    fn from_bolt(value: BoltType) -> Option<Self> {
+        String::from_bolt(value).map(|g| Gender::from(g.as_str()))
+    }
+}
+
+impl Property for PoliticalOrientation {
+    const KIND: &'static str = "a string";
+
+    fn to_bolt(&self) -> BoltType {
+        self.to_string().into()
+    }
+
+    fn from_bolt(value: BoltType) -> Option<Self> {
+        String::from_bolt(value).map(|po| PoliticalOrientation::from(po.as_str()))
+    }
+}
+
+/// A model stored as a single labelled node, written and read property by property
+pub trait NodeEntity: Default + Send + Sync {
+    /// Node label, i.e. `Debate`
+    const LABEL: &'static str;
+
+    /// Every property but `id`, each bound to the query parameter of the same name
+    const PROPERTIES: &'static [&'static str];
+
+    fn id(&self) -> &str;
+
+    /// Every property but `id` with its value
+    fn properties(&self) -> Vec<(&'static str, BoltType)>;
+
+    /// Binds every property but `id` to its query parameter
+    fn bind(&self, q: Query) -> Query {
+        self.properties()
+            .into_iter()
+            .fold(q, |q, (property, value)| q.param(property, value))
+    }
+
+    /// Reads the model back from its node, missing properties are left at their default and
+    /// properties of another type are an error
+    fn from_node(node: &Node) -> Result<Self, PulpError>;
+
+    /// Properties stored with a native type that has a string form, with the Cypher function converting it back
+    fn string_casts() -> Vec<(&'static str, &'static str)>;
+}
+
+/// Reads a model from the column of a returned row
+pub trait FromRow: Sized {
+    fn from_row(row: &Row, column: &str) -> Result<Self, PulpError>;
+}
+
+impl<T: NodeEntity> FromRow for T {
+    fn from_row(row: &Row, column: &str) -> Result<Self, PulpError> {
+        match row.get::<Node>(column) {
+            Some(node) => T::from_node(&node),
+
+            None => Err(PulpError::SimulationError(SimulationError::Neo4jError(
+                format!("missing {} in the returned row", column),
+            ))),
+        }
+    }
+}
+
+/// Implements `NodeEntity` from a single list of `property: (field path)` pairs,
+/// so the names written and the names read can't drift apart
+macro_rules! node_entity {
+    ($model:ty, $label:literal, id: ($($id:tt)+), { $($property:ident: ($($field:tt)+)),+ $(,)? }) => {
+        impl NodeEntity for $model {
+            const LABEL: &'static str = $label;
+
+            const PROPERTIES: &'static [&'static str] = &[$(stringify!($property)),+];
+
+            fn id(&self) -> &str {
+                &self.$($id)+
+            }
+
+            fn properties(&self) -> Vec<(&'static str, BoltType)> {
+                vec![$((stringify!($property), self.$($field)+.to_bolt())),+]
+            }
+
+            fn from_node(node: &Node) -> Result<Self, PulpError> {
+                let mut model = <$model>::default();
+
+                if let Some(id) = read(node, $label, "id")? {
+                    model.$($id)+ = id;
+                }
+                $(
+                    if let Some(value) = read(node, $label, stringify!($property))? {
+                        model.$($field)+ = value;
+                    }
+                )+
+
+                Ok(model)
+            }
+
+            fn string_casts() -> Vec<(&'static str, &'static str)> {
+                let model = <$model>::default();
+
+                let mut casts = Vec::new();
+                $(
+                    if let Some(cast) = from_string(&model.$($field)+) {
+                        casts.push((stringify!($property), cast));
+                    }
+                )+
+
+                casts
+            }
+        }
+    };
+}
+
+/// Reads `property` of a `label` node, `None` when the node has no such property
+fn read<T: Property>(node: &Node, label: &str, property: &str) -> Result<Option<T>, PulpError> {
+    let value = match node.get::<BoltType>(property) {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+
+    match T::from_bolt(value.clone()) {
+        Some(value) => Ok(Some(value)),
+
+        None => Err(PulpError::SimulationError(SimulationError::Neo4jError(
+            format!(
+                "{}.{} should be {} but is {:?}, `pulpcalc db migrate` converts properties older versions stored as strings",
+                label, property, T::KIND, value
+            ),
+        ))),
+    }
+}
+
+fn from_string<T: Property>(_: &T) -> Option<&'static str> {
+    T::FROM_STRING
+}
+
+node_entity!(Debate, "Debate", id: (id), {
+    score: (score),
+    topic: (topic),
+    category: (category),
+    registered_speakers: (registered_speakers),
+    commenters: (commenters),
+    voters: (voters),
+    comments: (comments),
+    inactive_participants: (inactive_participants),
+    responses: (responses),
+    init_timestamp: (init_timestamp),
+    registration_start: (registration_timestamps.0),
+    registration_end: (registration_timestamps.1),
+    competition_start: (competition_timestamps.0),
+    competition_end: (competition_timestamps.1),
+    rewards_start: (rewards_timestamps.0),
+    rewards_end: (rewards_timestamps.1),
+});
+
+node_entity!(User, "User", id: (id), {
+    debates: (debates),
+    simulation_data: (simulation_data),
+});
+
+node_entity!(Response, "Response", id: (id), {
+    content: (content),
+    confidence: (confidence),
+    score: (score),
+    valid_vote_count: (valid_vote_count),
+    invalid_vote_count: (invalid_vote_count),
+    abstain_vote_count: (abstain_vote_count),
+    report_count: (report_count),
+    hide_count: (hide_count),
+    topic_of_response: (topic_of_response),
+    ethos: (ethos),
+    pathos: (pathos),
+    logos: (logos),
+    relevance: (attributes.relevance),
+    soundness: (attributes.soundness),
+    grammar: (attributes.grammar),
+    reference_count: (attributes.references),
+    word_count: (attributes.word_count),
+    mastery_vocab_words: (attributes.mastery_vocab_words),
+    sentence_count: (attributes.sentence_count),
+    readability: (attributes.readability),
+    lexical_diversity: (attributes.lexical_diversity),
+    statistic_count: (attributes.statistics),
+    spelling_errors: (attributes.spelling_errors),
+    failed_scorers: (attributes.failed_scorers),
+    report_harmful_to_others: (engagements.report_harmful_to_others),
+    report_abuse_of_platform: (engagements.report_abuseof_platform),
+    hide: (engagements.hide),
+    vote_validity: (engagements.vote_validity),
+    vote_confidence: (engagements.vote_condfidence),
+    response_distance: (engagements.response_distance),
+    response_timing: (engagements.response_timing),
+    prompt_name: (prompt_name),
+    prompt_version: (prompt_version),
+    created_at: (created_at),
+    scoring_policy: (scoring_policy),
+});
+
+node_entity!(Reference, "Reference", id: (id), {
+    internal: (internal),
+    trust: (trust),
+    distrust: (distrust),
+    content: (content),
+});
+
+node_entity!(Blog, "Blog", id: (id), {
+    title: (title),
+    body: (body),
+    author: (author),
+    topic: (topic),
+    category: (category),
+    publish_timestamp: (publish_timestamp),
+    ethos: (ethos),
+    pathos: (pathos),
+    logos: (logos),
+    score: (score),
+    engagements: (engagements),
+    impressions: (impressions),
+});
+
+node_entity!(PersonasUser, "PersonaUser", id: (base_user.id), {
+    simulation_data: (base_user.simulation_data),
+    age: (age),
+    gender: (gender),
+    political_orientation: (political_orientation),
+    vote_valid_influence: (vote_valid_influence),
+    vote_invalid_influence: (vote_invalid_influence),
+    vote_abstain_influence: (vote_abstain_influence),
+    knowledge_references: (knoweledge.knowledge_references),
+    followers: (network.network_size.followers),
+    following: (network.network_size.following),
+    impressions: (network.network_activity.impressions),
+    engagements: (network.network_activity.engagements),
+    input: (personality.personality_content.input),
+    output: (personality.personality_content.output),
+    core_fear: (personality.personality_base.core_fear),
+    enneagram: (personality.personality_base.enneagram),
+    core_desire: (personality.personality_base.core_desire),
+    valid_voting_tendency: (personality.personality_engagement.voting_tendency.0),
+    invalid_voting_tendency: (personality.personality_engagement.voting_tendency.1),
+    abstain_voting_tendency: (personality.personality_engagement.voting_tendency.2),
+    hide_tendency: (personality.personality_engagement.hide_tendency),
+    report_tendency: (personality.personality_engagement.report_tendency),
+    engagement_type: (personality.personality_engagement.engagement_type),
+});
+
+node_entity!(Learned, "Learned", id: (id), {
+    learned_content: (learned_content),
+    reason: (reason),
+});
+
+node_entity!(SimulationRun, "SimulationRun", id: (id), {
+    simulation: (simulation),
+    config_file: (config_file),
+    config_hash: (config_hash),
+    seed: (seed),
+    started_at: (started_at),
+});
+
+node_entity!(ScoreVersion, "ScoreVersion", id: (id), {
+    run_id: (run_id),
+    policy: (policy),
+    scored_at: (scored_at),
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::cypher::{Cypher, Pattern};
+    use neo4rs::{BoltInteger, BoltList, BoltMap, BoltNode};
+
+    /// A `label` node with `properties`, as Neo4j returns it
+    fn node(label: &str, properties: Vec<(&str, BoltType)>) -> Node {
+        let mut map = BoltMap::new();
+        for (property, value) in properties {
+            map.put(property.into(), value);
+        }
+
+        Node::new(BoltNode::new(
+            BoltInteger::new(1),
+            BoltList::from(vec![label.into()]),
+            map,
+        ))
+    }
+
+    /// Every `$parameter` of a query
+    fn parameters(text: &str) -> Vec<&str> {
+        text.split('$')
+            .skip(1)
+            .map(|rest| {
+                let end = rest
+                    .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                    .unwrap_or(rest.len());
+
+                &rest[..end]
+            })
+            .collect()
+    }
+
+    fn response() -> Response {
+        let mut response = Response {
+            id: "r1".to_string(),
+            content: "Taxes fund roads".to_string(),
+            score: 42,
+            prompt_name: "persona_content".to_string(),
+            ..Default::default()
+        };
+        response.attributes.relevance = 0.5;
+        response.attributes.failed_scorers = vec!["grammar".to_string()];
+        response.engagements.hide = 3;
+
+        response
+    }
+
+    #[test]
+    fn properties_follow_the_declared_names() {
+        let response = response();
+        let properties = response.properties();
+
+        let names: Vec<&str> = properties.iter().map(|(p, _)| *p).collect();
+        assert_eq!(names, Response::PROPERTIES);
+        assert!(!names.contains(&"id"));
+
+        let value = |name: &str| {
+            properties
+                .iter()
+                .find(|(p, _)| *p == name)
+                .map(|(_, v)| v.clone())
+                .unwrap()
+        };
+        assert_eq!(value("content"), BoltType::from("Taxes fund roads"));
+        assert_eq!(value("score"), BoltType::from(42i64));
+        assert_eq!(value("relevance"), BoltType::from(0.5));
+        assert_eq!(value("hide"), BoltType::from(3i64));
+        assert_eq!(
+            value("failed_scorers"),
+            BoltType::from(vec!["grammar".to_string()])
+        );
+        assert_eq!(Response::LABEL, "Response");
+        assert_eq!(response.id(), "r1");
+
+        let mut persona = PersonasUser::default();
+        persona.base_user.id = "p1".to_string();
+        persona.gender = Gender::Female;
+        assert_eq!(persona.id(), "p1");
+        assert!(persona
+            .properties()
+            .contains(&("gender", BoltType::from(Gender::Female.to_string()))));
+    }
+
+    #[test]
+    fn create_and_update_queries_bind_every_parameter() {
+        let response = response();
+
+        let mut properties = vec!["id"];
+        properties.extend(Response::PROPERTIES);
+        let create = Cypher::new()
+            .create("n", Response::LABEL, &properties)
+            .returning("n.id");
+        let create_query = response.bind(create.query()).param("id", response.id());
+
+        let update = Cypher::new()
+            .matching(Pattern::new("n").label(Response::LABEL).id("id"))
+            .set("n", Response::PROPERTIES);
+        let update_query = response.bind(update.query()).param("id", response.id());
+
+        for (cypher, query) in [(create, create_query), (update, update_query)] {
+            let text = cypher.to_string();
+
+            assert_eq!(parameters(&text).len(), Response::PROPERTIES.len() + 1);
+            for parameter in parameters(&text) {
+                assert!(query.has_param_key(parameter), "{} is not bound", parameter);
+            }
+        }
+    }
+
+    #[test]
+    fn from_node_reads_back_what_was_written() {
+        let written = response();
+        let mut properties = vec![("id", BoltType::from(written.id.clone()))];
+        properties.extend(written.properties());
+
+        let read = Response::from_node(&node(Response::LABEL, properties)).unwrap();
+
+        assert_eq!(read.id, written.id);
+        assert_eq!(read.content, written.content);
+        assert_eq!(read.score, written.score);
+        assert_eq!(read.prompt_name, written.prompt_name);
+        assert_eq!(read.attributes.relevance, written.attributes.relevance);
+        assert_eq!(
+            read.attributes.failed_scorers,
+            written.attributes.failed_scorers
+        );
+        assert_eq!(read.engagements.hide, written.engagements.hide);
+    }
+
+    #[test]
+    fn from_node_leaves_missing_properties_at_their_default() {
+        let learned = Learned::from_node(&node(
+            Learned::LABEL,
+            vec![("id", "l1".into()), ("reason", "sources".into())],
+        ))
+        .unwrap();
+
+        assert_eq!(learned.id, "l1");
+        assert_eq!(learned.reason, "sources");
+        assert_eq!(learned.learned_content, "");
+    }
+
+    #[test]
+    fn from_node_rejects_a_property_of_another_type() {
+        let e = Debate::from_node(&node(
+            Debate::LABEL,
+            vec![("id", "d1".into()), ("score", "12".into())],
+        ))
+        .unwrap_err();
+
+        match e {
+            PulpError::SimulationError(SimulationError::Neo4jError(message)) => {
+                assert!(message.starts_with("Debate.score should be an integer"));
+                assert!(message.contains("pulpcalc db migrate"));
+            }
+            e => panic!("expected a neo4j error, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn from_row_reads_the_node_of_the_column() {
+        let row = Row::new(
+            BoltList::from(vec!["n".into()]),
+            BoltList::from(vec![BoltType::from(BoltNode::new(
+                BoltInteger::new(1),
+                BoltList::from(vec![Learned::LABEL.into()]),
+                {
+                    let mut map = BoltMap::new();
+                    map.put("id".into(), "l1".into());
+                    map
+                },
+            ))]),
+        );
+
+        assert_eq!(Learned::from_row(&row, "n").unwrap().id, "l1");
+        assert!(matches!(
+            Learned::from_row(&row, "m"),
+            Err(PulpError::SimulationError(SimulationError::Neo4jError(_)))
+        ));
+    }
+
+    #[test]
+    fn string_casts_cover_the_numeric_and_boolean_properties() {
+        let casts = Reference::string_casts();
+
+        assert_eq!(
+            casts,
+            vec![
+                ("internal", "toBoolean"),
+                ("trust", "toInteger"),
+                ("distrust", "toInteger"),
+            ]
+        );
+        assert!(Learned::string_casts().is_empty());
+    }
+}
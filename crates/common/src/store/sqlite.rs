@@ -0,0 +1,1571 @@
+use super::{created_at, new_id, Edge, Relationship, Store};
+use crate::{
+    errors::{PulpError, SimulationError},
+    models::{
+        personas_user::PoliticalOrientation, vote::VoteType, Attributes, Blog, Debate, Engagements,
+        Gender, Learned, PersonasUser, Reference, Response, Score, ScoreVersion, SimulationRun,
+        User, Vote,
+    },
+};
+use async_trait::async_trait;
+use rusqlite::{named_params, params, Connection, OptionalExtension, Params, Row};
+use std::sync::{Mutex, MutexGuard};
+
+/// Schema migrations in the order they are applied, the database `user_version` is the number applied so far
+const MIGRATIONS: &[&str] = &[
+    include_str!("migrations/0001_init.sql"),
+    include_str!("migrations/0002_runs.sql"),
+    include_str!("migrations/0003_response_created_at.sql"),
+    include_str!("migrations/0004_scoring_policy.sql"),
+    include_str!("migrations/0005_score_versions.sql"),
+    include_str!("migrations/0006_content_metrics.sql"),
+    include_str!("migrations/0007_grammar.sql"),
+];
+
+/// Tables of every entity with the label it has in Neo4j
+const ENTITY_TABLES: &[(&str, &str)] = &[
+    ("debates", "Debate"),
+    ("users", "User"),
+    ("responses", "Response"),
+    ("\"references\"", "Reference"),
+    ("votes", "Vote"),
+    ("blogs", "Blog"),
+    ("personas", "PersonaUser"),
+    ("learned", "Learned"),
+    ("score_versions", "ScoreVersion"),
+];
+
+/// Tables of every relationship but `VOTED_ON`, which is kept on the vote
+const EDGE_TABLES: &[&str] = &["replies", "authorships", "edges", "scores"];
+
+/// Keeps every entity and edge in a single SQLite file, handy for archiving and sharing runs
+pub struct SqliteStore {
+    conn: Mutex<Connection>,
+
+    /// Id of the run everything is written in, if one was started
+    run: Mutex<Option<String>>,
+}
+
+impl SqliteStore {
+    /// Opens, or creates, the database at `path` and brings its schema up to date
+    pub fn open(path: &str) -> Result<Self, PulpError> {
+        let conn = Connection::open(path)
+            .map_err(|e| sqlite_error(format!("failed to open {}: {}", path, e)))?;
+
+        Self::new(conn)
+    }
+
+    fn new(mut conn: Connection) -> Result<Self, PulpError> {
+        migrate(&mut conn)?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+            run: Mutex::new(None),
+        })
+    }
+
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap()
+    }
+
+    fn run_id(&self) -> Option<String> {
+        self.run.lock().unwrap().clone()
+    }
+
+    fn execute<P: Params>(&self, sql: &str, params: P) -> Result<(), PulpError> {
+        self.conn().execute(sql, params).map_err(sqlite_error)?;
+
+        Ok(())
+    }
+
+    /// The single row `sql` selects for `id`, not finding it is an error
+    fn row<T, F>(&self, sql: &str, label: &str, id: &str, f: F) -> Result<T, PulpError>
+    where
+        F: FnOnce(&Row<'_>) -> rusqlite::Result<T>,
+    {
+        let row = self
+            .conn()
+            .query_row(sql, params![id], f)
+            .optional()
+            .map_err(sqlite_error)?;
+
+        row.ok_or_else(|| {
+            PulpError::SimulationError(SimulationError::StoreError(format!(
+                "{} {} not found",
+                label, id
+            )))
+        })
+    }
+
+    fn rows<T, P, F>(&self, sql: &str, params: P, f: F) -> Result<Vec<T>, PulpError>
+    where
+        P: Params,
+        F: FnMut(&Row<'_>) -> rusqlite::Result<T>,
+    {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(sql).map_err(sqlite_error)?;
+        let rows = stmt
+            .query_map(params, f)
+            .map_err(sqlite_error)?
+            .collect::<rusqlite::Result<Vec<T>>>()
+            .map_err(sqlite_error)?;
+
+        Ok(rows)
+    }
+
+    fn ids(&self, sql: &str, id: &str) -> Result<Vec<String>, PulpError> {
+        self.rows(sql, params![id], |row| row.get(0))
+    }
+
+    /// Deletes the entity with `id` from `table` along with every edge from or to it, like a `DETACH DELETE`
+    fn delete(&self, table: &str, id: &str) -> Result<(), PulpError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction().map_err(sqlite_error)?;
+
+        for sql in [
+            format!("DELETE FROM {} WHERE id = ?1", table),
+            "DELETE FROM replies WHERE response_id = ?1 OR reply_id = ?1".to_string(),
+            "DELETE FROM authorships WHERE author_id = ?1 OR response_id = ?1".to_string(),
+            "DELETE FROM edges WHERE from_id = ?1 OR to_id = ?1".to_string(),
+        ] {
+            tx.execute(&sql, params![id]).map_err(sqlite_error)?;
+        }
+
+        tx.commit().map_err(sqlite_error)
+    }
+
+    /// Inserts or overwrites every column of a persona, which keeps the run it was created in
+    fn upsert_personas_user(&self, user: &PersonasUser) -> Result<(), PulpError> {
+        let network = &user.network;
+        let personality = &user.personality;
+        let (vv, iv, av) = personality.personality_engagement.voting_tendency;
+
+        self.execute(
+            "INSERT OR REPLACE INTO personas (id, simulation_data, age, gender, political_orientation, vote_valid_influence, vote_invalid_influence, vote_abstain_influence, knowledge_references, followers, following, impressions, engagements, input, output, core_fear, enneagram, core_desire, valid_voting_tendency, invalid_voting_tendency, abstain_voting_tendency, hide_tendency, report_tendency, engagement_type, run_id) VALUES (:id, :simulation_data, :age, :gender, :political_orientation, :vote_valid_influence, :vote_invalid_influence, :vote_abstain_influence, :knowledge_references, :followers, :following, :impressions, :engagements, :input, :output, :core_fear, :enneagram, :core_desire, :valid_voting_tendency, :invalid_voting_tendency, :abstain_voting_tendency, :hide_tendency, :report_tendency, :engagement_type, COALESCE((SELECT run_id FROM personas WHERE id = :id), :run_id))",
+            named_params! {
+                ":id": user.base_user.id,
+                ":simulation_data": user.base_user.simulation_data,
+                ":age": user.age,
+                ":gender": user.gender.to_string(),
+                ":political_orientation": user.political_orientation.to_string(),
+                ":vote_valid_influence": to_json(&user.vote_valid_influence)?,
+                ":vote_invalid_influence": to_json(&user.vote_invalid_influence)?,
+                ":vote_abstain_influence": to_json(&user.vote_abstain_influence)?,
+                ":knowledge_references": user.knoweledge.knowledge_references,
+                ":followers": network.network_size.followers,
+                ":following": network.network_size.following,
+                ":impressions": network.network_activity.impressions,
+                ":engagements": network.network_activity.engagements,
+                ":input": personality.personality_content.input,
+                ":output": personality.personality_content.output,
+                ":core_fear": personality.personality_base.core_fear,
+                ":enneagram": personality.personality_base.enneagram,
+                ":core_desire": personality.personality_base.core_desire,
+                ":valid_voting_tendency": vv,
+                ":invalid_voting_tendency": iv,
+                ":abstain_voting_tendency": av,
+                ":hide_tendency": personality.personality_engagement.hide_tendency,
+                ":report_tendency": personality.personality_engagement.report_tendency,
+                ":engagement_type": personality.personality_engagement.engagement_type,
+                ":run_id": self.run_id(),
+            },
+        )
+    }
+
+    /// Whether the entity with `id` is stored with the Neo4j `label`
+    fn has_label(&self, id: &str, label: &str) -> Result<bool, PulpError> {
+        let table = match ENTITY_TABLES.iter().find(|(_, l)| *l == label) {
+            Some((table, _)) => table,
+            None => return Ok(false),
+        };
+
+        self.conn()
+            .query_row(
+                &format!("SELECT EXISTS (SELECT 1 FROM {} WHERE id = ?1)", table),
+                params![id],
+                |row| row.get(0),
+            )
+            .map_err(sqlite_error)
+    }
+}
+
+#[async_trait]
+impl Store for SqliteStore {
+    async fn create_debate(&self, debate: &Debate) -> Result<String, PulpError> {
+        let id = new_id(&debate.id);
+
+        self.execute(
+            "INSERT INTO debates (id, score, topic, category, registered_speakers, commenters, voters, comments, inactive_participants, responses, init_timestamp, registration_start, registration_end, competition_start, competition_end, rewards_start, rewards_end, run_id) VALUES (:id, :score, :topic, :category, :registered_speakers, :commenters, :voters, :comments, :inactive_participants, :responses, :init_timestamp, :registration_start, :registration_end, :competition_start, :competition_end, :rewards_start, :rewards_end, :run_id)",
+            named_params! {
+                ":id": id,
+                ":score": debate.score,
+                ":topic": debate.topic,
+                ":category": debate.category,
+                ":registered_speakers": debate.registered_speakers,
+                ":commenters": debate.commenters,
+                ":voters": debate.voters,
+                ":comments": debate.comments,
+                ":inactive_participants": debate.inactive_participants,
+                ":responses": debate.responses,
+                ":init_timestamp": debate.init_timestamp,
+                ":registration_start": debate.registration_timestamps.0,
+                ":registration_end": debate.registration_timestamps.1,
+                ":competition_start": debate.competition_timestamps.0,
+                ":competition_end": debate.competition_timestamps.1,
+                ":rewards_start": debate.rewards_timestamps.0,
+                ":rewards_end": debate.rewards_timestamps.1,
+                ":run_id": self.run_id(),
+            },
+        )?;
+
+        Ok(id)
+    }
+
+    async fn get_debate(&self, id: &str) -> Result<Debate, PulpError> {
+        self.row(
+            "SELECT * FROM debates WHERE id = ?1",
+            "debate",
+            id,
+            debate_from_row,
+        )
+    }
+
+    async fn update_debate(&self, debate: &Debate) -> Result<(), PulpError> {
+        self.execute(
+            "UPDATE debates SET score = :score, topic = :topic, category = :category, registered_speakers = :registered_speakers, commenters = :commenters, voters = :voters, comments = :comments, inactive_participants = :inactive_participants, responses = :responses, init_timestamp = :init_timestamp, registration_start = :registration_start, registration_end = :registration_end, competition_start = :competition_start, competition_end = :competition_end, rewards_start = :rewards_start, rewards_end = :rewards_end WHERE id = :id",
+            named_params! {
+                ":id": debate.id,
+                ":score": debate.score,
+                ":topic": debate.topic,
+                ":category": debate.category,
+                ":registered_speakers": debate.registered_speakers,
+                ":commenters": debate.commenters,
+                ":voters": debate.voters,
+                ":comments": debate.comments,
+                ":inactive_participants": debate.inactive_participants,
+                ":responses": debate.responses,
+                ":init_timestamp": debate.init_timestamp,
+                ":registration_start": debate.registration_timestamps.0,
+                ":registration_end": debate.registration_timestamps.1,
+                ":competition_start": debate.competition_timestamps.0,
+                ":competition_end": debate.competition_timestamps.1,
+                ":rewards_start": debate.rewards_timestamps.0,
+                ":rewards_end": debate.rewards_timestamps.1,
+            },
+        )
+    }
+
+    async fn update_debate_commenters(&self, id: &str, count: i64) -> Result<(), PulpError> {
+        self.execute(
+            "UPDATE debates SET commenters = ?2 WHERE id = ?1",
+            params![id, count],
+        )
+    }
+
+    async fn update_debate_voters(&self, id: &str, count: i64) -> Result<(), PulpError> {
+        self.execute(
+            "UPDATE debates SET voters = ?2 WHERE id = ?1",
+            params![id, count],
+        )
+    }
+
+    async fn update_debate_responses(&self, id: &str, count: i64) -> Result<(), PulpError> {
+        self.execute(
+            "UPDATE debates SET responses = ?2 WHERE id = ?1",
+            params![id, count],
+        )
+    }
+
+    async fn delete_debate(&self, id: &str) -> Result<(), PulpError> {
+        self.delete("debates", id)
+    }
+
+    async fn create_user(&self, user: &User) -> Result<String, PulpError> {
+        let id = new_id(&user.id);
+
+        self.execute(
+            "INSERT INTO users (id, simulation_data, run_id) VALUES (?1, ?2, ?3)",
+            params![id, user.simulation_data, self.run_id()],
+        )?;
+
+        Ok(id)
+    }
+
+    async fn get_user(&self, id: &str) -> Result<User, PulpError> {
+        self.row(
+            "SELECT * FROM users WHERE id = ?1",
+            "user",
+            id,
+            user_from_row,
+        )
+    }
+
+    async fn get_all_users(&self) -> Result<Vec<User>, PulpError> {
+        self.rows("SELECT * FROM users", [], user_from_row)
+    }
+
+    async fn update_user(&self, user: &User) -> Result<(), PulpError> {
+        self.execute(
+            "UPDATE users SET simulation_data = ?2 WHERE id = ?1",
+            params![user.id, user.simulation_data],
+        )
+    }
+
+    async fn delete_user(&self, id: &str) -> Result<(), PulpError> {
+        self.delete("users", id)
+    }
+
+    async fn create_response(&self, response: &Response) -> Result<String, PulpError> {
+        let id = new_id(&response.id);
+        let words = to_json(&response.attributes.mastery_vocab_words)?;
+
+        self.execute(
+            "INSERT INTO responses (id, content, confidence, score, valid_vote_count, invalid_vote_count, abstain_vote_count, report_count, hide_count, topic_of_response, ethos, pathos, logos, relevance, soundness, reference_count, word_count, mastery_vocab_words, sentence_count, readability, lexical_diversity, statistic_count, spelling_errors, grammar, failed_scorers, report_harmful_to_others, report_abuse_of_platform, hide, vote_validity, vote_confidence, response_distance, response_timing, prompt_name, prompt_version, created_at, scoring_policy, run_id) VALUES (:id, :content, :confidence, :score, :valid_vote_count, :invalid_vote_count, :abstain_vote_count, :report_count, :hide_count, :topic_of_response, :ethos, :pathos, :logos, :relevance, :soundness, :reference_count, :word_count, :mastery_vocab_words, :sentence_count, :readability, :lexical_diversity, :statistic_count, :spelling_errors, :grammar, :failed_scorers, :report_harmful_to_others, :report_abuse_of_platform, :hide, :vote_validity, :vote_confidence, :response_distance, :response_timing, :prompt_name, :prompt_version, :created_at, :scoring_policy, :run_id)",
+            named_params! {
+                ":id": id,
+                ":content": response.content,
+                ":confidence": response.confidence,
+                ":score": response.score,
+                ":valid_vote_count": response.valid_vote_count,
+                ":invalid_vote_count": response.invalid_vote_count,
+                ":abstain_vote_count": response.abstain_vote_count,
+                ":report_count": response.report_count,
+                ":hide_count": response.hide_count,
+                ":topic_of_response": response.topic_of_response,
+                ":ethos": response.ethos,
+                ":pathos": response.pathos,
+                ":logos": response.logos,
+                ":relevance": response.attributes.relevance,
+                ":soundness": response.attributes.soundness,
+                ":reference_count": response.attributes.references,
+                ":word_count": response.attributes.word_count,
+                ":mastery_vocab_words": words,
+                ":sentence_count": response.attributes.sentence_count,
+                ":readability": response.attributes.readability,
+                ":lexical_diversity": response.attributes.lexical_diversity,
+                ":statistic_count": response.attributes.statistics,
+                ":spelling_errors": response.attributes.spelling_errors,
+                ":grammar": response.attributes.grammar,
+                ":failed_scorers": to_json(&response.attributes.failed_scorers)?,
+                ":report_harmful_to_others": response.engagements.report_harmful_to_others,
+                ":report_abuse_of_platform": response.engagements.report_abuseof_platform,
+                ":hide": response.engagements.hide,
+                ":vote_validity": response.engagements.vote_validity,
+                ":vote_confidence": response.engagements.vote_condfidence,
+                ":response_distance": response.engagements.response_distance,
+                ":response_timing": response.engagements.response_timing,
+                ":prompt_name": response.prompt_name,
+                ":prompt_version": response.prompt_version,
+                ":created_at": created_at(response.created_at),
+                ":scoring_policy": response.scoring_policy,
+                ":run_id": self.run_id(),
+            },
+        )?;
+
+        Ok(id)
+    }
+
+    async fn get_response(&self, id: &str) -> Result<Response, PulpError> {
+        self.row(
+            "SELECT * FROM responses WHERE id = ?1",
+            "response",
+            id,
+            response_from_row,
+        )
+    }
+
+    async fn update_response(&self, response: &Response) -> Result<(), PulpError> {
+        let words = to_json(&response.attributes.mastery_vocab_words)?;
+
+        self.execute(
+            "UPDATE responses SET content = :content, confidence = :confidence, score = :score, valid_vote_count = :valid_vote_count, invalid_vote_count = :invalid_vote_count, abstain_vote_count = :abstain_vote_count, report_count = :report_count, hide_count = :hide_count, topic_of_response = :topic_of_response, ethos = :ethos, pathos = :pathos, logos = :logos, relevance = :relevance, soundness = :soundness, reference_count = :reference_count, word_count = :word_count, mastery_vocab_words = :mastery_vocab_words, sentence_count = :sentence_count, readability = :readability, lexical_diversity = :lexical_diversity, statistic_count = :statistic_count, spelling_errors = :spelling_errors, grammar = :grammar, failed_scorers = :failed_scorers, report_harmful_to_others = :report_harmful_to_others, report_abuse_of_platform = :report_abuse_of_platform, hide = :hide, vote_validity = :vote_validity, vote_confidence = :vote_confidence, response_distance = :response_distance, response_timing = :response_timing, prompt_name = :prompt_name, prompt_version = :prompt_version, created_at = :created_at, scoring_policy = :scoring_policy WHERE id = :id",
+            named_params! {
+                ":id": response.id,
+                ":content": response.content,
+                ":confidence": response.confidence,
+                ":score": response.score,
+                ":valid_vote_count": response.valid_vote_count,
+                ":invalid_vote_count": response.invalid_vote_count,
+                ":abstain_vote_count": response.abstain_vote_count,
+                ":report_count": response.report_count,
+                ":hide_count": response.hide_count,
+                ":topic_of_response": response.topic_of_response,
+                ":ethos": response.ethos,
+                ":pathos": response.pathos,
+                ":logos": response.logos,
+                ":relevance": response.attributes.relevance,
+                ":soundness": response.attributes.soundness,
+                ":reference_count": response.attributes.references,
+                ":word_count": response.attributes.word_count,
+                ":mastery_vocab_words": words,
+                ":sentence_count": response.attributes.sentence_count,
+                ":readability": response.attributes.readability,
+                ":lexical_diversity": response.attributes.lexical_diversity,
+                ":statistic_count": response.attributes.statistics,
+                ":spelling_errors": response.attributes.spelling_errors,
+                ":grammar": response.attributes.grammar,
+                ":failed_scorers": to_json(&response.attributes.failed_scorers)?,
+                ":report_harmful_to_others": response.engagements.report_harmful_to_others,
+                ":report_abuse_of_platform": response.engagements.report_abuseof_platform,
+                ":hide": response.engagements.hide,
+                ":vote_validity": response.engagements.vote_validity,
+                ":vote_confidence": response.engagements.vote_condfidence,
+                ":response_distance": response.engagements.response_distance,
+                ":response_timing": response.engagements.response_timing,
+                ":prompt_name": response.prompt_name,
+                ":prompt_version": response.prompt_version,
+                ":created_at": response.created_at,
+                ":scoring_policy": response.scoring_policy,
+            },
+        )
+    }
+
+    async fn update_response_score(
+        &self,
+        id: &str,
+        score: i64,
+        policy: &str,
+    ) -> Result<(), PulpError> {
+        self.execute(
+            "UPDATE responses SET score = ?2, scoring_policy = ?3 WHERE id = ?1",
+            params![id, score, policy],
+        )
+    }
+
+    async fn update_response_votes(
+        &self,
+        id: &str,
+        valid: i64,
+        invalid: i64,
+        abstain: i64,
+    ) -> Result<(), PulpError> {
+        self.execute(
+            "UPDATE responses SET valid_vote_count = ?2, invalid_vote_count = ?3, abstain_vote_count = ?4 WHERE id = ?1",
+            params![id, valid, invalid, abstain],
+        )
+    }
+
+    async fn update_response_appeals(
+        &self,
+        id: &str,
+        ethos: f64,
+        pathos: f64,
+        logos: f64,
+    ) -> Result<(), PulpError> {
+        self.execute(
+            "UPDATE responses SET ethos = ?2, pathos = ?3, logos = ?4 WHERE id = ?1",
+            params![id, ethos, pathos, logos],
+        )
+    }
+
+    async fn update_response_attributes(
+        &self,
+        id: &str,
+        attributes: &Attributes,
+    ) -> Result<(), PulpError> {
+        self.execute(
+            "UPDATE responses SET relevance = :relevance, soundness = :soundness, reference_count = :reference_count, word_count = :word_count, mastery_vocab_words = :mastery_vocab_words, sentence_count = :sentence_count, readability = :readability, lexical_diversity = :lexical_diversity, statistic_count = :statistic_count, spelling_errors = :spelling_errors, grammar = :grammar, failed_scorers = :failed_scorers WHERE id = :id",
+            named_params! {
+                ":id": id,
+                ":relevance": attributes.relevance,
+                ":soundness": attributes.soundness,
+                ":reference_count": attributes.references,
+                ":word_count": attributes.word_count,
+                ":mastery_vocab_words": to_json(&attributes.mastery_vocab_words)?,
+                ":sentence_count": attributes.sentence_count,
+                ":readability": attributes.readability,
+                ":lexical_diversity": attributes.lexical_diversity,
+                ":statistic_count": attributes.statistics,
+                ":spelling_errors": attributes.spelling_errors,
+                ":grammar": attributes.grammar,
+                ":failed_scorers": to_json(&attributes.failed_scorers)?,
+            },
+        )
+    }
+
+    async fn update_response_engagements(
+        &self,
+        id: &str,
+        engagements: &Engagements,
+    ) -> Result<(), PulpError> {
+        self.execute(
+            "UPDATE responses SET report_harmful_to_others = ?2, report_abuse_of_platform = ?3, hide = ?4, vote_validity = ?5, vote_confidence = ?6, response_distance = ?7, response_timing = ?8 WHERE id = ?1",
+            params![
+                id,
+                engagements.report_harmful_to_others,
+                engagements.report_abuseof_platform,
+                engagements.hide,
+                engagements.vote_validity,
+                engagements.vote_condfidence,
+                engagements.response_distance,
+                engagements.response_timing
+            ],
+        )
+    }
+
+    async fn delete_response(&self, id: &str) -> Result<(), PulpError> {
+        self.delete("responses", id)
+    }
+
+    async fn create_reference(&self, reference: &Reference) -> Result<String, PulpError> {
+        let id = new_id(&reference.id);
+
+        self.execute(
+            "INSERT INTO \"references\" (id, internal, trust, distrust, content, run_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                id,
+                reference.internal,
+                reference.trust,
+                reference.distrust,
+                reference.content,
+                self.run_id()
+            ],
+        )?;
+
+        Ok(id)
+    }
+
+    async fn get_reference(&self, id: &str) -> Result<Reference, PulpError> {
+        self.row(
+            "SELECT * FROM \"references\" WHERE id = ?1",
+            "reference",
+            id,
+            |row| {
+                Ok(Reference::new(
+                    row.get("id")?,
+                    row.get("internal")?,
+                    row.get("trust")?,
+                    row.get("distrust")?,
+                    row.get("content")?,
+                ))
+            },
+        )
+    }
+
+    async fn update_reference(&self, reference: &Reference) -> Result<(), PulpError> {
+        self.execute(
+            "UPDATE \"references\" SET internal = ?2, trust = ?3, distrust = ?4, content = ?5 WHERE id = ?1",
+            params![
+                reference.id,
+                reference.internal,
+                reference.trust,
+                reference.distrust,
+                reference.content
+            ],
+        )
+    }
+
+    async fn delete_reference(&self, id: &str) -> Result<(), PulpError> {
+        self.delete("\"references\"", id)
+    }
+
+    async fn create_vote(&self, vote: &Vote) -> Result<String, PulpError> {
+        let id = new_id(&vote.id);
+
+        // Like the Neo4j store, a vote on a missing response is not kept
+        self.execute(
+            "INSERT INTO votes (id, vote_type, reason, debate_id, response_id, prompt_name, prompt_version, run_id) SELECT ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8 WHERE EXISTS (SELECT 1 FROM responses WHERE id = ?5)",
+            params![
+                id,
+                vote.vote_type.to_string(),
+                vote.vote_type.reason(),
+                vote.debate_id,
+                vote.response_id,
+                vote.prompt_name,
+                vote.prompt_version,
+                self.run_id()
+            ],
+        )?;
+
+        Ok(id)
+    }
+
+    async fn votes(&self, id: &str) -> Result<Vec<Vote>, PulpError> {
+        self.rows(
+            "SELECT * FROM votes WHERE response_id = ?1 ORDER BY rowid",
+            params![id],
+            vote_from_row,
+        )
+    }
+
+    async fn create_blog(&self, blog: &Blog) -> Result<String, PulpError> {
+        let id = new_id(&blog.id);
+
+        self.execute(
+            "INSERT INTO blogs (id, title, body, author, topic, category, publish_timestamp, ethos, pathos, logos, score, engagements, impressions, run_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            params![
+                id,
+                blog.title,
+                blog.body,
+                blog.author,
+                blog.topic,
+                blog.category,
+                blog.publish_timestamp,
+                blog.ethos,
+                blog.pathos,
+                blog.logos,
+                blog.score,
+                blog.engagements,
+                blog.impressions,
+                self.run_id()
+            ],
+        )?;
+
+        Ok(id)
+    }
+
+    async fn get_blog(&self, id: &str) -> Result<Blog, PulpError> {
+        self.row("SELECT * FROM blogs WHERE id = ?1", "blog", id, |row| {
+            Ok(Blog::new(
+                row.get("id")?,
+                row.get("title")?,
+                row.get("body")?,
+                row.get("author")?,
+                row.get("topic")?,
+                row.get("category")?,
+                row.get("publish_timestamp")?,
+                row.get("ethos")?,
+                row.get("pathos")?,
+                row.get("logos")?,
+                row.get("score")?,
+                row.get("engagements")?,
+                row.get("impressions")?,
+            ))
+        })
+    }
+
+    async fn update_blog(&self, blog: &Blog) -> Result<(), PulpError> {
+        self.execute(
+            "UPDATE blogs SET title = ?2, body = ?3, author = ?4, topic = ?5, category = ?6, publish_timestamp = ?7, ethos = ?8, pathos = ?9, logos = ?10, score = ?11, engagements = ?12, impressions = ?13 WHERE id = ?1",
+            params![
+                blog.id,
+                blog.title,
+                blog.body,
+                blog.author,
+                blog.topic,
+                blog.category,
+                blog.publish_timestamp,
+                blog.ethos,
+                blog.pathos,
+                blog.logos,
+                blog.score,
+                blog.engagements,
+                blog.impressions
+            ],
+        )
+    }
+
+    async fn delete_blog(&self, id: &str) -> Result<(), PulpError> {
+        self.delete("blogs", id)
+    }
+
+    async fn create_personas_user(&self, user: &PersonasUser) -> Result<String, PulpError> {
+        let id = new_id(&user.base_user.id);
+        let mut user = user.clone();
+        user.base_user.id = id.clone();
+
+        self.upsert_personas_user(&user)?;
+
+        Ok(id)
+    }
+
+    async fn get_personas_user(&self, id: &str) -> Result<PersonasUser, PulpError> {
+        self.row(
+            "SELECT * FROM personas WHERE id = ?1",
+            "persona",
+            id,
+            personas_user_from_row,
+        )
+    }
+
+    async fn get_all_personas_users(&self) -> Result<Vec<PersonasUser>, PulpError> {
+        self.rows("SELECT * FROM personas", [], personas_user_from_row)
+    }
+
+    async fn update_personas_user(&self, user: &PersonasUser) -> Result<(), PulpError> {
+        let exists: bool = self
+            .conn()
+            .query_row(
+                "SELECT EXISTS (SELECT 1 FROM personas WHERE id = ?1)",
+                params![user.base_user.id],
+                |row| row.get(0),
+            )
+            .map_err(sqlite_error)?;
+
+        if !exists {
+            return Ok(());
+        }
+
+        self.upsert_personas_user(user)
+    }
+
+    async fn delete_personas_user(&self, id: &str) -> Result<(), PulpError> {
+        self.delete("personas", id)
+    }
+
+    async fn create_learned(&self, learned: &Learned) -> Result<String, PulpError> {
+        let id = new_id(&learned.id);
+
+        self.execute(
+            "INSERT INTO learned (id, learned_content, reason, run_id) VALUES (?1, ?2, ?3, ?4)",
+            params![id, learned.learned_content, learned.reason, self.run_id()],
+        )?;
+
+        Ok(id)
+    }
+
+    async fn get_learned(&self, id: &str) -> Result<Learned, PulpError> {
+        self.row(
+            "SELECT * FROM learned WHERE id = ?1",
+            "learned",
+            id,
+            |row| {
+                Ok(Learned::new(
+                    row.get("id")?,
+                    row.get("learned_content")?,
+                    row.get("reason")?,
+                ))
+            },
+        )
+    }
+
+    async fn update_learned(&self, learned: &Learned) -> Result<(), PulpError> {
+        self.execute(
+            "UPDATE learned SET learned_content = ?2, reason = ?3 WHERE id = ?1",
+            params![learned.id, learned.learned_content, learned.reason],
+        )
+    }
+
+    async fn delete_learned(&self, id: &str) -> Result<(), PulpError> {
+        self.delete("learned", id)
+    }
+
+    async fn start_run(&self, run: &SimulationRun) -> Result<(), PulpError> {
+        self.execute(
+            "INSERT INTO runs (id, simulation, config_file, config_hash, seed, started_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                run.id,
+                run.simulation,
+                run.config_file,
+                run.config_hash,
+                run.seed,
+                run.started_at
+            ],
+        )?;
+
+        *self.run.lock().unwrap() = Some(run.id.clone());
+
+        Ok(())
+    }
+
+    async fn get_run(&self, id: &str) -> Result<SimulationRun, PulpError> {
+        self.row("SELECT * FROM runs WHERE id = ?1", "run", id, run_from_row)
+    }
+
+    async fn get_all_runs(&self) -> Result<Vec<SimulationRun>, PulpError> {
+        self.rows(
+            "SELECT * FROM runs ORDER BY started_at",
+            params![],
+            run_from_row,
+        )
+    }
+
+    async fn run_counts(&self, id: &str) -> Result<Vec<(String, i64)>, PulpError> {
+        let conn = self.conn();
+        let count = |table: &str| -> Result<i64, PulpError> {
+            conn.query_row(
+                &format!("SELECT count(*) FROM {} WHERE run_id = ?1", table),
+                params![id],
+                |row| row.get(0),
+            )
+            .map_err(sqlite_error)
+        };
+
+        let mut counts = Vec::new();
+        for (table, label) in ENTITY_TABLES {
+            let n = count(table)?;
+            if n > 0 {
+                counts.push((label.to_string(), n));
+            }
+        }
+        counts.sort();
+
+        let mut relationships = count("votes")?;
+        for table in EDGE_TABLES {
+            relationships += count(table)?;
+        }
+        counts.push(("relationships".to_string(), relationships));
+
+        Ok(counts)
+    }
+
+    async fn delete_run(&self, id: &str) -> Result<(), PulpError> {
+        let written = ENTITY_TABLES
+            .iter()
+            .map(|(table, _)| format!("SELECT id FROM {} WHERE run_id = ?1", table))
+            .collect::<Vec<_>>()
+            .join(" UNION ALL ");
+
+        // Relationships of the run and of the entities it wrote go first, then the entities themselves
+        let mut statements = vec![
+            format!(
+                "DELETE FROM replies WHERE run_id = ?1 OR response_id IN ({0}) OR reply_id IN ({0})",
+                written
+            ),
+            format!(
+                "DELETE FROM authorships WHERE run_id = ?1 OR author_id IN ({0}) OR response_id IN ({0})",
+                written
+            ),
+            format!(
+                "DELETE FROM edges WHERE run_id = ?1 OR from_id IN ({0}) OR to_id IN ({0})",
+                written
+            ),
+            format!(
+                "DELETE FROM votes WHERE run_id = ?1 OR response_id IN ({})",
+                written
+            ),
+            format!(
+                "DELETE FROM scores WHERE run_id = ?1 OR version_id IN ({0}) OR entity_id IN ({0})",
+                written
+            ),
+        ];
+        statements.extend(
+            ENTITY_TABLES
+                .iter()
+                .map(|(table, _)| format!("DELETE FROM {} WHERE run_id = ?1", table)),
+        );
+        statements.push("DELETE FROM runs WHERE id = ?1".to_string());
+
+        let mut conn = self.conn();
+        let tx = conn.transaction().map_err(sqlite_error)?;
+
+        for sql in statements {
+            tx.execute(&sql, params![id]).map_err(sqlite_error)?;
+        }
+
+        tx.commit().map_err(sqlite_error)?;
+
+        let mut run = self.run.lock().unwrap();
+        if run.as_deref() == Some(id) {
+            *run = None;
+        }
+
+        Ok(())
+    }
+
+    async fn run_entities(&self, id: &str, label: &str) -> Result<Vec<String>, PulpError> {
+        match ENTITY_TABLES.iter().find(|(_, l)| *l == label) {
+            Some((table, _)) => self.ids(
+                &format!("SELECT id FROM {} WHERE run_id = ?1 ORDER BY rowid", table),
+                id,
+            ),
+
+            None => Ok(Vec::new()),
+        }
+    }
+
+    async fn create_score_version(
+        &self,
+        version: &ScoreVersion,
+        scores: &[Score],
+    ) -> Result<String, PulpError> {
+        let id = new_id(&version.id);
+
+        let mut conn = self.conn();
+        let tx = conn.transaction().map_err(sqlite_error)?;
+
+        tx.execute(
+            "INSERT INTO score_versions (id, run_id, policy, scored_at) VALUES (?1, ?2, ?3, ?4)",
+            params![id, version.run_id, version.policy, version.scored_at],
+        )
+        .map_err(sqlite_error)?;
+
+        for score in scores {
+            tx.execute(
+                "INSERT INTO scores (version_id, entity_id, label, content, engagement, score, run_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    id,
+                    score.entity_id,
+                    score.label,
+                    score.content,
+                    score.engagement,
+                    score.score,
+                    version.run_id
+                ],
+            )
+            .map_err(sqlite_error)?;
+        }
+
+        tx.commit().map_err(sqlite_error)?;
+
+        Ok(id)
+    }
+
+    async fn get_score_versions(&self, id: &str) -> Result<Vec<ScoreVersion>, PulpError> {
+        self.rows(
+            "SELECT * FROM score_versions WHERE run_id = ?1 ORDER BY scored_at, rowid",
+            params![id],
+            score_version_from_row,
+        )
+    }
+
+    async fn get_scores(&self, id: &str) -> Result<Vec<Score>, PulpError> {
+        self.rows(
+            "SELECT * FROM scores WHERE version_id = ?1 ORDER BY rowid",
+            params![id],
+            score_from_row,
+        )
+    }
+
+    async fn relate(&self, relationship: Relationship) -> Result<(), PulpError> {
+        let (from, to) = relationship.ends();
+        let (from_label, to_label) = relationship.labels();
+        if !self.has_label(from, from_label)? || !self.has_label(to, to_label)? {
+            return Ok(());
+        }
+
+        let run = self.run_id();
+
+        match relationship.edge() {
+            Edge::Replied => self.execute(
+                "INSERT OR IGNORE INTO replies (response_id, reply_id, run_id) VALUES (?1, ?2, ?3)",
+                params![from, to, run],
+            ),
+
+            Edge::Responded => self.execute(
+                "INSERT OR IGNORE INTO authorships (author_id, response_id, run_id) VALUES (?1, ?2, ?3)",
+                params![from, to, run],
+            ),
+
+            edge => self.execute(
+                "INSERT OR IGNORE INTO edges (from_id, edge, to_id, run_id) VALUES (?1, ?2, ?3, ?4)",
+                params![from, edge.as_str(), to, run],
+            ),
+        }
+    }
+
+    async fn outgoing(&self, id: &str, edge: Edge) -> Result<Vec<String>, PulpError> {
+        match edge {
+            Edge::Replied => self.ids("SELECT reply_id FROM replies WHERE response_id = ?1", id),
+
+            Edge::Responded => self.ids(
+                "SELECT response_id FROM authorships WHERE author_id = ?1",
+                id,
+            ),
+
+            Edge::VotedOn => self.ids("SELECT response_id FROM votes WHERE id = ?1", id),
+
+            edge => self.rows(
+                "SELECT to_id FROM edges WHERE from_id = ?1 AND edge = ?2",
+                params![id, edge.as_str()],
+                |row| row.get(0),
+            ),
+        }
+    }
+
+    async fn incoming(&self, id: &str, edge: Edge) -> Result<Vec<String>, PulpError> {
+        match edge {
+            Edge::Replied => self.ids("SELECT response_id FROM replies WHERE reply_id = ?1", id),
+
+            Edge::Responded => self.ids(
+                "SELECT author_id FROM authorships WHERE response_id = ?1",
+                id,
+            ),
+
+            Edge::VotedOn => self.ids("SELECT id FROM votes WHERE response_id = ?1", id),
+
+            edge => self.rows(
+                "SELECT from_id FROM edges WHERE to_id = ?1 AND edge = ?2",
+                params![id, edge.as_str()],
+                |row| row.get(0),
+            ),
+        }
+    }
+}
+
+/// Applies every migration past the database `user_version`, each in its own transaction
+fn migrate(conn: &mut Connection) -> Result<(), PulpError> {
+    let applied: usize = conn
+        .pragma_query_value(None, "user_version", |row| row.get(0))
+        .map_err(sqlite_error)?;
+
+    if applied > MIGRATIONS.len() {
+        return Err(sqlite_error(format!(
+            "database schema version {} is newer than the {} known migrations",
+            applied,
+            MIGRATIONS.len()
+        )));
+    }
+
+    for (i, migration) in MIGRATIONS.iter().enumerate().skip(applied) {
+        let tx = conn.transaction().map_err(sqlite_error)?;
+        tx.execute_batch(migration)
+            .map_err(|e| sqlite_error(format!("migration {} failed: {}", i + 1, e)))?;
+        tx.pragma_update(None, "user_version", i + 1)
+            .map_err(sqlite_error)?;
+        tx.commit().map_err(sqlite_error)?;
+    }
+
+    Ok(())
+}
+
+fn run_from_row(row: &Row<'_>) -> rusqlite::Result<SimulationRun> {
+    Ok(SimulationRun {
+        id: row.get("id")?,
+        simulation: row.get("simulation")?,
+        config_file: row.get("config_file")?,
+        config_hash: row.get("config_hash")?,
+        seed: row.get("seed")?,
+        started_at: row.get("started_at")?,
+    })
+}
+
+fn score_version_from_row(row: &Row<'_>) -> rusqlite::Result<ScoreVersion> {
+    Ok(ScoreVersion {
+        id: row.get("id")?,
+        run_id: row.get("run_id")?,
+        policy: row.get("policy")?,
+        scored_at: row.get("scored_at")?,
+    })
+}
+
+fn score_from_row(row: &Row<'_>) -> rusqlite::Result<Score> {
+    Ok(Score {
+        entity_id: row.get("entity_id")?,
+        label: row.get("label")?,
+        content: row.get("content")?,
+        engagement: row.get("engagement")?,
+        score: row.get("score")?,
+    })
+}
+
+fn debate_from_row(row: &Row<'_>) -> rusqlite::Result<Debate> {
+    Ok(Debate::new(
+        row.get("id")?,
+        row.get("score")?,
+        row.get("topic")?,
+        row.get("category")?,
+        row.get("registered_speakers")?,
+        row.get("commenters")?,
+        row.get("voters")?,
+        row.get("comments")?,
+        row.get("inactive_participants")?,
+        row.get("responses")?,
+        row.get("init_timestamp")?,
+        (row.get("registration_start")?, row.get("registration_end")?),
+        (row.get("competition_start")?, row.get("competition_end")?),
+        (row.get("rewards_start")?, row.get("rewards_end")?),
+    ))
+}
+
+fn user_from_row(row: &Row<'_>) -> rusqlite::Result<User> {
+    Ok(User::new(row.get("id")?, row.get("simulation_data")?))
+}
+
+fn response_from_row(row: &Row<'_>) -> rusqlite::Result<Response> {
+    let mut response = Response::default();
+
+    response.id = row.get("id")?;
+    response.content = row.get("content")?;
+    response.confidence = row.get("confidence")?;
+    response.score = row.get("score")?;
+    response.valid_vote_count = row.get("valid_vote_count")?;
+    response.invalid_vote_count = row.get("invalid_vote_count")?;
+    response.abstain_vote_count = row.get("abstain_vote_count")?;
+    response.report_count = row.get("report_count")?;
+    response.hide_count = row.get("hide_count")?;
+    response.topic_of_response = row.get("topic_of_response")?;
+    response.ethos = row.get("ethos")?;
+    response.pathos = row.get("pathos")?;
+    response.logos = row.get("logos")?;
+    response.attributes.relevance = row.get("relevance")?;
+    response.attributes.soundness = row.get("soundness")?;
+    response.attributes.references = row.get("reference_count")?;
+    response.attributes.word_count = row.get("word_count")?;
+    response.attributes.mastery_vocab_words = from_json(row, "mastery_vocab_words")?;
+    response.attributes.sentence_count = row.get("sentence_count")?;
+    response.attributes.readability = row.get("readability")?;
+    response.attributes.lexical_diversity = row.get("lexical_diversity")?;
+    response.attributes.statistics = row.get("statistic_count")?;
+    response.attributes.spelling_errors = row.get("spelling_errors")?;
+    response.attributes.grammar = row.get("grammar")?;
+    response.attributes.failed_scorers = from_json(row, "failed_scorers")?;
+    response.engagements.report_harmful_to_others = row.get("report_harmful_to_others")?;
+    response.engagements.report_abuseof_platform = row.get("report_abuse_of_platform")?;
+    response.engagements.hide = row.get("hide")?;
+    response.engagements.vote_validity = row.get("vote_validity")?;
+    response.engagements.vote_condfidence = row.get("vote_confidence")?;
+    response.engagements.response_distance = row.get("response_distance")?;
+    response.engagements.response_timing = row.get("response_timing")?;
+    response.prompt_name = row.get("prompt_name")?;
+    response.prompt_version = row.get("prompt_version")?;
+    response.created_at = row.get("created_at")?;
+    response.scoring_policy = row.get("scoring_policy")?;
+
+    Ok(response)
+}
+
+fn vote_from_row(row: &Row<'_>) -> rusqlite::Result<Vote> {
+    Ok(Vote::new(
+        row.get("id")?,
+        VoteType::with_reason(&row.get::<_, String>("vote_type")?, row.get("reason")?),
+        row.get("debate_id")?,
+        row.get("response_id")?,
+        row.get("prompt_name")?,
+        row.get("prompt_version")?,
+    ))
+}
+
+fn personas_user_from_row(row: &Row<'_>) -> rusqlite::Result<PersonasUser> {
+    let mut user = PersonasUser::default();
+
+    user.base_user.id = row.get("id")?;
+    user.base_user.simulation_data = row.get("simulation_data")?;
+    user.age = row.get("age")?;
+    user.gender = Gender::from(row.get::<_, String>("gender")?.as_str());
+    user.political_orientation =
+        PoliticalOrientation::from(row.get::<_, String>("political_orientation")?.as_str());
+    user.vote_valid_influence = from_json(row, "vote_valid_influence")?;
+    user.vote_invalid_influence = from_json(row, "vote_invalid_influence")?;
+    user.vote_abstain_influence = from_json(row, "vote_abstain_influence")?;
+    user.knoweledge.knowledge_references = row.get("knowledge_references")?;
+    user.network.network_size.followers = row.get("followers")?;
+    user.network.network_size.following = row.get("following")?;
+    user.network.network_activity.impressions = row.get("impressions")?;
+    user.network.network_activity.engagements = row.get("engagements")?;
+    user.personality.personality_content.input = row.get("input")?;
+    user.personality.personality_content.output = row.get("output")?;
+    user.personality.personality_base.core_fear = row.get("core_fear")?;
+    user.personality.personality_base.enneagram = row.get("enneagram")?;
+    user.personality.personality_base.core_desire = row.get("core_desire")?;
+    user.personality.personality_engagement.voting_tendency = (
+        row.get("valid_voting_tendency")?,
+        row.get("invalid_voting_tendency")?,
+        row.get("abstain_voting_tendency")?,
+    );
+    user.personality.personality_engagement.hide_tendency = row.get("hide_tendency")?;
+    user.personality.personality_engagement.report_tendency = row.get("report_tendency")?;
+    user.personality.personality_engagement.engagement_type = row.get("engagement_type")?;
+
+    Ok(user)
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<String, PulpError> {
+    serde_json::to_string(value).map_err(sqlite_error)
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(row: &Row<'_>, column: &str) -> rusqlite::Result<T> {
+    let json: String = row.get(column)?;
+
+    serde_json::from_str(&json).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+fn sqlite_error<E: ToString>(e: E) -> PulpError {
+    PulpError::SimulationError(SimulationError::SqliteError(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::personas_user::{
+        Knowledge, Network, NetworkActivity, NetworkSize, Personality, PersonalityBase,
+        PersonalityContent, PersonalityEngagement,
+    };
+    use std::{env, fmt::Debug};
+
+    /// Models have no `PartialEq`, two of them are the same when every field prints the same
+    fn assert_same<T: Debug>(got: &T, want: &T) {
+        assert_eq!(format!("{:?}", got), format!("{:?}", want));
+    }
+
+    fn response(id: &str, seed: i64) -> Response {
+        let n = seed as f64;
+
+        Response {
+            id: id.to_string(),
+            content: format!("Content {} with \"quotes\" and 'apostrophes'", seed),
+            confidence: 0.25 * n,
+            score: 100 * seed,
+            valid_vote_count: seed + 1,
+            invalid_vote_count: seed + 2,
+            abstain_vote_count: seed + 3,
+            report_count: seed + 4,
+            hide_count: seed + 5,
+            topic_of_response: format!("Topic {}", seed),
+            ethos: 0.1 * n,
+            pathos: 0.2 * n,
+            logos: 0.3 * n,
+            attributes: Attributes {
+                relevance: 0.4 * n,
+                soundness: 0.5 * n,
+                grammar: 0.6 * n,
+                references: seed + 6,
+                word_count: seed + 7,
+                mastery_vocab_words: vec![format!("word{}", seed), "\"quoted\"".to_string()],
+                sentence_count: seed + 8,
+                readability: 1.5 * n,
+                lexical_diversity: 0.05 * n,
+                statistics: seed + 9,
+                spelling_errors: seed + 10,
+                failed_scorers: vec!["grammar".to_string(), format!("scorer{}", seed)],
+            },
+            engagements: Engagements {
+                report_harmful_to_others: seed + 11,
+                report_abuseof_platform: seed + 12,
+                hide: seed + 13,
+                vote_validity: seed + 14,
+                vote_condfidence: seed + 15,
+                response_distance: seed + 16,
+                response_timing: seed + 17,
+            },
+            prompt_name: format!("prompt{}", seed),
+            prompt_version: format!("v{}", seed),
+            created_at: 1_000 * seed,
+            scoring_policy: format!("policy{}", seed),
+            ..Default::default()
+        }
+    }
+
+    fn personas_user(id: &str, seed: i64) -> PersonasUser {
+        let n = seed as f64;
+        let mut user = PersonasUser {
+            age: 20 + seed,
+            gender: if seed % 2 == 0 {
+                Gender::Female
+            } else {
+                Gender::RatherNotSay
+            },
+            political_orientation: if seed % 2 == 0 {
+                PoliticalOrientation::Left
+            } else {
+                PoliticalOrientation::Right
+            },
+            vote_valid_influence: vec![0.1 * n, 0.2],
+            vote_invalid_influence: vec![0.3 * n],
+            vote_abstain_influence: Vec::new(),
+            knoweledge: Knowledge {
+                knowledge_references: 0.4 * n,
+            },
+            network: Network {
+                network_size: NetworkSize {
+                    followers: seed + 1,
+                    following: seed + 2,
+                },
+                network_activity: NetworkActivity {
+                    impressions: seed + 3,
+                    engagements: seed + 4,
+                },
+                ..Default::default()
+            },
+            personality: Personality {
+                personality_content: PersonalityContent {
+                    input: 0.5 * n,
+                    output: 0.6 * n,
+                },
+                personality_base: PersonalityBase {
+                    core_fear: format!("fear{}", seed),
+                    enneagram: seed % 9 + 1,
+                    core_desire: format!("desire{}", seed),
+                },
+                personality_engagement: PersonalityEngagement {
+                    voting_tendency: (seed + 5, seed + 6, seed + 7),
+                    hide_tendency: 0.7 * n,
+                    report_tendency: 0.8 * n,
+                    engagement_type: seed + 8,
+                },
+            },
+            ..Default::default()
+        };
+        user.base_user = User::new(id.to_string(), format!("data{}", seed));
+
+        user
+    }
+
+    #[tokio::test]
+    async fn debates_round_trip() {
+        let store = SqliteStore::open(":memory:").unwrap();
+        let debate = |seed: i64| {
+            Debate::new(
+                "debate".to_string(),
+                seed,
+                format!("Topic {}", seed),
+                format!("Category {}", seed),
+                seed + 1,
+                seed + 2,
+                seed + 3,
+                seed + 4,
+                seed + 5,
+                seed + 6,
+                seed + 7,
+                (seed + 8, seed + 9),
+                (seed + 10, seed + 11),
+                (seed + 12, seed + 13),
+            )
+        };
+
+        let id = store.create_debate(&debate(1)).await.unwrap();
+        assert_eq!(id, "debate");
+        assert_same(&store.get_debate(&id).await.unwrap(), &debate(1));
+
+        store.update_debate(&debate(2)).await.unwrap();
+        assert_same(&store.get_debate(&id).await.unwrap(), &debate(2));
+    }
+
+    #[tokio::test]
+    async fn users_round_trip() {
+        let store = SqliteStore::open(":memory:").unwrap();
+        let user = |data: &str| User::new("user".to_string(), data.to_string());
+
+        let id = store.create_user(&user("first")).await.unwrap();
+        assert_same(&store.get_user(&id).await.unwrap(), &user("first"));
+
+        store.update_user(&user("second")).await.unwrap();
+        assert_same(&store.get_user(&id).await.unwrap(), &user("second"));
+        assert_same(&store.get_all_users().await.unwrap(), &vec![user("second")]);
+    }
+
+    #[tokio::test]
+    async fn responses_round_trip() {
+        let store = SqliteStore::open(":memory:").unwrap();
+
+        let id = store
+            .create_response(&response("response", 1))
+            .await
+            .unwrap();
+        assert_same(
+            &store.get_response(&id).await.unwrap(),
+            &response("response", 1),
+        );
+
+        store
+            .update_response(&response("response", 2))
+            .await
+            .unwrap();
+        assert_same(
+            &store.get_response(&id).await.unwrap(),
+            &response("response", 2),
+        );
+
+        // Empty JSON lists stay empty lists
+        let mut bare = response("response", 3);
+        bare.attributes.mastery_vocab_words.clear();
+        bare.attributes.failed_scorers.clear();
+        store.update_response(&bare).await.unwrap();
+        assert_same(&store.get_response(&id).await.unwrap(), &bare);
+    }
+
+    #[tokio::test]
+    async fn response_columns_update_on_their_own() {
+        let store = SqliteStore::open(":memory:").unwrap();
+        let id = store
+            .create_response(&response("response", 1))
+            .await
+            .unwrap();
+        let update = response("response", 2);
+
+        store
+            .update_response_score(&id, update.score, &update.scoring_policy)
+            .await
+            .unwrap();
+        store
+            .update_response_attributes(&id, &update.attributes)
+            .await
+            .unwrap();
+        store
+            .update_response_engagements(&id, &update.engagements)
+            .await
+            .unwrap();
+
+        let mut want = response("response", 1);
+        want.score = update.score;
+        want.scoring_policy = update.scoring_policy;
+        want.attributes = update.attributes;
+        want.engagements = update.engagements;
+        assert_same(&store.get_response(&id).await.unwrap(), &want);
+    }
+
+    #[tokio::test]
+    async fn references_round_trip() {
+        let store = SqliteStore::open(":memory:").unwrap();
+        let reference = |seed: i64| {
+            Reference::new(
+                "reference".to_string(),
+                seed % 2 == 0,
+                seed,
+                seed + 1,
+                format!("https://example.com/{}", seed),
+            )
+        };
+
+        let id = store.create_reference(&reference(1)).await.unwrap();
+        assert_same(&store.get_reference(&id).await.unwrap(), &reference(1));
+
+        store.update_reference(&reference(2)).await.unwrap();
+        assert_same(&store.get_reference(&id).await.unwrap(), &reference(2));
+    }
+
+    #[tokio::test]
+    async fn votes_round_trip_with_their_type_and_reason() {
+        let store = SqliteStore::open(":memory:").unwrap();
+        let id = store
+            .create_response(&response("response", 1))
+            .await
+            .unwrap();
+
+        let votes = [
+            VoteType::Valid(Some("sourced".to_string())),
+            VoteType::Invalid(None),
+            VoteType::Abstain(Some("unsure".to_string())),
+            VoteType::Valid(None),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(i, vote_type)| {
+            Vote::new(
+                format!("vote{}", i),
+                vote_type,
+                "debate".to_string(),
+                id.clone(),
+                "vote".to_string(),
+                format!("v{}", i),
+            )
+        })
+        .collect::<Vec<_>>();
+
+        for vote in &votes {
+            store.create_vote(vote).await.unwrap();
+        }
+
+        assert_same(&store.votes(&id).await.unwrap(), &votes);
+    }
+
+    #[tokio::test]
+    async fn blogs_round_trip() {
+        let store = SqliteStore::open(":memory:").unwrap();
+        let blog = |seed: i64| {
+            Blog::new(
+                "blog".to_string(),
+                format!("Title {}", seed),
+                format!("Body {}", seed),
+                format!("author{}", seed),
+                format!("Topic {}", seed),
+                format!("Category {}", seed),
+                seed,
+                seed + 1,
+                seed + 2,
+                seed + 3,
+                seed + 4,
+                seed + 5,
+                seed + 6,
+            )
+        };
+
+        let id = store.create_blog(&blog(1)).await.unwrap();
+        assert_same(&store.get_blog(&id).await.unwrap(), &blog(1));
+
+        store.update_blog(&blog(2)).await.unwrap();
+        assert_same(&store.get_blog(&id).await.unwrap(), &blog(2));
+    }
+
+    #[tokio::test]
+    async fn personas_users_round_trip() {
+        let store = SqliteStore::open(":memory:").unwrap();
+
+        let id = store
+            .create_personas_user(&personas_user("persona", 1))
+            .await
+            .unwrap();
+        assert_same(
+            &store.get_personas_user(&id).await.unwrap(),
+            &personas_user("persona", 1),
+        );
+
+        store
+            .update_personas_user(&personas_user("persona", 2))
+            .await
+            .unwrap();
+        assert_same(
+            &store.get_personas_user(&id).await.unwrap(),
+            &personas_user("persona", 2),
+        );
+        assert_same(
+            &store.get_all_personas_users().await.unwrap(),
+            &vec![personas_user("persona", 2)],
+        );
+    }
+
+    #[tokio::test]
+    async fn learned_round_trips() {
+        let store = SqliteStore::open(":memory:").unwrap();
+        let learned = |seed: i64| {
+            Learned::new(
+                "learned".to_string(),
+                format!("Learned {}", seed),
+                format!("Reason {}", seed),
+            )
+        };
+
+        let id = store.create_learned(&learned(1)).await.unwrap();
+        assert_same(&store.get_learned(&id).await.unwrap(), &learned(1));
+
+        store.update_learned(&learned(2)).await.unwrap();
+        assert_same(&store.get_learned(&id).await.unwrap(), &learned(2));
+    }
+
+    fn user_version(conn: &Connection) -> usize {
+        conn.pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn migrations_apply_in_order_once() {
+        let path = env::temp_dir().join(format!("pulpcalc-sqlite-{}.db", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        // A database left at the second migration, holding a response written before `created_at`
+        {
+            let conn = Connection::open(path).unwrap();
+            for migration in &MIGRATIONS[..2] {
+                conn.execute_batch(migration).unwrap();
+            }
+            conn.pragma_update(None, "user_version", 2).unwrap();
+            conn.execute(
+                "INSERT INTO responses (id, content) VALUES ('old', 'Written before created_at')",
+                [],
+            )
+            .unwrap();
+        }
+
+        let store = SqliteStore::open(path).unwrap();
+        assert_eq!(user_version(&store.conn()), MIGRATIONS.len());
+        let old = store.get_response("old").await.unwrap();
+        assert_eq!(old.content, "Written before created_at");
+        assert_eq!(old.created_at, 0);
+        assert!(old.attributes.failed_scorers.is_empty());
+
+        store.create_response(&response("new", 1)).await.unwrap();
+        drop(store);
+
+        // Reopening applies nothing and keeps every row
+        let store = SqliteStore::open(path).unwrap();
+        assert_eq!(user_version(&store.conn()), MIGRATIONS.len());
+        assert_same(
+            &store.get_response("new").await.unwrap(),
+            &response("new", 1),
+        );
+        assert!(store.get_response("old").await.is_ok());
+        drop(store);
+
+        // A database migrated by a newer build is not opened
+        Connection::open(path)
+            .unwrap()
+            .pragma_update(None, "user_version", MIGRATIONS.len() + 1)
+            .unwrap();
+        let newer = SqliteStore::open(path);
+        std::fs::remove_file(path).unwrap();
+
+        assert!(newer.is_err());
+    }
+}
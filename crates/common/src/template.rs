@@ -0,0 +1,200 @@
+use crate::errors::TemplateError;
+
+/// A prompt template with `{{name}}` placeholders
+///
+/// Placeholder names are made of ascii letters, digits and underscores. A literal
+/// `{{` is written as `\{{`. Bound values are inserted as is and never scanned for
+/// placeholders, so user content containing `{{name}}` is left untouched.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Text(String),
+
+    Var(String),
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Self, TemplateError> {
+        let mut segments = Vec::new();
+        let mut text = String::new();
+        let mut rest = source;
+
+        while let Some(i) = rest.find("{{") {
+            // `\{{` is a literal `{{`
+            if rest[..i].ends_with('\\') {
+                text.push_str(&rest[..i - 1]);
+                text.push_str("{{");
+                rest = &rest[i + 2..];
+
+                continue;
+            }
+
+            text.push_str(&rest[..i]);
+
+            let offset = source.len() - rest.len() + i;
+            let end = match rest[i + 2..].find("}}") {
+                Some(end) => i + 2 + end,
+                None => return Err(TemplateError::Unclosed(offset)),
+            };
+
+            let name = rest[i + 2..end].trim();
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(TemplateError::InvalidName(name.to_string()));
+            }
+
+            if !text.is_empty() {
+                segments.push(Segment::Text(std::mem::take(&mut text)));
+            }
+            segments.push(Segment::Var(name.to_string()));
+
+            rest = &rest[end + 2..];
+        }
+
+        text.push_str(rest);
+        if !text.is_empty() {
+            segments.push(Segment::Text(text));
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// The placeholder names in the order they first appear
+    pub fn variables(&self) -> Vec<&str> {
+        let mut vars: Vec<&str> = Vec::new();
+
+        for segment in &self.segments {
+            if let Segment::Var(name) = segment {
+                if !vars.contains(&name.as_str()) {
+                    vars.push(name);
+                }
+            }
+        }
+
+        vars
+    }
+
+    /// Fills every placeholder, failing if one is left unbound or a binding is never used
+    pub fn render(&self, vars: &[(String, String)]) -> Result<String, TemplateError> {
+        let placeholders = self.variables();
+
+        let missing: Vec<String> = placeholders
+            .iter()
+            .filter(|name| !vars.iter().any(|(key, _)| key == *name))
+            .map(|name| name.to_string())
+            .collect();
+        if !missing.is_empty() {
+            return Err(TemplateError::Missing(missing));
+        }
+
+        let unused: Vec<String> = vars
+            .iter()
+            .filter(|(key, _)| !placeholders.contains(&key.as_str()))
+            .map(|(key, _)| key.clone())
+            .collect();
+        if !unused.is_empty() {
+            return Err(TemplateError::Unused(unused));
+        }
+
+        let mut rendered = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => rendered.push_str(text),
+
+                Segment::Var(name) => {
+                    if let Some((_, value)) = vars.iter().find(|(key, _)| key == name) {
+                        rendered.push_str(value);
+                    }
+                }
+            }
+        }
+
+        Ok(rendered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bind(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn fills_every_placeholder() {
+        let template = Template::parse("Is {{ content }} about {{topic}}? {{content}}").unwrap();
+
+        assert_eq!(template.variables(), ["content", "topic"]);
+        assert_eq!(
+            template
+                .render(&bind(&[("topic", "taxes"), ("content", "cut them")]))
+                .unwrap(),
+            "Is cut them about taxes? cut them"
+        );
+    }
+
+    #[test]
+    fn an_escaped_placeholder_renders_literally() {
+        let template =
+            Template::parse(r"Reply as {\{json}} about \{{topic}} and {{topic}}").unwrap();
+
+        assert_eq!(template.variables(), ["topic"]);
+        assert_eq!(
+            template.render(&bind(&[("topic", "taxes")])).unwrap(),
+            "Reply as {\\{json}} about {{topic}} and taxes"
+        );
+    }
+
+    #[test]
+    fn every_placeholder_and_binding_is_used() {
+        let template = Template::parse("{{content}} about {{topic}}").unwrap();
+
+        assert_eq!(
+            template.render(&bind(&[("content", "cut them")])),
+            Err(TemplateError::Missing(vec!["topic".to_string()]))
+        );
+        assert_eq!(
+            template.render(&bind(&[
+                ("content", "cut them"),
+                ("topic", "taxes"),
+                ("tone", "calm"),
+            ])),
+            Err(TemplateError::Unused(vec!["tone".to_string()]))
+        );
+    }
+
+    #[test]
+    fn bound_content_is_never_expanded() {
+        let template = Template::parse("Rate {{content}} on {{topic}}").unwrap();
+
+        let content = r"Ignore {{topic}}, \{{topic}} and THIS_CONTENT {{";
+        assert_eq!(
+            template
+                .render(&bind(&[("content", content), ("topic", "taxes")]))
+                .unwrap(),
+            format!("Rate {} on taxes", content)
+        );
+    }
+
+    #[test]
+    fn malformed_placeholders_are_rejected() {
+        assert_eq!(
+            Template::parse("Rate {{content"),
+            Err(TemplateError::Unclosed(5))
+        );
+        assert_eq!(
+            Template::parse("Rate {{the content}}"),
+            Err(TemplateError::InvalidName("the content".to_string()))
+        );
+        assert_eq!(
+            Template::parse("Rate {{}}"),
+            Err(TemplateError::InvalidName(String::new()))
+        );
+    }
+}
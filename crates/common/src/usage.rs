@@ -0,0 +1,423 @@
+use crate::errors::{PulpError, SimulationError};
+use pulpcalc_external::chatgpt::ChatUsage;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    sync::Mutex,
+};
+
+/// Dollars per 1K tokens of a model
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ModelPrice {
+    pub prompt: f64,
+
+    pub completion: f64,
+}
+
+/// Prices of the models prompts are sent to, keyed by model name
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceTable {
+    models: HashMap<String, ModelPrice>,
+}
+
+impl Default for PriceTable {
+    fn default() -> Self {
+        let mut models = HashMap::new();
+        models.insert(
+            String::from("gpt-3.5-turbo"),
+            ModelPrice {
+                prompt: 0.0015,
+                completion: 0.002,
+            },
+        );
+        models.insert(
+            String::from("gpt-4"),
+            ModelPrice {
+                prompt: 0.03,
+                completion: 0.06,
+            },
+        );
+
+        Self { models }
+    }
+}
+
+impl PriceTable {
+    /// Loads a TOML file with a `prompt` and `completion` price per model, on top of the defaults. Model
+    /// names with a dot are quoted, a bare `[gpt-3.5-turbo]` is the table `5-turbo` within `gpt-3`
+    ///
+    /// ```toml
+    /// ["gpt-3.5-turbo"]
+    /// prompt = 0.0015
+    /// completion = 0.002
+    /// ```
+    pub fn load(path: &str) -> Result<Self, PulpError> {
+        let source = fs::read_to_string(path).map_err(|e| usage_error(path, e))?;
+        let models: HashMap<String, ModelPrice> =
+            toml::from_str(&source).map_err(|e| usage_error(path, e))?;
+
+        let mut table = Self::default();
+        table.models.extend(models);
+
+        Ok(table)
+    }
+
+    /// Price of `model`, falling back to the longest priced prefix so `gpt-4-0613` is priced as `gpt-4`
+    pub fn price(&self, model: &str) -> Option<&ModelPrice> {
+        if let Some(price) = self.models.get(model) {
+            return Some(price);
+        }
+
+        self.models
+            .iter()
+            .filter(|(name, _)| model.starts_with(name.as_str()))
+            .max_by_key(|(name, _)| name.len())
+            .map(|(_, price)| price)
+    }
+
+    /// Dollar cost of `usage`, zero for models missing from the table
+    pub fn cost(&self, model: &str, usage: &ChatUsage) -> f64 {
+        match self.price(model) {
+            Some(price) => {
+                (usage.prompt_tokens as f64 * price.prompt
+                    + usage.completion_tokens as f64 * price.completion)
+                    / 1000.0
+            }
+
+            None => 0.0,
+        }
+    }
+}
+
+/// Token and dollar ceilings of a run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Budget {
+    pub max_tokens: Option<i64>,
+
+    pub max_cost: Option<f64>,
+}
+
+impl Budget {
+    /// Adds a ceiling written as a token count, i.e. `200000` or `200k`, or as dollars, i.e. `$2.50`
+    pub fn limit(mut self, limit: &str) -> Result<Self, PulpError> {
+        let limit = limit.trim();
+        let invalid = || {
+            PulpError::SimulationError(SimulationError::SimError(format!(
+                "invalid budget {}, expected a token count like 200000 or 200k, or dollars like $2.50",
+                limit
+            )))
+        };
+
+        if let Some(dollars) = limit.strip_prefix('$') {
+            self.max_cost = Some(dollars.parse::<f64>().map_err(|_| invalid())?);
+        } else if let Some(thousands) = limit.strip_suffix(['k', 'K']) {
+            self.max_tokens = Some(thousands.parse::<i64>().map_err(|_| invalid())? * 1000);
+        } else {
+            self.max_tokens = Some(limit.parse::<i64>().map_err(|_| invalid())?);
+        }
+
+        Ok(self)
+    }
+
+    pub fn is_exceeded(&self, totals: &UsageTotals) -> bool {
+        self.max_tokens
+            .is_some_and(|max| totals.total_tokens >= max)
+            || self.max_cost.is_some_and(|max| totals.cost >= max)
+    }
+}
+
+/// Tokens used and their cost
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct UsageTotals {
+    pub requests: i64,
+
+    /// Requests answered from the response cache, which use no tokens
+    pub cached: i64,
+
+    pub prompt_tokens: i64,
+
+    pub completion_tokens: i64,
+
+    pub total_tokens: i64,
+
+    pub cost: f64,
+}
+
+impl UsageTotals {
+    fn add(&mut self, usage: &ChatUsage, cost: f64) {
+        self.requests += 1;
+        self.prompt_tokens += usage.prompt_tokens;
+        self.completion_tokens += usage.completion_tokens;
+        self.total_tokens += usage.total_tokens;
+        self.cost += cost;
+    }
+}
+
+/// Usage of a run, in total and broken down per debate, prompt family and model
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct UsageSummary {
+    pub total: UsageTotals,
+
+    pub debates: BTreeMap<String, UsageTotals>,
+
+    pub prompts: BTreeMap<String, UsageTotals>,
+
+    pub models: BTreeMap<String, UsageTotals>,
+
+    /// Whether the run stopped early because the budget was reached
+    pub budget_exceeded: bool,
+}
+
+/// Accumulates the usage reported with every LLM reply
+#[derive(Debug, Default)]
+pub struct UsageLedger {
+    summary: Mutex<UsageSummary>,
+}
+
+impl UsageLedger {
+    /// Records a reply to the `prompt` family sent to `model`, for `debate` if the prompt belongs to one
+    pub fn record(
+        &self,
+        debate: Option<&str>,
+        prompt: &str,
+        model: &str,
+        usage: &ChatUsage,
+        cost: f64,
+    ) {
+        let mut summary = self.summary.lock().unwrap();
+
+        summary.total.add(usage, cost);
+        if let Some(debate) = debate {
+            summary
+                .debates
+                .entry(debate.to_string())
+                .or_default()
+                .add(usage, cost);
+        }
+        summary
+            .prompts
+            .entry(prompt.to_string())
+            .or_default()
+            .add(usage, cost);
+        summary
+            .models
+            .entry(model.to_string())
+            .or_default()
+            .add(usage, cost);
+    }
+
+    /// Records a reply served from the response cache
+    pub fn record_cached(&self, debate: Option<&str>, prompt: &str, model: &str) {
+        let mut summary = self.summary.lock().unwrap();
+
+        summary.total.cached += 1;
+        if let Some(debate) = debate {
+            summary
+                .debates
+                .entry(debate.to_string())
+                .or_default()
+                .cached += 1;
+        }
+        summary
+            .prompts
+            .entry(prompt.to_string())
+            .or_default()
+            .cached += 1;
+        summary.models.entry(model.to_string()).or_default().cached += 1;
+    }
+
+    pub fn total(&self) -> UsageTotals {
+        self.summary.lock().unwrap().total.clone()
+    }
+
+    /// Fails once the totals reach the budget, so no further prompts are sent
+    pub fn check_budget(&self, budget: &Budget) -> Result<(), PulpError> {
+        let mut summary = self.summary.lock().unwrap();
+
+        if budget.is_exceeded(&summary.total) {
+            summary.budget_exceeded = true;
+
+            return Err(PulpError::SimulationError(SimulationError::BudgetExceeded(
+                format!(
+                    "{} tokens, ${:.4} spent",
+                    summary.total.total_tokens, summary.total.cost
+                ),
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub fn summary(&self) -> UsageSummary {
+        self.summary.lock().unwrap().clone()
+    }
+
+    /// Writes the summary to `path` as JSON
+    pub fn write_summary(&self, path: &str) -> Result<(), PulpError> {
+        let json =
+            serde_json::to_string_pretty(&self.summary()).map_err(|e| usage_error(path, e))?;
+
+        fs::write(path, json).map_err(|e| usage_error(path, e))
+    }
+}
+
+fn usage_error<E: ToString>(path: &str, e: E) -> PulpError {
+    PulpError::SimulationError(SimulationError::SimError(format!(
+        "{}: {}",
+        path,
+        e.to_string()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn usage(prompt_tokens: i64, completion_tokens: i64) -> ChatUsage {
+        ChatUsage {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens + completion_tokens,
+        }
+    }
+
+    fn assert_close(left: f64, right: f64) {
+        assert!((left - right).abs() < 1e-9, "{} != {}", left, right);
+    }
+
+    #[test]
+    fn budgets_are_token_counts_or_dollars() {
+        let budget = Budget::default().limit("200k").unwrap();
+        assert_eq!(budget.max_tokens, Some(200_000));
+
+        let budget = budget.limit(" $2.50 ").unwrap();
+        assert_eq!(budget.max_tokens, Some(200_000));
+        assert_eq!(budget.max_cost, Some(2.5));
+
+        assert_eq!(
+            Budget::default().limit("1500").unwrap().max_tokens,
+            Some(1500)
+        );
+        assert_eq!(
+            Budget::default().limit("3K").unwrap().max_tokens,
+            Some(3000)
+        );
+
+        for invalid in ["", "k", "$", "2.5k", "$two", "200m", "-"] {
+            assert!(
+                matches!(
+                    Budget::default().limit(invalid),
+                    Err(PulpError::SimulationError(SimulationError::SimError(_)))
+                ),
+                "{}",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn usage_is_priced_per_1k_tokens() {
+        let prices = PriceTable::default();
+
+        assert_close(prices.cost("gpt-3.5-turbo", &usage(1000, 500)), 0.0025);
+        assert_close(prices.cost("gpt-4", &usage(2000, 1000)), 0.06 + 0.06);
+
+        // The longest priced prefix prices a model version, unpriced models are free
+        assert_close(prices.cost("gpt-4-0613", &usage(1000, 0)), 0.03);
+        assert_close(prices.cost("gpt-3.5-turbo-16k", &usage(0, 1000)), 0.002);
+        assert_close(prices.cost("llama-2", &usage(1000, 1000)), 0.0);
+    }
+
+    #[test]
+    fn a_price_file_adds_to_the_default_prices() {
+        let path = env::temp_dir().join(format!("pulpcalc-prices-{}.toml", std::process::id()));
+        fs::write(
+            &path,
+            "[\"gpt-3.5-turbo-16k\"]\nprompt = 0.003\ncompletion = 0.004\n\n[gpt-4]\nprompt = 0.02\ncompletion = 0.05\n",
+        )
+        .unwrap();
+
+        let prices = PriceTable::load(path.to_str().unwrap()).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_close(prices.cost("gpt-3.5-turbo-16k", &usage(1000, 1000)), 0.007);
+        assert_close(prices.cost("gpt-3.5-turbo", &usage(1000, 1000)), 0.0035);
+        assert_close(prices.cost("gpt-4-0613", &usage(1000, 1000)), 0.07);
+        assert!(PriceTable::load("/nonexistent/prices.toml").is_err());
+    }
+
+    #[test]
+    fn the_ledger_adds_up_usage_per_debate_prompt_and_model() {
+        let ledger = UsageLedger::default();
+
+        ledger.record(Some("d1"), "grammar", "gpt-3.5-turbo", &usage(100, 10), 0.5);
+        ledger.record(Some("d1"), "vote", "gpt-4", &usage(200, 20), 1.0);
+        ledger.record(Some("d2"), "grammar", "gpt-4", &usage(300, 30), 2.0);
+        ledger.record(None, "enneagram_tendency", "gpt-4", &usage(400, 40), 4.0);
+        ledger.record_cached(Some("d2"), "grammar", "gpt-4");
+
+        let summary = ledger.summary();
+        let totals = |requests, cached, prompt_tokens, completion_tokens, cost| UsageTotals {
+            requests,
+            cached,
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens + completion_tokens,
+            cost,
+        };
+
+        assert_eq!(summary.total, totals(4, 1, 1000, 100, 7.5));
+        assert_eq!(ledger.total(), summary.total);
+
+        // Prompts outside a debate count towards the total alone
+        assert_eq!(summary.debates.len(), 2);
+        assert_eq!(summary.debates["d1"], totals(2, 0, 300, 30, 1.5));
+        assert_eq!(summary.debates["d2"], totals(1, 1, 300, 30, 2.0));
+
+        assert_eq!(summary.prompts.len(), 3);
+        assert_eq!(summary.prompts["grammar"], totals(2, 1, 400, 40, 2.5));
+        assert_eq!(summary.prompts["vote"], totals(1, 0, 200, 20, 1.0));
+        assert_eq!(
+            summary.prompts["enneagram_tendency"],
+            totals(1, 0, 400, 40, 4.0)
+        );
+
+        assert_eq!(summary.models.len(), 2);
+        assert_eq!(summary.models["gpt-3.5-turbo"], totals(1, 0, 100, 10, 0.5));
+        assert_eq!(summary.models["gpt-4"], totals(3, 1, 900, 90, 7.0));
+    }
+
+    #[test]
+    fn the_budget_fails_only_once_it_is_reached() {
+        let ledger = UsageLedger::default();
+        let budget = Budget::default().limit("1k").unwrap().limit("$1").unwrap();
+
+        assert!(ledger.check_budget(&budget).is_ok());
+        assert!(ledger.check_budget(&Budget::default()).is_ok());
+
+        ledger.record(None, "grammar", "gpt-4", &usage(900, 99), 0.5);
+        assert!(ledger.check_budget(&budget).is_ok());
+        assert!(!ledger.summary().budget_exceeded);
+
+        ledger.record(None, "grammar", "gpt-4", &usage(1, 0), 0.1);
+        assert!(matches!(
+            ledger.check_budget(&budget),
+            Err(PulpError::SimulationError(SimulationError::BudgetExceeded(
+                _
+            )))
+        ));
+        assert!(ledger.summary().budget_exceeded);
+
+        // The dollar ceiling stops a run on its own
+        let ledger = UsageLedger::default();
+        ledger.record(None, "grammar", "gpt-4", &usage(10, 10), 0.99);
+        assert!(ledger.check_budget(&budget).is_ok());
+        ledger.record(None, "grammar", "gpt-4", &usage(10, 10), 0.01);
+        assert!(ledger.check_budget(&budget).is_err());
+
+        // With no ceiling nothing is ever exceeded
+        assert!(ledger.check_budget(&Budget::default()).is_ok());
+    }
+}
@@ -0,0 +1,212 @@
+use pulpcalc_common::{
+    models::{Debate, Response, Score, ScoreVersion, SimulationRun, User, Vote},
+    store::{BatchedStore, Edge, MemoryStore, Relationship, SqliteStore, Store},
+};
+use std::sync::Arc;
+
+/// Writes the run `id` started at `started_at`: a user answering a debate with a response that has a
+/// vote and a reply, and a score version of the response and the debate. The reply of run `b` replies
+/// to the response of run `a` as well
+async fn write_run(store: &dyn Store, id: &str, started_at: i64) {
+    let name = |name: &str| format!("{}-{}", id, name);
+
+    store
+        .start_run(&SimulationRun {
+            id: id.to_string(),
+            simulation: "personas".to_string(),
+            started_at,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    store
+        .create_debate(&Debate {
+            id: name("debate"),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    store
+        .create_user(&User {
+            id: name("user"),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    for response in ["response", "reply"] {
+        store
+            .create_response(&Response {
+                id: name(response),
+                created_at: started_at,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+    store
+        .create_vote(&Vote {
+            id: name("vote"),
+            response_id: name("response"),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let mut relationships = vec![
+        Relationship::Participated {
+            user_id: name("user"),
+            debate_id: name("debate"),
+        },
+        Relationship::Responded {
+            user_id: name("user"),
+            response_id: name("response"),
+        },
+        Relationship::DebateResponse {
+            response_id: name("response"),
+            debate_id: name("debate"),
+        },
+        Relationship::Replied {
+            response_id: name("response"),
+            reply_id: name("reply"),
+        },
+    ];
+    if id == "b" {
+        relationships.push(Relationship::Replied {
+            response_id: "a-response".to_string(),
+            reply_id: name("reply"),
+        });
+    }
+
+    for relationship in relationships {
+        store.relate(relationship).await.unwrap();
+    }
+
+    let scores = ["response", "debate"].map(|entity| Score {
+        entity_id: name(entity),
+        score: 1,
+        ..Default::default()
+    });
+    store
+        .create_score_version(
+            &ScoreVersion {
+                scored_at: started_at,
+                ..ScoreVersion::new(id, "default")
+            },
+            &scores,
+        )
+        .await
+        .unwrap();
+}
+
+/// What the store holds of the run `b`
+async fn run_b(
+    store: &dyn Store,
+) -> (
+    Vec<(String, i64)>,
+    Vec<Vec<String>>,
+    Vec<Vec<String>>,
+    usize,
+) {
+    let mut entities = Vec::new();
+    for label in ["Debate", "User", "Response", "Vote", "ScoreVersion"] {
+        let mut ids = store.run_entities("b", label).await.unwrap();
+        ids.sort();
+        entities.push(ids);
+    }
+
+    let mut edges = Vec::new();
+    for (id, edge, outgoing) in [
+        ("b-user", Edge::Participated, true),
+        ("b-user", Edge::Responded, true),
+        ("b-debate", Edge::Response, false),
+        ("b-response", Edge::Replied, true),
+        ("b-reply", Edge::Replied, false),
+    ] {
+        let mut ids = if outgoing {
+            store.outgoing(id, edge).await.unwrap()
+        } else {
+            store.incoming(id, edge).await.unwrap()
+        };
+        ids.sort();
+        edges.push(ids);
+    }
+
+    let versions = store.get_score_versions("b").await.unwrap();
+    assert_eq!(versions.len(), 1);
+    let scores = store.get_scores(&versions[0].id).await.unwrap().len();
+
+    (
+        store.run_counts("b").await.unwrap(),
+        entities,
+        edges,
+        scores,
+    )
+}
+
+/// Writes the runs `a` and `b` and deletes `a`, checking that nothing of `b` but its relationship to
+/// the response of `a` goes with it
+async fn delete_run(store: &dyn Store) {
+    write_run(store, "a", 1).await;
+    write_run(store, "b", 2).await;
+
+    let runs = store.get_all_runs().await.unwrap();
+    let ids = runs.iter().map(|run| run.id.as_str()).collect::<Vec<_>>();
+    assert_eq!(ids, ["a", "b"]);
+    assert_eq!(store.get_run("a").await.unwrap().started_at, 1);
+
+    let count = |label: &str, count| (label.to_string(), count);
+    let (counts, entities, mut edges, scores) = run_b(store).await;
+    assert_eq!(
+        counts,
+        vec![
+            count("Debate", 1),
+            count("Response", 2),
+            count("ScoreVersion", 1),
+            count("User", 1),
+            count("Vote", 1),
+            // 5 relationships, the vote's on the response and 2 scores
+            count("relationships", 8),
+        ]
+    );
+    assert_eq!(edges[4], ["a-response", "b-response"]);
+
+    store.delete_run("a").await.unwrap();
+
+    assert!(store.get_run("a").await.is_err());
+    let runs = store.get_all_runs().await.unwrap();
+    let ids = runs.iter().map(|run| run.id.as_str()).collect::<Vec<_>>();
+    assert_eq!(ids, ["b"]);
+
+    assert!(store.get_response("a-response").await.is_err());
+    assert!(store.get_debate("a-debate").await.is_err());
+    assert!(store.get_score_versions("a").await.unwrap().is_empty());
+    assert_eq!(
+        store.run_counts("a").await.unwrap(),
+        vec![count("relationships", 0)]
+    );
+
+    // The reply of `b` no longer replies to the deleted response
+    edges[4] = vec!["b-response".to_string()];
+    let mut counts = counts;
+    counts.last_mut().unwrap().1 -= 1;
+    assert_eq!(run_b(store).await, (counts, entities, edges, scores));
+    assert!(store.get_response("b-reply").await.is_ok());
+}
+
+#[tokio::test]
+async fn memory_store_deletes_a_run_alone() {
+    delete_run(&MemoryStore::new()).await;
+}
+
+#[tokio::test]
+async fn sqlite_store_deletes_a_run_alone() {
+    delete_run(&SqliteStore::open(":memory:").unwrap()).await;
+}
+
+#[tokio::test]
+async fn batched_store_deletes_a_run_alone() {
+    let store = BatchedStore::new(Arc::new(SqliteStore::open(":memory:").unwrap()), 3);
+
+    delete_run(&store).await;
+}
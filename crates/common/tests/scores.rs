@@ -0,0 +1,227 @@
+use pulpcalc_common::{
+    models::{
+        vote::VoteType, Attributes, Debate, Response, Score, ScoreVersion, SimulationRun, Vote,
+    },
+    scoring::ScoringPolicy,
+    store::{MemoryStore, Relationship, SqliteStore, Store},
+};
+
+/// When every response was written, so each reply came instantly
+const CREATED_AT: i64 = 1_000;
+
+/// Scores of the debate and responses of a run, sorted by entity
+type Scores = Vec<(String, i64, i64, i64)>;
+
+/// Writes a run with `id` of a debate answered by `id-response` and `id-other`, replied to by `id-reply`,
+/// which is replied to by `id-nested`. Every response scores 10 content points and was stored with a
+/// score of 1, and the response got a valid vote
+async fn write_run(store: &dyn Store, id: &str) {
+    let name = |name: &str| format!("{}-{}", id, name);
+
+    store
+        .start_run(&SimulationRun {
+            id: id.to_string(),
+            simulation: "personas".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    store
+        .create_debate(&Debate {
+            id: name("debate"),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    for response in ["response", "reply", "nested", "other"] {
+        store
+            .create_response(&Response {
+                id: name(response),
+                score: 1,
+                created_at: CREATED_AT,
+                attributes: Attributes {
+                    relevance: 2.0,
+                    soundness: 3.0,
+                    mastery_vocab_words: vec!["cogent".to_string(), "tenable".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+
+    store
+        .create_vote(&Vote {
+            vote_type: VoteType::Valid(None),
+            response_id: name("response"),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let relationships = [
+        Relationship::DebateResponse {
+            response_id: name("response"),
+            debate_id: name("debate"),
+        },
+        Relationship::DebateResponse {
+            response_id: name("other"),
+            debate_id: name("debate"),
+        },
+        Relationship::Replied {
+            response_id: name("response"),
+            reply_id: name("reply"),
+        },
+        Relationship::Replied {
+            response_id: name("reply"),
+            reply_id: name("nested"),
+        },
+    ];
+
+    for relationship in relationships {
+        store.relate(relationship).await.unwrap();
+    }
+}
+
+/// The default policy, and one doubling content points and halving engagement points
+fn policies() -> [ScoringPolicy; 2] {
+    let mut weighted = ScoringPolicy {
+        id: "weighted".to_string(),
+        ..Default::default()
+    };
+    weighted.combine.content = 2.0;
+    weighted.combine.engagement = 0.5;
+
+    [ScoringPolicy::default(), weighted]
+}
+
+fn sorted(scores: &[Score]) -> Scores {
+    let mut scores: Scores = scores
+        .iter()
+        .map(|s| (s.entity_id.clone(), s.content, s.engagement, s.score))
+        .collect();
+    scores.sort();
+
+    scores
+}
+
+/// Rescores the run `a` under both policies, recording a version for each, and the run `b` under the
+/// default policy alone
+async fn rescore(store: &dyn Store) -> (Vec<ScoreVersion>, Vec<Scores>, Vec<ScoreVersion>) {
+    write_run(store, "a").await;
+    write_run(store, "b").await;
+
+    for (scored_at, policy) in policies().iter().enumerate() {
+        let scored = policy.score_run(store, "a").await.unwrap();
+
+        // The stored score of a debate adds up the stored scores of its responses too
+        for (score, stored) in &scored {
+            let expected = if score.label == "Debate" { 4 } else { 1 };
+            assert_eq!(*stored, expected, "{}", score.entity_id);
+        }
+
+        let scores = scored
+            .into_iter()
+            .map(|(score, _)| score)
+            .collect::<Vec<_>>();
+        store
+            .create_score_version(
+                &ScoreVersion {
+                    scored_at: scored_at as i64,
+                    ..ScoreVersion::new("a", &policy.id)
+                },
+                &scores,
+            )
+            .await
+            .unwrap();
+    }
+
+    let default = ScoringPolicy::default();
+    let scored = default.score_run(store, "b").await.unwrap();
+    let scores = scored
+        .into_iter()
+        .map(|(score, _)| score)
+        .collect::<Vec<_>>();
+    store
+        .create_score_version(&ScoreVersion::new("b", &default.id), &scores)
+        .await
+        .unwrap();
+
+    let versions = store.get_score_versions("a").await.unwrap();
+    let mut scores = Vec::new();
+    for version in &versions {
+        scores.push(sorted(&store.get_scores(&version.id).await.unwrap()));
+    }
+
+    (
+        versions,
+        scores,
+        store.get_score_versions("b").await.unwrap(),
+    )
+}
+
+fn assert_rescored(versions: &[ScoreVersion], scores: &[Scores], other: &[ScoreVersion]) {
+    let policies = versions
+        .iter()
+        .map(|v| v.policy.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(policies, ["default", "weighted"]);
+    assert!(versions.iter().all(|v| v.run_id == "a" && !v.id.is_empty()));
+    assert_ne!(versions[0].id, versions[1].id);
+
+    let score = |entity: &str, content, engagement| {
+        (
+            entity.to_string(),
+            content,
+            engagement,
+            content + engagement,
+        )
+    };
+
+    // Engagement of the response: a valid vote, an instant direct reply and a reply two levels down,
+    // of the reply: an instant direct reply. The debate adds up all 4 responses of its threads
+    assert_eq!(
+        scores[0],
+        vec![
+            score("a-debate", 40, 50 + 150 + 150 + 75 + 150 + 150),
+            score("a-nested", 10, 0),
+            score("a-other", 10, 0),
+            score("a-reply", 10, 150 + 150),
+            score("a-response", 10, 50 + 150 + 150 + 75),
+        ]
+    );
+    assert_eq!(
+        scores[1],
+        vec![
+            score("a-debate", 80, 212 + 150),
+            score("a-nested", 20, 0),
+            score("a-other", 20, 0),
+            score("a-reply", 20, 150),
+            score("a-response", 20, 212),
+        ]
+    );
+
+    // The versions of the other run are kept apart
+    assert_eq!(other.len(), 1);
+    assert_eq!(other[0].run_id, "b");
+    assert_eq!(other[0].policy, "default");
+}
+
+#[tokio::test]
+async fn memory_store_keeps_every_score_version_of_a_run() {
+    let store = MemoryStore::new();
+    let (versions, scores, other) = rescore(&store).await;
+
+    assert_rescored(&versions, &scores, &other);
+}
+
+#[tokio::test]
+async fn sqlite_store_keeps_every_score_version_of_a_run() {
+    let store = SqliteStore::open(":memory:").unwrap();
+    let (versions, scores, other) = rescore(&store).await;
+
+    assert_rescored(&versions, &scores, &other);
+}
@@ -0,0 +1,240 @@
+use pulpcalc_common::{
+    models::{Debate, Learned, PersonasUser, Reference, Response, User},
+    store::{Edge, MemoryStore, Neo4jStore, Relationship, SqliteStore, Store},
+};
+use std::env;
+
+/// Names of the entities the traversals start from, every store is given the same graph of them
+const DEBATE: &str = "debate";
+const USER: &str = "user";
+const PERSONA: &str = "persona";
+const RESPONSE: &str = "response";
+const REPLY: &str = "reply";
+const REFERENCE: &str = "reference";
+const LEARNED: &str = "learned";
+
+/// Writes the graph with every entity id prefixed with `prefix`, including relationships whose ends
+/// have the wrong labels or are missing, which no store keeps
+async fn write_graph(store: &dyn Store, prefix: &str) {
+    let id = |name: &str| format!("{}{}", prefix, name);
+
+    store
+        .create_debate(&Debate {
+            id: id(DEBATE),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    store
+        .create_user(&User {
+            id: id(USER),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let mut persona = PersonasUser::default();
+    persona.base_user.id = id(PERSONA);
+    store.create_personas_user(&persona).await.unwrap();
+
+    for response in [RESPONSE, REPLY] {
+        store
+            .create_response(&Response {
+                id: id(response),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+    store
+        .create_reference(&Reference {
+            id: id(REFERENCE),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    store
+        .create_learned(&Learned {
+            id: id(LEARNED),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let relationships = [
+        Relationship::Participated {
+            user_id: id(USER),
+            debate_id: id(DEBATE),
+        },
+        Relationship::PersonaParticipated {
+            persona_id: id(PERSONA),
+            debate_id: id(DEBATE),
+        },
+        Relationship::Responded {
+            user_id: id(USER),
+            response_id: id(RESPONSE),
+        },
+        Relationship::PersonaResponded {
+            persona_id: id(PERSONA),
+            response_id: id(REPLY),
+        },
+        Relationship::Replied {
+            response_id: id(RESPONSE),
+            reply_id: id(REPLY),
+        },
+        Relationship::DebateResponse {
+            response_id: id(RESPONSE),
+            debate_id: id(DEBATE),
+        },
+        Relationship::HasReference {
+            response_id: id(RESPONSE),
+            reference_id: id(REFERENCE),
+        },
+        Relationship::Learned {
+            persona_id: id(PERSONA),
+            learned_id: id(LEARNED),
+        },
+        Relationship::LearnedIn {
+            learned_id: id(LEARNED),
+            debate_id: id(DEBATE),
+        },
+        Relationship::LearnedFrom {
+            learned_id: id(LEARNED),
+            response_id: id(RESPONSE),
+        },
+        // A persona is not a `User`
+        Relationship::Participated {
+            user_id: id(PERSONA),
+            debate_id: id(DEBATE),
+        },
+        // A debate is not a `Response`
+        Relationship::Replied {
+            response_id: id(RESPONSE),
+            reply_id: id(DEBATE),
+        },
+        Relationship::Responded {
+            user_id: id(REPLY),
+            response_id: id(RESPONSE),
+        },
+        Relationship::LearnedFrom {
+            learned_id: id(LEARNED),
+            response_id: id(REFERENCE),
+        },
+        Relationship::Replied {
+            response_id: id(REPLY),
+            reply_id: id("missing"),
+        },
+    ];
+
+    for relationship in relationships {
+        store.relate(relationship).await.unwrap();
+    }
+}
+
+/// Every traversal of the graph, with the names of the entities found in sorted order
+async fn traverse(store: &dyn Store, prefix: &str) -> Vec<(&'static str, Edge, bool, Vec<String>)> {
+    let traversals = [
+        (USER, Edge::Participated, true),
+        (DEBATE, Edge::Participated, true),
+        (DEBATE, Edge::Participated, false),
+        (USER, Edge::Responded, true),
+        (PERSONA, Edge::Responded, true),
+        (REPLY, Edge::Responded, true),
+        (RESPONSE, Edge::Responded, false),
+        (RESPONSE, Edge::Replied, true),
+        (REPLY, Edge::Replied, true),
+        (REPLY, Edge::Replied, false),
+        (DEBATE, Edge::Response, false),
+        (RESPONSE, Edge::HasReference, true),
+        (PERSONA, Edge::Learned, true),
+        (LEARNED, Edge::LearnedIn, true),
+        (LEARNED, Edge::LearnedFrom, true),
+    ];
+
+    let mut found = Vec::new();
+    for (name, edge, outgoing) in traversals {
+        let id = format!("{}{}", prefix, name);
+        let ids = if outgoing {
+            store.outgoing(&id, edge).await.unwrap()
+        } else {
+            store.incoming(&id, edge).await.unwrap()
+        };
+
+        let mut names: Vec<String> = ids
+            .iter()
+            .map(|id| id.trim_start_matches(prefix).to_string())
+            .collect();
+        names.sort();
+
+        found.push((name, edge, outgoing, names));
+    }
+
+    found
+}
+
+/// What the traversals return in Neo4j, where each end of a relationship is matched by its label
+fn expected() -> Vec<(&'static str, Edge, bool, Vec<String>)> {
+    let names = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+
+    vec![
+        (USER, Edge::Participated, true, names(&[DEBATE])),
+        (DEBATE, Edge::Participated, true, names(&[PERSONA])),
+        (DEBATE, Edge::Participated, false, names(&[USER])),
+        (USER, Edge::Responded, true, names(&[RESPONSE])),
+        (PERSONA, Edge::Responded, true, names(&[REPLY])),
+        (REPLY, Edge::Responded, true, names(&[])),
+        (RESPONSE, Edge::Responded, false, names(&[USER])),
+        (RESPONSE, Edge::Replied, true, names(&[REPLY])),
+        (REPLY, Edge::Replied, true, names(&[])),
+        (REPLY, Edge::Replied, false, names(&[RESPONSE])),
+        (DEBATE, Edge::Response, false, names(&[RESPONSE])),
+        (RESPONSE, Edge::HasReference, true, names(&[REFERENCE])),
+        (PERSONA, Edge::Learned, true, names(&[LEARNED])),
+        (LEARNED, Edge::LearnedIn, true, names(&[DEBATE])),
+        (LEARNED, Edge::LearnedFrom, true, names(&[RESPONSE])),
+    ]
+}
+
+#[tokio::test]
+async fn memory_store_traverses_like_neo4j() {
+    let store = MemoryStore::new();
+    write_graph(&store, "").await;
+
+    assert_eq!(traverse(&store, "").await, expected());
+}
+
+#[tokio::test]
+async fn sqlite_store_traverses_like_neo4j() {
+    let store = SqliteStore::open(":memory:").unwrap();
+    write_graph(&store, "").await;
+
+    assert_eq!(traverse(&store, "").await, expected());
+}
+
+#[tokio::test]
+#[ignore = "needs a Neo4j database at NEO_ENDPOINT"]
+async fn neo4j_store_traverses_like_the_other_stores() {
+    let store = Neo4jStore::connect(
+        &env::var("NEO_ENDPOINT").unwrap(),
+        &env::var("NEO_USER").unwrap_or_default(),
+        &env::var("NEO_PASSWORD").unwrap_or_default(),
+    )
+    .await
+    .unwrap();
+
+    // The database may hold other data, the entities of this test are kept apart by their ids
+    let prefix = format!("traversal-{}-", std::process::id());
+    write_graph(&store, &prefix).await;
+    let found = traverse(&store, &prefix).await;
+
+    let id = |name: &str| format!("{}{}", prefix, name);
+    store.delete_debate(&id(DEBATE)).await.unwrap();
+    store.delete_user(&id(USER)).await.unwrap();
+    store.delete_personas_user(&id(PERSONA)).await.unwrap();
+    store.delete_response(&id(RESPONSE)).await.unwrap();
+    store.delete_response(&id(REPLY)).await.unwrap();
+    store.delete_reference(&id(REFERENCE)).await.unwrap();
+    store.delete_learned(&id(LEARNED)).await.unwrap();
+
+    assert_eq!(found, expected());
+}
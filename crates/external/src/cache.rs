@@ -0,0 +1,152 @@
+use crate::{chatgpt::ChatResponse, errors::LlmError};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Where cached replies are kept
+enum CacheBackend {
+    /// Replies with the unix time they were stored at, lost when the process exits
+    Memory(Mutex<HashMap<String, (u64, ChatResponse)>>),
+
+    /// Replies kept in a SQLite file across runs
+    Sqlite(Mutex<Connection>),
+}
+
+/// Replies keyed by a hash of the request they answer, so identical prompts are
+/// only sent to the provider once
+pub struct ResponseCache {
+    backend: CacheBackend,
+
+    /// How long a reply is served for, forever when unset
+    ttl: Option<Duration>,
+
+    hits: AtomicU64,
+
+    misses: AtomicU64,
+}
+
+impl ResponseCache {
+    pub fn memory(ttl: Option<Duration>) -> Self {
+        Self::new(CacheBackend::Memory(Mutex::new(HashMap::new())), ttl)
+    }
+
+    /// Opens, or creates, the SQLite cache at `path`
+    pub fn sqlite(path: &str, ttl: Option<Duration>) -> Result<Self, LlmError> {
+        let conn = Connection::open(path)
+            .map_err(|e| LlmError::Cache(format!("failed to open {}: {}", path, e)))?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS responses (key TEXT PRIMARY KEY, created_at INTEGER NOT NULL, response TEXT NOT NULL)",
+            [],
+        )
+        .map_err(|e| LlmError::Cache(format!("failed to create the cache table in {}: {}", path, e)))?;
+
+        Ok(Self::new(CacheBackend::Sqlite(Mutex::new(conn)), ttl))
+    }
+
+    fn new(backend: CacheBackend, ttl: Option<Duration>) -> Self {
+        Self {
+            backend,
+            ttl,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// The reply stored under `key`, unless it is missing or older than the TTL
+    pub fn get(&self, key: &str) -> Option<ChatResponse> {
+        let entry = match &self.backend {
+            CacheBackend::Memory(entries) => entries.lock().unwrap().get(key).cloned(),
+
+            CacheBackend::Sqlite(conn) => {
+                let row: Option<(u64, String)> = conn
+                    .lock()
+                    .unwrap()
+                    .query_row(
+                        "SELECT created_at, response FROM responses WHERE key = ?1",
+                        params![key],
+                        |row| Ok((row.get(0)?, row.get(1)?)),
+                    )
+                    .optional()
+                    .unwrap_or_else(|e| {
+                        println!("failed to read the llm cache: {}", e);
+
+                        None
+                    });
+
+                row.and_then(|(created_at, response)| {
+                    serde_json::from_str(&response)
+                        .ok()
+                        .map(|response| (created_at, response))
+                })
+            }
+        };
+
+        let fresh = entry.filter(|(created_at, _)| match self.ttl {
+            Some(ttl) => now().saturating_sub(*created_at) < ttl.as_secs(),
+            None => true,
+        });
+
+        match fresh {
+            Some((_, response)) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+
+                Some(response)
+            }
+
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+
+                None
+            }
+        }
+    }
+
+    pub fn put(&self, key: &str, response: &ChatResponse) {
+        match &self.backend {
+            CacheBackend::Memory(entries) => {
+                entries
+                    .lock()
+                    .unwrap()
+                    .insert(key.to_string(), (now(), response.clone()));
+            }
+
+            CacheBackend::Sqlite(conn) => {
+                let response = match serde_json::to_string(response) {
+                    Ok(response) => response,
+                    Err(_) => return,
+                };
+
+                if let Err(e) = conn.lock().unwrap().execute(
+                    "INSERT OR REPLACE INTO responses (key, created_at, response) VALUES (?1, ?2, ?3)",
+                    params![key, now(), response],
+                ) {
+                    println!("failed to write the llm cache: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Number of requests answered from the cache
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Number of requests looked up but not found, or found expired
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
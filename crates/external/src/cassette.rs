@@ -0,0 +1,221 @@
+use crate::{
+    chatgpt::{ChatRequest, ChatResponse},
+    errors::LlmError,
+    provider::ChatProvider,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{HashMap, VecDeque},
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+/// Whether a cassette is being written or served from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CassetteMode {
+    /// Forward requests to the real provider and write every reply to the cassette
+    Record,
+
+    /// Serve replies from the cassette without touching the network
+    Replay,
+}
+
+/// A single request/reply pair, stored one per line in the cassette file
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CassetteEntry {
+    pub key: String,
+
+    pub request: ChatRequest,
+
+    pub response: ChatResponse,
+}
+
+/// Records LLM traffic to, or replays it from, a JSON lines cassette file
+pub struct CassetteProvider {
+    mode: CassetteMode,
+
+    path: String,
+
+    /// The real provider, only set when recording
+    inner: Option<Arc<dyn ChatProvider>>,
+
+    /// The file recorded replies are appended to
+    file: Mutex<Option<File>>,
+
+    /// Recorded replies by key, served in the order they were recorded
+    entries: Mutex<HashMap<String, VecDeque<ChatResponse>>>,
+
+    misses: AtomicU64,
+}
+
+impl CassetteProvider {
+    /// Records every reply of `inner` to a new cassette at `path`
+    pub fn record(inner: Arc<dyn ChatProvider>, path: &str) -> Result<Self, LlmError> {
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(path)
+            .map_err(|e| LlmError::Cassette(format!("failed to create {}: {}", path, e)))?;
+
+        Ok(Self {
+            mode: CassetteMode::Record,
+            path: path.to_string(),
+            inner: Some(inner),
+            file: Mutex::new(Some(file)),
+            entries: Mutex::new(HashMap::new()),
+            misses: AtomicU64::new(0),
+        })
+    }
+
+    /// Serves replies from the cassette at `path`
+    pub fn replay(path: &str) -> Result<Self, LlmError> {
+        let file = File::open(path)
+            .map_err(|e| LlmError::Cassette(format!("failed to open {}: {}", path, e)))?;
+
+        let mut entries: HashMap<String, VecDeque<ChatResponse>> = HashMap::new();
+        for (i, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(|e| LlmError::Cassette(e.to_string()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let entry = serde_json::from_str::<CassetteEntry>(&line).map_err(|e| {
+                LlmError::Cassette(format!("{} line {} is invalid: {}", path, i + 1, e))
+            })?;
+
+            entries
+                .entry(entry.key)
+                .or_default()
+                .push_back(entry.response);
+        }
+
+        Ok(Self {
+            mode: CassetteMode::Replay,
+            path: path.to_string(),
+            inner: None,
+            file: Mutex::new(None),
+            entries: Mutex::new(entries),
+            misses: AtomicU64::new(0),
+        })
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Number of requests with no recorded reply while replaying
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    fn write_entry(&self, entry: &CassetteEntry) -> Result<(), LlmError> {
+        let line = serde_json::to_string(entry).map_err(|e| LlmError::Cassette(e.to_string()))?;
+
+        let mut file = self.file.lock().unwrap();
+        if let Some(file) = file.as_mut() {
+            writeln!(file, "{}", line)
+                .and_then(|_| file.flush())
+                .map_err(|e| LlmError::Cassette(format!("failed to write {}: {}", self.path, e)))?;
+        }
+
+        Ok(())
+    }
+
+    fn next_entry(&self, key: &str) -> Option<ChatResponse> {
+        let mut entries = self.entries.lock().unwrap();
+        let replies = entries.get_mut(key)?;
+
+        // The last reply for a key keeps being served once the earlier ones are used up
+        if replies.len() > 1 {
+            replies.pop_front()
+        } else {
+            replies.front().cloned()
+        }
+    }
+}
+
+#[async_trait]
+impl ChatProvider for CassetteProvider {
+    fn name(&self) -> &str {
+        match &self.inner {
+            Some(inner) => inner.name(),
+            None => "cassette",
+        }
+    }
+
+    async fn send(&self, request: &ChatRequest) -> Result<ChatResponse, LlmError> {
+        let key = cassette_key(request);
+
+        match (&self.mode, &self.inner) {
+            (CassetteMode::Record, Some(inner)) => {
+                let res = inner.send(request).await?;
+
+                self.write_entry(&CassetteEntry {
+                    key,
+                    request: request.clone(),
+                    response: res.clone(),
+                })?;
+
+                Ok(res)
+            }
+
+            _ => match self.next_entry(&key) {
+                Some(res) => Ok(res),
+
+                None => {
+                    self.misses.fetch_add(1, Ordering::Relaxed);
+                    println!(
+                        "CASSETTE MISS: no reply recorded in {} for request {} ({} messages, model {})",
+                        self.path,
+                        key,
+                        request.messages.len(),
+                        request.model
+                    );
+
+                    Err(LlmError::CassetteMiss {
+                        key,
+                        path: self.path.clone(),
+                    })
+                }
+            },
+        }
+    }
+}
+
+/// Hash of the model, messages, sampling params and response format of a request
+pub fn cassette_key(request: &ChatRequest) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(request.model.as_bytes());
+
+    for message in &request.messages {
+        hasher.update([0]);
+        hasher.update(message.role.as_bytes());
+        hasher.update([0]);
+        hasher.update(message.content.as_bytes());
+    }
+
+    hasher.update([0]);
+    hasher.update(request.max_tokens.to_le_bytes());
+    hasher.update(request.temperature.to_le_bytes());
+    hasher.update(request.top_p.to_le_bytes());
+    hasher.update(request.frequency_penalty.to_le_bytes());
+    hasher.update(request.presence_penalty.to_le_bytes());
+
+    if let Some(format) = &request.response_format {
+        hasher.update([0]);
+        hasher.update(serde_json::to_string(format).unwrap_or_default().as_bytes());
+    }
+
+    format!("{:x}", hasher.finalize())
+}
@@ -1,9 +1,8 @@
-use std::sync::Arc;
-
-use reqwest::Client;
+use crate::{errors::LlmError, provider::ChatProvider};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
 pub struct ChatUsage {
     pub prompt_tokens: i64,
 
@@ -12,36 +11,127 @@ pub struct ChatUsage {
     pub total_tokens: i64,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
 pub struct ChatMessage {
     pub role: String,
 
     pub content: String,
 }
 
-#[derive(Debug, Default, Deserialize)]
+impl ChatMessage {
+    pub fn system(content: String) -> Self {
+        Self {
+            role: String::from("system"),
+            content,
+        }
+    }
+
+    pub fn user(content: String) -> Self {
+        Self {
+            role: String::from("user"),
+            content,
+        }
+    }
+
+    pub fn assistant(content: String) -> Self {
+        Self {
+            role: String::from("assistant"),
+            content,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
 pub struct ChatChoice {
     pub index: i64,
 
     pub message: ChatMessage,
 
-    pub finish_reason: String,
+    pub finish_reason: Option<String>,
 }
 
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
 pub struct ChatResponse {
     pub id: Option<String>,
 
+    #[serde(default)]
     pub object: String,
 
+    #[serde(default)]
     pub created: i64,
 
+    /// The model that produced the reply
+    #[serde(default)]
+    pub model: String,
+
     pub choices: Vec<ChatChoice>,
 
+    #[serde(default)]
     pub usage: ChatUsage,
+
+    /// The `x-request-id` header returned with the response
+    #[serde(skip)]
+    pub request_id: Option<String>,
+}
+
+impl ChatResponse {
+    /// Checks that the response holds a complete reply
+    pub fn validate(self) -> Result<Self, LlmError> {
+        let request_id = self.request_id.clone().or(self.id.clone());
+
+        match self.choices.first() {
+            None => Err(LlmError::EmptyChoices { request_id }),
+
+            Some(choice) if choice.finish_reason.as_deref() == Some("length") => {
+                Err(LlmError::Truncated { request_id })
+            }
+
+            Some(_) => Ok(self),
+        }
+    }
+
+    /// The content of the first choice
+    pub fn content(&self) -> &str {
+        match self.choices.first() {
+            Some(choice) => &choice.message.content,
+            None => "",
+        }
+    }
+}
+
+/// Constrains the reply to JSON matching a schema
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ResponseFormat {
+    #[serde(rename = "type")]
+    pub format_type: String,
+
+    pub json_schema: JsonSchemaFormat,
 }
 
-#[derive(Debug, Default, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct JsonSchemaFormat {
+    pub name: String,
+
+    pub schema: Value,
+
+    #[serde(default)]
+    pub strict: bool,
+}
+
+impl ResponseFormat {
+    pub fn json_schema(name: &str, schema: Value) -> Self {
+        Self {
+            format_type: String::from("json_schema"),
+            json_schema: JsonSchemaFormat {
+                name: name.to_string(),
+                schema,
+                strict: false,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
 pub struct ChatRequest {
     pub messages: Vec<ChatMessage>,
 
@@ -56,6 +146,9 @@ pub struct ChatRequest {
     pub frequency_penalty: f64,
 
     pub presence_penalty: f64,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<ResponseFormat>,
 }
 
 impl ChatRequest {
@@ -69,41 +162,25 @@ impl ChatRequest {
         presence_penalty: f64,
     ) -> Self {
         Self {
-            messages: vec![ChatMessage {
-                role: String::from("user"),
-                content: message,
-            }],
+            messages: vec![ChatMessage::user(message)],
             model,
             max_tokens,
             temperature,
             top_p,
             frequency_penalty,
             presence_penalty,
+            response_format: None,
         }
     }
 
-    // TODO: handle error
-    pub async fn send(&self, open_ai_key: Arc<String>, client: Client) -> ChatResponse {
-        let url = "https://api.openai.com/v1/chat/completions";
-
-        let res = client
-            .post(url)
-            .json(self)
-            .bearer_auth(open_ai_key)
-            .send()
-            .await
-            .unwrap()
-            .json::<ChatResponse>()
-            .await
-            .unwrap();
-
-        res
+    pub async fn send(&self, provider: &dyn ChatProvider) -> Result<ChatResponse, LlmError> {
+        provider.send(self).await?.validate()
     }
 }
 
 #[derive(Debug)]
 pub struct ChatRequestBuilder {
-    message: String,
+    messages: Vec<ChatMessage>,
 
     model: String,
 
@@ -118,12 +195,14 @@ pub struct ChatRequestBuilder {
     presence_penalty: f64,
 
     stop: Vec<String>,
+
+    response_format: Option<ResponseFormat>,
 }
 
 impl ChatRequestBuilder {
     pub fn new() -> Self {
         Self {
-            message: String::new(),
+            messages: Vec::new(),
             model: String::from("gpt-3.5-turbo"),
             max_tokens: 0,
             temperature: 0.0,
@@ -131,11 +210,34 @@ impl ChatRequestBuilder {
             frequency_penalty: 0.0,
             presence_penalty: 0.0,
             stop: vec![String::from("")],
+            response_format: None,
         }
     }
 
+    /// Appends a user message
     pub fn messages(mut self, message: String) -> Self {
-        self.message = message.to_string();
+        self.messages.push(ChatMessage::user(message));
+        self
+    }
+
+    pub fn system(mut self, content: String) -> Self {
+        self.messages.push(ChatMessage::system(content));
+        self
+    }
+
+    pub fn user(mut self, content: String) -> Self {
+        self.messages.push(ChatMessage::user(content));
+        self
+    }
+
+    pub fn assistant(mut self, content: String) -> Self {
+        self.messages.push(ChatMessage::assistant(content));
+        self
+    }
+
+    /// Appends earlier turns of a conversation, in order
+    pub fn history(mut self, history: Vec<ChatMessage>) -> Self {
+        self.messages.extend(history);
         self
     }
 
@@ -174,15 +276,21 @@ impl ChatRequestBuilder {
         self
     }
 
+    pub fn response_format(mut self, response_format: ResponseFormat) -> Self {
+        self.response_format = Some(response_format);
+        self
+    }
+
     pub fn build(self) -> ChatRequest {
-        ChatRequest::new(
-            self.message,
-            self.model,
-            self.max_tokens,
-            self.temperature,
-            self.top_p,
-            self.frequency_penalty,
-            self.presence_penalty,
-        )
+        ChatRequest {
+            messages: self.messages,
+            model: self.model,
+            max_tokens: self.max_tokens,
+            temperature: self.temperature,
+            top_p: self.top_p,
+            frequency_penalty: self.frequency_penalty,
+            presence_penalty: self.presence_penalty,
+            response_format: self.response_format,
+        }
     }
 }
@@ -0,0 +1,78 @@
+use serde::Deserialize;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Errors returned when a chat completion request fails
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum LlmError {
+    #[error("llm transport error: {0}")]
+    Transport(String),
+
+    #[error("llm returned status {status} (request {}): {message}", .request_id.as_deref().unwrap_or("unknown"))]
+    Status {
+        status: u16,
+
+        request_id: Option<String>,
+
+        /// The `type` field of the OpenAI error body, i.e. `insufficient_quota`
+        error_type: Option<String>,
+
+        message: String,
+
+        /// How long the provider asked us to wait before retrying
+        retry_after: Option<Duration>,
+    },
+
+    #[error("llm returned no choices (request {})", .request_id.as_deref().unwrap_or("unknown"))]
+    EmptyChoices { request_id: Option<String> },
+
+    #[error("llm reply was cut off by max_tokens (request {})", .request_id.as_deref().unwrap_or("unknown"))]
+    Truncated { request_id: Option<String> },
+
+    #[error("llm reply could not be decoded: {0}")]
+    Decode(String),
+
+    #[error("no recorded llm reply for request {key} in cassette {path}")]
+    CassetteMiss { key: String, path: String },
+
+    #[error("llm cassette error: {0}")]
+    Cassette(String),
+
+    #[error("llm cache error: {0}")]
+    Cache(String),
+}
+
+impl LlmError {
+    /// The HTTP status returned by the provider, if any
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            LlmError::Status { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
+    /// The request id reported by the provider, if any
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            LlmError::Status { request_id, .. }
+            | LlmError::EmptyChoices { request_id }
+            | LlmError::Truncated { request_id } => request_id.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// The error body returned by OpenAI style apis on a non-2xx status
+#[derive(Debug, Default, Deserialize)]
+pub struct ApiErrorBody {
+    pub error: ApiErrorDetail,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ApiErrorDetail {
+    #[serde(default)]
+    pub message: String,
+
+    #[serde(rename = "type")]
+    pub error_type: Option<String>,
+}
@@ -1 +1,7 @@
+pub mod cache;
+pub mod cassette;
 pub mod chatgpt;
+pub mod errors;
+pub mod limiter;
+pub mod mock;
+pub mod provider;
@@ -0,0 +1,485 @@
+use crate::{
+    chatgpt::{ChatRequest, ChatResponse},
+    errors::LlmError,
+    provider::ChatProvider,
+};
+use async_trait::async_trait;
+use rand::Rng;
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::{
+    sync::{Mutex, Semaphore},
+    time::{sleep, Instant},
+};
+
+/// Limits applied to every request sent through a `RateLimitedProvider`
+#[derive(Debug, Clone)]
+pub struct RateLimits {
+    /// Requests allowed per minute, unlimited when `None`
+    pub requests_per_minute: Option<u32>,
+
+    /// Prompt and completion tokens allowed per minute, unlimited when `None`
+    pub tokens_per_minute: Option<u32>,
+
+    /// Maximum number of requests in flight at once
+    pub max_concurrency: usize,
+
+    /// Maximum number of times a failed request is retried
+    pub max_retries: u32,
+
+    /// Delay before the first retry, doubled on every attempt
+    pub base_delay: Duration,
+
+    /// Upper bound of the backoff delay
+    pub max_delay: Duration,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self {
+            requests_per_minute: None,
+            tokens_per_minute: None,
+            max_concurrency: 16,
+            max_retries: 5,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Counters of the work done by a `RateLimitedProvider`
+#[derive(Debug, Default)]
+pub struct LimiterStats {
+    retries: AtomicU64,
+
+    throttled_waits: AtomicU64,
+}
+
+impl LimiterStats {
+    /// Number of requests retried after a 429, 5xx or transport error
+    pub fn retries(&self) -> u64 {
+        self.retries.load(Ordering::Relaxed)
+    }
+
+    /// Number of times a request waited for the per minute budgets to refill
+    pub fn throttled_waits(&self) -> u64 {
+        self.throttled_waits.load(Ordering::Relaxed)
+    }
+}
+
+/// Wraps a provider with per minute budgets, a concurrency cap and
+/// exponential backoff with jitter on retryable failures
+pub struct RateLimitedProvider {
+    inner: Arc<dyn ChatProvider>,
+
+    limits: RateLimits,
+
+    requests: Option<Mutex<TokenBucket>>,
+
+    tokens: Option<Mutex<TokenBucket>>,
+
+    in_flight: Semaphore,
+
+    stats: Arc<LimiterStats>,
+}
+
+impl RateLimitedProvider {
+    pub fn new(inner: Arc<dyn ChatProvider>, limits: RateLimits, stats: Arc<LimiterStats>) -> Self {
+        Self {
+            inner,
+            requests: limits
+                .requests_per_minute
+                .map(|rpm| Mutex::new(TokenBucket::per_minute(rpm))),
+            tokens: limits
+                .tokens_per_minute
+                .map(|tpm| Mutex::new(TokenBucket::per_minute(tpm))),
+            in_flight: Semaphore::new(limits.max_concurrency.max(1)),
+            limits,
+            stats,
+        }
+    }
+
+    pub fn stats(&self) -> Arc<LimiterStats> {
+        self.stats.clone()
+    }
+
+    /// Corrects the token budget by the difference between the estimate taken for a request and the
+    /// tokens its reply reports it used
+    async fn reconcile(&self, estimated_tokens: f64, response: &ChatResponse) {
+        if let Some(bucket) = &self.tokens {
+            if response.usage.total_tokens > 0 {
+                bucket
+                    .lock()
+                    .await
+                    .give_back(estimated_tokens - response.usage.total_tokens as f64);
+            }
+        }
+    }
+
+    /// Waits until both per minute budgets can cover the request
+    async fn throttle(&self, estimated_tokens: f64) {
+        if let Some(bucket) = &self.requests {
+            self.take(bucket, 1.0).await;
+        }
+
+        if let Some(bucket) = &self.tokens {
+            self.take(bucket, estimated_tokens).await;
+        }
+    }
+
+    async fn take(&self, bucket: &Mutex<TokenBucket>, amount: f64) {
+        loop {
+            let wait = bucket.lock().await.try_take(amount);
+
+            match wait {
+                None => return,
+
+                Some(wait) => {
+                    self.stats.throttled_waits.fetch_add(1, Ordering::Relaxed);
+                    sleep(wait).await;
+                }
+            }
+        }
+    }
+
+    /// The delay before retry number `attempt`, honoring `Retry-After` when the provider sent one
+    fn backoff(&self, attempt: u32, err: &LlmError) -> Duration {
+        if let LlmError::Status {
+            retry_after: Some(retry_after),
+            ..
+        } = err
+        {
+            return (*retry_after).min(self.limits.max_delay);
+        }
+
+        let exp = self
+            .limits
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.limits.max_delay);
+
+        // Full jitter keeps concurrent simulations from retrying in lockstep
+        let jitter = rand::thread_rng().gen_range(0..=exp.as_millis() as u64);
+
+        Duration::from_millis(jitter)
+    }
+}
+
+#[async_trait]
+impl ChatProvider for RateLimitedProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn send(&self, request: &ChatRequest) -> Result<ChatResponse, LlmError> {
+        let estimated_tokens = estimate_tokens(request);
+
+        let mut attempt = 0;
+        loop {
+            // The permit is only held while the request is in flight, so a request backing off
+            // doesn't keep the others from being sent
+            let permit = self
+                .in_flight
+                .acquire()
+                .await
+                .map_err(|e| LlmError::Transport(e.to_string()))?;
+
+            self.throttle(estimated_tokens).await;
+
+            let result = self.inner.send(request).await;
+            drop(permit);
+
+            match result {
+                Ok(res) => {
+                    self.reconcile(estimated_tokens, &res).await;
+
+                    return Ok(res);
+                }
+
+                Err(e) if is_retryable(&e) && attempt < self.limits.max_retries => {
+                    let wait = self.backoff(attempt, &e);
+                    println!(
+                        "{} request failed, retrying in {:?}: {}",
+                        self.inner.name(),
+                        wait,
+                        e
+                    );
+
+                    self.stats.retries.fetch_add(1, Ordering::Relaxed);
+                    sleep(wait).await;
+
+                    attempt += 1;
+                }
+
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// Rate limits, server errors and dropped connections are worth retrying,
+/// an exhausted quota is not
+fn is_retryable(err: &LlmError) -> bool {
+    match err {
+        LlmError::Transport(_) => true,
+
+        LlmError::Status {
+            status, error_type, ..
+        } => {
+            if error_type.as_deref() == Some("insufficient_quota") {
+                return false;
+            }
+
+            *status == 429 || (500..600).contains(status)
+        }
+
+        _ => false,
+    }
+}
+
+/// Rough token count of a request, four characters per token plus the completion budget
+fn estimate_tokens(request: &ChatRequest) -> f64 {
+    let prompt_chars: usize = request.messages.iter().map(|m| m.content.len()).sum();
+
+    (prompt_chars / 4) as f64 + request.max_tokens.max(0) as f64
+}
+
+/// A budget refilled continuously over a minute
+struct TokenBucket {
+    capacity: f64,
+
+    available: f64,
+
+    refill_per_sec: f64,
+
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn per_minute(capacity: u32) -> Self {
+        let capacity = capacity.max(1) as f64;
+
+        Self {
+            capacity,
+            available: capacity,
+            refill_per_sec: capacity / 60.0,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Adds `amount` back to the bucket, a negative amount leaves it owing tokens that later requests
+    /// wait for
+    fn give_back(&mut self, amount: f64) {
+        self.available = (self.available + amount).min(self.capacity);
+    }
+
+    /// Takes `amount` from the bucket, or returns how long to wait until it can be taken
+    fn try_take(&mut self, amount: f64) -> Option<Duration> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.available = (self.available + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+
+        // A single request larger than the whole budget only has to wait for a full bucket
+        let amount = amount.min(self.capacity);
+
+        if self.available >= amount {
+            self.available -= amount;
+
+            None
+        } else {
+            Some(Duration::from_secs_f64(
+                (amount - self.available) / self.refill_per_sec,
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chatgpt::{ChatChoice, ChatMessage, ChatUsage};
+    use std::sync::Mutex as StdMutex;
+
+    /// Replies after `latency` with `used_tokens` of usage, failing the first `failures` requests with a 429
+    struct FakeProvider {
+        latency: Duration,
+
+        used_tokens: i64,
+
+        failures: AtomicU64,
+
+        /// The first message of every request, in the order they were sent
+        sent: StdMutex<Vec<String>>,
+    }
+
+    impl FakeProvider {
+        fn new(latency: Duration, used_tokens: i64, failures: u64) -> Arc<Self> {
+            Arc::new(Self {
+                latency,
+                used_tokens,
+                failures: AtomicU64::new(failures),
+                sent: StdMutex::new(Vec::new()),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl ChatProvider for FakeProvider {
+        fn name(&self) -> &str {
+            "fake"
+        }
+
+        async fn send(&self, request: &ChatRequest) -> Result<ChatResponse, LlmError> {
+            self.sent
+                .lock()
+                .unwrap()
+                .push(request.messages[0].content.clone());
+            sleep(self.latency).await;
+
+            if self
+                .failures
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |f| f.checked_sub(1))
+                .is_ok()
+            {
+                return Err(LlmError::Status {
+                    status: 429,
+                    request_id: None,
+                    error_type: None,
+                    message: String::from("slow down"),
+                    retry_after: Some(Duration::from_secs(10)),
+                });
+            }
+
+            Ok(ChatResponse {
+                choices: vec![ChatChoice {
+                    message: ChatMessage::assistant(String::from("{}")),
+                    ..Default::default()
+                }],
+                usage: ChatUsage {
+                    total_tokens: self.used_tokens,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+        }
+    }
+
+    fn request(content: &str, max_tokens: i64) -> ChatRequest {
+        ChatRequest {
+            messages: vec![ChatMessage::user(content.to_string())],
+            max_tokens,
+            ..Default::default()
+        }
+    }
+
+    fn limits(tokens_per_minute: Option<u32>, max_concurrency: usize) -> RateLimits {
+        RateLimits {
+            tokens_per_minute,
+            max_concurrency,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn backoff_releases_the_permit() {
+        let fake = FakeProvider::new(Duration::from_secs(1), 0, 1);
+        let provider = Arc::new(RateLimitedProvider::new(
+            fake.clone(),
+            limits(None, 1),
+            Arc::new(LimiterStats::default()),
+        ));
+        let start = Instant::now();
+
+        let retried = tokio::spawn({
+            let provider = provider.clone();
+            async move { provider.send(&request("retried", 0)).await }
+        });
+        // Let the first request take the only permit and fail
+        sleep(Duration::from_millis(1500)).await;
+
+        provider.send(&request("other", 0)).await.unwrap();
+        assert!(start.elapsed() < Duration::from_secs(10));
+
+        retried.await.unwrap().unwrap();
+        assert_eq!(provider.stats().retries(), 1);
+        assert_eq!(
+            *fake.sent.lock().unwrap(),
+            vec!["retried", "other", "retried"]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn permit_caps_requests_in_flight() {
+        let fake = FakeProvider::new(Duration::from_secs(1), 0, 0);
+        let provider = Arc::new(RateLimitedProvider::new(
+            fake,
+            limits(None, 2),
+            Arc::new(LimiterStats::default()),
+        ));
+        let start = Instant::now();
+
+        let sends: Vec<_> = (0..4)
+            .map(|i| {
+                let provider = provider.clone();
+                tokio::spawn(async move { provider.send(&request(&i.to_string(), 0)).await })
+            })
+            .collect();
+        for send in sends {
+            send.await.unwrap().unwrap();
+        }
+
+        assert_eq!(start.elapsed(), Duration::from_secs(2));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn tokens_used_below_the_estimate_are_given_back() {
+        let stats = Arc::new(LimiterStats::default());
+        let provider = RateLimitedProvider::new(
+            FakeProvider::new(Duration::ZERO, 100, 0),
+            limits(Some(1000), 1),
+            stats.clone(),
+        );
+
+        // Each request is estimated at 500 tokens but uses 100, so none of them has to wait
+        for _ in 0..5 {
+            provider.send(&request("", 500)).await.unwrap();
+        }
+
+        assert_eq!(stats.throttled_waits(), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn tokens_used_above_the_estimate_are_waited_for() {
+        let stats = Arc::new(LimiterStats::default());
+        let provider = RateLimitedProvider::new(
+            FakeProvider::new(Duration::ZERO, 1000, 0),
+            limits(Some(1000), 1),
+            stats.clone(),
+        );
+        let start = Instant::now();
+
+        // The first request is estimated at 500 tokens but uses the whole minute's budget
+        provider.send(&request("", 500)).await.unwrap();
+        provider.send(&request("", 500)).await.unwrap();
+
+        assert_eq!(stats.throttled_waits(), 1);
+        assert!(start.elapsed() >= Duration::from_secs(30));
+    }
+
+    #[test]
+    fn bucket_waits_for_the_missing_tokens() {
+        let mut bucket = TokenBucket::per_minute(60);
+
+        assert_eq!(bucket.try_take(60.0), None);
+        bucket.give_back(-30.0);
+
+        let wait = bucket.try_take(10.0).unwrap();
+        assert!((wait.as_secs_f64() - 40.0).abs() < 0.1);
+    }
+}
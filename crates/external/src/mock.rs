@@ -0,0 +1,313 @@
+use crate::{
+    cassette::cassette_key,
+    chatgpt::{ChatChoice, ChatMessage, ChatRequest, ChatResponse, ChatUsage},
+    errors::LlmError,
+    provider::ChatProvider,
+};
+use async_trait::async_trait;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use serde_json::{json, Value};
+
+/// The prompt families the mock provider knows how to answer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PromptFamily {
+    Relevance,
+    Soundness,
+    Grammar,
+    MasteryVocab,
+    PersonaContent,
+    Vote,
+    Sentiment,
+    Learned,
+    EnneagramTendency,
+    EnneagramContent,
+    GenerateBlog,
+    Nahco,
+}
+
+impl PromptFamily {
+    /// Recognizes the family of a prompt from the text only that family asks for
+    pub fn detect(prompt: &str) -> Option<Self> {
+        // Ordered from the most to the least specific signature
+        let signatures = [
+            ("\"type9\"", PromptFamily::EnneagramTendency),
+            (
+                "Tendency to cast a valid vote",
+                PromptFamily::EnneagramContent,
+            ),
+            (
+                "How do the attributes of the user change",
+                PromptFamily::Learned,
+            ),
+            (
+                "what vote is the user most likely to cast",
+                PromptFamily::Vote,
+            ),
+            (
+                "what sentiment is the user most likely to have",
+                PromptFamily::Sentiment,
+            ),
+            ("\"ethos\"", PromptFamily::PersonaContent),
+            (
+                "what response would this user give",
+                PromptFamily::PersonaContent,
+            ),
+            ("\"mastery_vocab\"", PromptFamily::MasteryVocab),
+            ("\"relevance\"", PromptFamily::Relevance),
+            ("\"soundness\"", PromptFamily::Soundness),
+            ("\"grammar\"", PromptFamily::Grammar),
+            ("generate a blog post", PromptFamily::GenerateBlog),
+            ("Venture Capital, Startups", PromptFamily::Nahco),
+        ];
+
+        signatures
+            .iter()
+            .find(|(signature, _)| prompt.contains(signature))
+            .map(|(_, family)| *family)
+    }
+}
+
+const WORDS: [&str; 32] = [
+    "policy",
+    "evidence",
+    "community",
+    "market",
+    "people",
+    "argument",
+    "history",
+    "research",
+    "economy",
+    "freedom",
+    "balance",
+    "growth",
+    "risk",
+    "trust",
+    "values",
+    "future",
+    "data",
+    "change",
+    "government",
+    "family",
+    "health",
+    "education",
+    "technology",
+    "cost",
+    "impact",
+    "debate",
+    "opinion",
+    "study",
+    "report",
+    "system",
+    "choice",
+    "outcome",
+];
+
+const LINKS: [&str; 4] = [
+    "https://example.com/research",
+    "https://example.org/report",
+    "https://news.example.com/article",
+    "https://data.example.net/study",
+];
+
+/// A synthetic provider answering every known prompt family with schema valid JSON,
+/// for running simulations offline and at scale
+pub struct MockProvider {
+    seed: u64,
+}
+
+impl MockProvider {
+    /// Replies are generated from `seed` and a hash of the request, so the same
+    /// request always gets the same reply regardless of the order requests arrive in
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    fn reply(&self, family: PromptFamily, rng: &mut StdRng) -> Value {
+        match family {
+            PromptFamily::Relevance => json!({ "relevance": score(rng) }),
+
+            PromptFamily::Soundness => json!({ "soundness": score(rng) }),
+
+            PromptFamily::Grammar => json!({ "grammar": score(rng) }),
+
+            PromptFamily::MasteryVocab => {
+                let count = rng.gen_range(0..6);
+                let words: Vec<&str> = WORDS.choose_multiple(rng, count).cloned().collect();
+
+                json!({ "mastery_words": words, "mastery_vocab": count })
+            }
+
+            PromptFamily::PersonaContent => {
+                let (ethos, pathos, logos) = split_three(rng);
+                let reference = LINKS.choose(rng).unwrap();
+
+                json!({
+                    "content": format!("{} {}", sentences(rng, 3), reference),
+                    "confidence": score(rng),
+                    "ethos": ethos,
+                    "pathos": pathos,
+                    "logos": logos,
+                    "reason": sentences(rng, 1),
+                    "reference": reference,
+                })
+            }
+
+            PromptFamily::Vote => json!({
+                "vote": pick(rng, &["valid", "invalid", "abstain"]),
+                "reason": sentences(rng, 1),
+            }),
+
+            PromptFamily::Sentiment => json!({
+                "sentiment": pick(rng, &["positive", "negative", "neutral"]),
+                "reason": sentences(rng, 1),
+            }),
+
+            PromptFamily::Learned => json!({
+                "political_orientation": pick(rng, &["left", "right", "center"]),
+                "enneagram_type": rng.gen_range(1..10).to_string(),
+                "gender": pick(rng, &["male", "female", "nonbinary"]),
+                "age": rng.gen_range(18..80),
+                "core_fear": WORDS.choose(rng).unwrap(),
+                "core_desire": WORDS.choose(rng).unwrap(),
+                "valid_vote_tendency": format!("{:.2}", score(rng)),
+                "invalid_vote_tendency": format!("{:.2}", score(rng)),
+                "abstain_vote_tendency": format!("{:.2}", score(rng)),
+                "report_tendency": format!("{:.2}", score(rng)),
+                "hide_tendency": format!("{:.2}", score(rng)),
+                "reason": sentences(rng, 1),
+            }),
+
+            PromptFamily::EnneagramTendency => {
+                let mut tendencies = serde_json::Map::new();
+                for t in 1..=9 {
+                    tendencies.insert(
+                        format!("type{}", t),
+                        json!({
+                            "valid_vote_tendency": score(rng),
+                            "invalid_vote_tendency": score(rng),
+                            "abstain_vote_tendency": score(rng),
+                            "report_tendency": score(rng),
+                            "hide_tendency": score(rng),
+                        }),
+                    );
+                }
+
+                Value::Object(tendencies)
+            }
+
+            PromptFamily::EnneagramContent => json!({
+                "content": format!("{} {}", sentences(rng, 2), LINKS.choose(rng).unwrap()),
+                "confidence": score(rng),
+            }),
+
+            PromptFamily::GenerateBlog => json!({
+                "title": sentences(rng, 1),
+                "body": sentences(rng, 8),
+            }),
+
+            PromptFamily::Nahco => json!({ "answer": rng.gen_bool(0.5) }),
+        }
+    }
+}
+
+#[async_trait]
+impl ChatProvider for MockProvider {
+    fn name(&self) -> &str {
+        "mock"
+    }
+
+    async fn send(&self, request: &ChatRequest) -> Result<ChatResponse, LlmError> {
+        let prompt = match request.messages.last() {
+            Some(message) => &message.content,
+            None => "",
+        };
+
+        // A repair re-prompt is answered like the prompt it follows up on
+        let family = request
+            .messages
+            .iter()
+            .rev()
+            .find_map(|message| PromptFamily::detect(&message.content));
+
+        let family = match family {
+            Some(family) => family,
+
+            None => {
+                return Err(LlmError::Status {
+                    status: 400,
+                    request_id: None,
+                    error_type: Some(String::from("unknown_prompt")),
+                    message: String::from("the mock provider does not recognize this prompt"),
+                    retry_after: None,
+                })
+            }
+        };
+
+        let key = cassette_key(request);
+        let request_hash = u64::from_str_radix(&key[..16], 16).unwrap_or_default();
+        let mut rng = StdRng::seed_from_u64(self.seed ^ request_hash);
+
+        let content = self.reply(family, &mut rng).to_string();
+
+        let prompt_tokens = (prompt.len() / 4) as i64;
+        let completion_tokens = (content.len() / 4) as i64;
+
+        Ok(ChatResponse {
+            id: Some(format!("mock-{}", &key[..16])),
+            object: String::from("chat.completion"),
+            created: 0,
+            model: request.model.clone(),
+            choices: vec![ChatChoice {
+                index: 0,
+                message: ChatMessage {
+                    role: String::from("assistant"),
+                    content,
+                },
+                finish_reason: Some(String::from("stop")),
+            }],
+            usage: ChatUsage {
+                prompt_tokens,
+                completion_tokens,
+                total_tokens: prompt_tokens + completion_tokens,
+            },
+            request_id: None,
+        })
+    }
+}
+
+/// A score between 0.0 and .999, rounded like a model would answer
+fn score(rng: &mut StdRng) -> f64 {
+    (rng.gen_range(0.0..0.999) * 1000.0_f64).floor() / 1000.0
+}
+
+/// Three weights that add up to 1.0
+fn split_three(rng: &mut StdRng) -> (f64, f64, f64) {
+    let a: f64 = rng.gen_range(0.0..1.0);
+    let b: f64 = rng.gen_range(0.0..1.0 - a);
+
+    let a = (a * 100.0).round() / 100.0;
+    let b = (b * 100.0).round() / 100.0;
+
+    (a, b, ((1.0 - a - b) * 100.0).round() / 100.0)
+}
+
+fn pick<'a>(rng: &mut StdRng, options: &[&'a str]) -> &'a str {
+    options.choose(rng).unwrap()
+}
+
+fn sentences(rng: &mut StdRng, count: usize) -> String {
+    let mut out = Vec::with_capacity(count);
+
+    for _ in 0..count {
+        let len = rng.gen_range(6..14);
+        let words: Vec<&str> = (0..len).map(|_| *WORDS.choose(rng).unwrap()).collect();
+
+        let mut sentence = words.join(" ");
+        sentence[..1].make_ascii_uppercase();
+        sentence.push('.');
+
+        out.push(sentence);
+    }
+
+    out.join(" ")
+}
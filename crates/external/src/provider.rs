@@ -0,0 +1,172 @@
+use crate::{
+    chatgpt::{ChatRequest, ChatResponse},
+    errors::{ApiErrorBody, LlmError},
+};
+use async_trait::async_trait;
+use reqwest::{header::HeaderMap, Client};
+use std::{sync::Arc, time::Duration};
+
+/// Base url of the public OpenAI api
+pub const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+
+/// Base url used for OpenAI compatible servers when none is configured
+/// (llama.cpp, vLLM and Ollama all serve an OpenAI style api)
+pub const LOCAL_BASE_URL: &str = "http://localhost:8080/v1";
+
+/// A backend that can serve chat completion requests
+#[async_trait]
+pub trait ChatProvider: Send + Sync {
+    /// The name of the provider, used when reporting
+    fn name(&self) -> &str;
+
+    /// Sends a chat completion request to the provider
+    async fn send(&self, request: &ChatRequest) -> Result<ChatResponse, LlmError>;
+}
+
+/// The public OpenAI chat completions api
+pub struct OpenAIProvider {
+    key: Arc<String>,
+
+    client: Client,
+}
+
+impl OpenAIProvider {
+    pub fn new(key: Arc<String>) -> Self {
+        Self {
+            key,
+            client: Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl ChatProvider for OpenAIProvider {
+    fn name(&self) -> &str {
+        "openai"
+    }
+
+    async fn send(&self, request: &ChatRequest) -> Result<ChatResponse, LlmError> {
+        post_chat_completion(&self.client, OPENAI_BASE_URL, Some(&self.key), request).await
+    }
+}
+
+/// Any server exposing an OpenAI compatible `/chat/completions` endpoint,
+/// i.e. a local llama.cpp, vLLM or Ollama server, or a stub server in CI
+pub struct OpenAICompatibleProvider {
+    base_url: String,
+
+    key: Option<Arc<String>>,
+
+    /// Overrides the model set on each request, local servers rarely serve `gpt-3.5-turbo`
+    model: Option<String>,
+
+    client: Client,
+}
+
+impl OpenAICompatibleProvider {
+    pub fn new(base_url: String, key: Option<Arc<String>>, model: Option<String>) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            key,
+            model,
+            client: Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl ChatProvider for OpenAICompatibleProvider {
+    fn name(&self) -> &str {
+        "openai_compatible"
+    }
+
+    async fn send(&self, request: &ChatRequest) -> Result<ChatResponse, LlmError> {
+        match &self.model {
+            Some(model) => {
+                let mut request = request.clone();
+                request.model = model.clone();
+
+                post_chat_completion(&self.client, &self.base_url, self.key.as_deref(), &request)
+                    .await
+            }
+
+            None => {
+                post_chat_completion(&self.client, &self.base_url, self.key.as_deref(), request)
+                    .await
+            }
+        }
+    }
+}
+
+async fn post_chat_completion(
+    client: &Client,
+    base_url: &str,
+    key: Option<&String>,
+    request: &ChatRequest,
+) -> Result<ChatResponse, LlmError> {
+    let url = format!("{}/chat/completions", base_url);
+
+    let mut req = client.post(url).json(request);
+    if let Some(key) = key {
+        req = req.bearer_auth(key);
+    }
+
+    let res = req
+        .send()
+        .await
+        .map_err(|e| LlmError::Transport(e.to_string()))?;
+
+    let status = res.status();
+    let request_id = res
+        .headers()
+        .get("x-request-id")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_string());
+    let retry_after = retry_after(res.headers());
+
+    if !status.is_success() {
+        let body = res.text().await.unwrap_or_default();
+
+        return Err(match serde_json::from_str::<ApiErrorBody>(&body) {
+            Ok(err) => LlmError::Status {
+                status: status.as_u16(),
+                request_id,
+                error_type: err.error.error_type,
+                message: err.error.message,
+                retry_after,
+            },
+
+            Err(_) => LlmError::Status {
+                status: status.as_u16(),
+                request_id,
+                error_type: None,
+                message: body,
+                retry_after,
+            },
+        });
+    }
+
+    let mut chat_res = res
+        .json::<ChatResponse>()
+        .await
+        .map_err(|e| LlmError::Decode(e.to_string()))?;
+    chat_res.request_id = request_id;
+
+    Ok(chat_res)
+}
+
+/// Reads `retry-after-ms`, falling back to `retry-after` in seconds
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .filter(|v| v.is_finite() && *v >= 0.0)
+    };
+
+    match header("retry-after-ms") {
+        Some(ms) => Some(Duration::from_secs_f64(ms / 1000.0)),
+        None => header("retry-after").map(Duration::from_secs_f64),
+    }
+}
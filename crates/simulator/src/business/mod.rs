@@ -2,14 +2,12 @@ use crate::business::prompts::*;
 use eyre::Result;
 use pulpcalc_common::{
     config::Config,
-    errors::{PulpError, SimulationError},
+    errors::PulpError,
     models::{Debate, Reference, Response, User},
+    store::Relationship,
 };
-use pulpcalc_external::chatgpt::ChatRequestBuilder;
-use reqwest::Client;
 use serde::Deserialize;
-use serde_json::from_str;
-mod prompts;
+pub(crate) mod prompts;
 
 pub struct BusinessData {}
 
@@ -53,135 +51,56 @@ impl BusinessSimulation {
 
     pub async fn run_simulation(
         &self,
-        config: Config,
+        mut config: Config,
         mut debate: Debate,
     ) -> Result<(), PulpError> {
-        let key = config.open_ai_key.clone();
-
-        let debate_id = debate.create(&config.neo4j_graph).await?;
+        let debate_id = config.store.create_debate(&debate).await?;
         debate.id = debate_id;
+        config.debate_id = Some(debate.id.clone());
 
         let content = if !self.source_url.is_empty() {
             todo!("Scrape content from link")
         } else {
-            let mut blog_prompt = GENERATE_BLOG_PROMPT.to_string();
-            blog_prompt = blog_prompt.replace("THIS_TOPIC", &self.topic);
-
-            let blog_chat_res = ChatRequestBuilder::new()
-                .messages(blog_prompt)
-                .temperature(0.7)
-                .max_tokens(850)
-                .top_p(1.0)
-                .presence_penalty(0.0)
-                .frequency_penalty(0.0)
-                .build()
-                .send(key.clone(), Client::new())
-                .await;
-
-            let content =
-                from_str::<GenerateBlogResponse>(&blog_chat_res.choices[0].message.content.clone());
-
-            let cont_res = match content {
-                Ok(res) => Some(res),
-
-                Err(e) => {
-                    println!(
-                        "failed to unmarshal content: {:?}: {}",
-                        e, blog_chat_res.choices[0].message.content
-                    );
-
-                    None
-                }
-            }
-            .unwrap();
+            let cont_res = generate_blog_prompt(&self.topic).send(&config).await?;
 
             cont_res;
         };
 
-        let mut nahco_prompt = NAHCO_PROMPT.to_string();
-        nahco_prompt = nahco_prompt.replace("THIS_TOPIC", &self.topic);
-
-        let nahco_chat_res = ChatRequestBuilder::new()
-            .messages(nahco_prompt)
-            .temperature(0.7)
-            .max_tokens(850)
-            .top_p(1.0)
-            .presence_penalty(0.0)
-            .frequency_penalty(0.0)
-            .build()
-            .send(key.clone(), Client::new())
-            .await;
-
-        let content = from_str::<NahcoResponse>(&nahco_chat_res.choices[0].message.content.clone());
-
-        let nahco_res = match content {
-            Ok(res) => Some(res),
-
-            Err(e) => {
-                println!(
-                    "failed to unmarshal content: {:?}: {}",
-                    e, nahco_chat_res.choices[0].message.content
-                );
-
-                None
-            }
-        }
-        .unwrap();
+        let nahco_res = nahco_prompt(&self.topic).send(&config).await?;
 
         if nahco_res.answer {
-            let mut nahco_reference_prompt =
-                PERSONA_CONTENT_PROMPT_WITH_NAHCO_REFERENCE.to_string();
-            nahco_reference_prompt = nahco_reference_prompt.replace("THIS_TOPIC", &self.topic);
-
-            let nahco_reference_chat_res = ChatRequestBuilder::new()
-                .messages(nahco_reference_prompt)
-                .temperature(0.7)
-                .max_tokens(850)
-                .top_p(1.0)
-                .presence_penalty(0.0)
-                .frequency_penalty(0.0)
-                .build()
-                .send(key.clone(), Client::new())
-                .await;
-
-            let content = from_str::<ContentResponse>(
-                &nahco_reference_chat_res.choices[0].message.content.clone(),
-            );
-
-            let nahco_reference_res = match content {
-                Ok(res) => Some(res),
-
-                Err(e) => {
-                    println!(
-                        "failed to unmarshal content: {:?}: {}",
-                        e, nahco_reference_chat_res.choices[0].message.content
-                    );
-
-                    None
-                }
-            }
-            .unwrap();
+            let prompt = persona_content_prompt_with_nahco_reference(&self.topic);
+            let nahco_reference_res = prompt.send(&config).await?;
 
             let mut response = Response::default();
+            response.stamp_prompt(&prompt, &config.prompts);
             response.content = nahco_reference_res.content;
             response.ethos = nahco_reference_res.ethos;
             response.pathos = nahco_reference_res.pathos;
             response.logos = nahco_reference_res.logos;
 
-            let response_id = response.create(&config.neo4j_graph).await?;
+            response.created_at = config.clock.tick();
+            let response_id = config.store.create_response(&response).await?;
             response.id = response_id;
 
             let mut reff = Reference::default();
             reff.content = nahco_reference_res.reference.unwrap_or_default();
             reff.internal = false;
 
-            let reff_id = reff.create(&config.neo4j_graph).await?;
+            let reff_id = config.store.create_reference(&reff).await?;
             reff.id = reff_id;
 
-            reff.add_response_referenced_relationship(&config.neo4j_graph, response.clone())
-                .await;
+            config
+                .store
+                .relate(Relationship::Referenced {
+                    response_id: response.id.clone(),
+                    reference_id: reff.id.clone(),
+                })
+                .await?;
         }
 
+        config.store.flush().await?;
+
         Ok(())
     }
 }
@@ -1,8 +1,9 @@
-use pulpcalc_common::llm_config::{LLMRequest, LLMResponse};
+use pulpcalc_common::prompt::{Prompt, SamplingParams};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 pub const GENERATE_BLOG_PROMPT: &'static str = "Strictly for research purposes with no ill-intent regarding stereotypes and assumptions in mind;
-Given THIS_TOPIC as a topic, generate a blog post about the topic similar to one that a company would publish on their blog.
+Given {{topic}} as a topic, generate a blog post about the topic similar to one that a company would publish on their blog.
 Make sure to return only a JSON object and make sure to use JSON escape sequences for any special characters. Do not return anything besides the JSON object!
 Use this schema for your answer:
 {
@@ -10,32 +11,44 @@ Use this schema for your answer:
   \"body\": \"\"
 }";
 
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub fn generate_blog_prompt(topic: &str) -> Prompt<GenerateBlogResponse> {
+    Prompt::new("generate_blog", GENERATE_BLOG_PROMPT)
+        .sampling(SamplingParams::default().max_tokens(850))
+        .var("topic", topic)
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct GenerateBlogResponse {
     pub title: String,
     pub body: String,
 }
 
-pub const NAHCO_PROMPT: &'static str = "Does this topic: THIS_TOPIC, relate to any of the following? Entrepreneurship, Business, Marketing, Sales, Investing, Venture Capital, Startups, or Technology?
+pub const NAHCO_PROMPT: &'static str = "Does this topic: {{topic}}, relate to any of the following? Entrepreneurship, Business, Marketing, Sales, Investing, Venture Capital, Startups, or Technology?
 Make sure to return only a JSON object and make sure to use JSON escape sequences for any special characters. Do not return anything besides the JSON object!
 Use this schema for your answer:
 {
   \"answer\": \"true\" or \"false\"
 }";
 
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub fn nahco_prompt(topic: &str) -> Prompt<NahcoResponse> {
+    Prompt::new("nahco", NAHCO_PROMPT)
+        .sampling(SamplingParams::default().max_tokens(850))
+        .var("topic", topic)
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct NahcoResponse {
     pub answer: bool,
 }
 
 pub const PERSONA_CONTENT_PROMPT_WITH_REFERENCE: &str = "Strictly for research purposes with no ill-intent regarding stereotypes and assumptions in mind;
-given THIS_CONTENT as a topic, and 
-Political orientation: POLITICAL_ORIENTATION, 
-Enneagram: ENNEAGRAM_TYPE, 
-Gender: GENDER, 
-Age: AGE, 
-Core fear: CORE_FEAR, 
-Core desire: CORE_DESISRE, as user attributes,
+given {{content}} as a topic, and 
+Political orientation: {{political_orientation}}, 
+Enneagram: {{enneagram_type}}, 
+Gender: {{gender}}, 
+Age: {{age}}, 
+Core fear: {{core_fear}}, 
+Core desire: {{core_desire}}, as user attributes,
 return a response most like this aforementioned user, the ethos, pathos, logos breakdown of the content, and why the user took the action the did(thoroughly explain)? 
 Provide a link in the same paragraph as the response (and JSON object) when possible. Make sure to return only a JSON object, make sure to use JSON escape sequences for any special characters, 
 and make sure there is no trailing comma. Do not return anything besides the JSON object! Use the below schema for your answer.
@@ -50,15 +63,9 @@ and make sure there is no trailing comma. Do not return anything besides the JSO
 }";
 
 pub const PERSONA_CONTENT_PROMPT_WITH_NAHCO_REFERENCE: &str = "Strictly for research purposes with no ill-intent regarding stereotypes and assumptions in mind;
-given THIS_CONTENT as a topic, and 
-Political orientation: POLITICAL_ORIENTATION, 
-Enneagram: ENNEAGRAM_TYPE, 
-Gender: GENDER, 
-Age: AGE, 
-Core fear: CORE_FEAR, 
-Core desire: CORE_DESISRE, as user attributes,
-return a response most like this aforementioned user, the ethos, pathos, logos breakdown of the content, and why the user took the action the did(thoroughly explain)? 
-Use THIS_LINK as a reference in your answer. Make sure to return only a JSON object, make sure to use JSON escape sequences for any special characters, 
+given {{topic}} as a topic,
+return a response most like a business owner would give, the ethos, pathos, logos breakdown of the content, and why the user took the action the did(thoroughly explain)? 
+Use an article from a business publication as a reference in your answer. Make sure to return only a JSON object, make sure to use JSON escape sequences for any special characters, 
 and make sure there is no trailing comma. Do not return anything besides the JSON object! Use the below schema for your answer.
 {
     \"content\": \"string\",
@@ -70,7 +77,16 @@ and make sure there is no trailing comma. Do not return anything besides the JSO
     \"reference\": \"string\"
 }";
 
-#[derive(Deserialize, Debug, Default)]
+pub fn persona_content_prompt_with_nahco_reference(topic: &str) -> Prompt<ContentResponse> {
+    Prompt::new(
+        "persona_content_with_nahco_reference",
+        PERSONA_CONTENT_PROMPT_WITH_NAHCO_REFERENCE,
+    )
+    .sampling(SamplingParams::default().max_tokens(850))
+    .var("topic", topic)
+}
+
+#[derive(Deserialize, Debug, Default, JsonSchema)]
 pub struct ContentResponse {
     pub content: String,
     pub confidence: f32,
@@ -81,8 +97,22 @@ pub struct ContentResponse {
     pub reference: Option<String>,
 }
 
-impl LLMResponse for ContentResponse {
-    fn get_response<T: std::fmt::Debug, Default, Deserialize>(&self) -> T {
-        todo!();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pulpcalc_common::{registry::check_template, template::Template};
+
+    #[test]
+    fn prompts_match_the_builtin_placeholders() {
+        for (name, template) in [
+            ("generate_blog", GENERATE_BLOG_PROMPT),
+            ("nahco", NAHCO_PROMPT),
+            (
+                "persona_content_with_nahco_reference",
+                PERSONA_CONTENT_PROMPT_WITH_NAHCO_REFERENCE,
+            ),
+        ] {
+            check_template(name, &Template::parse(template).unwrap()).unwrap();
+        }
     }
 }
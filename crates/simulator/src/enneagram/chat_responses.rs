@@ -1,6 +1,7 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
 pub struct TendencyRespose {
     pub type1: ActionTendencies,
     pub type2: ActionTendencies,
@@ -30,7 +31,7 @@ impl TendencyRespose {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, JsonSchema)]
 pub struct ActionTendencies {
     pub valid_vote_tendency: f64,
 
@@ -43,7 +44,7 @@ pub struct ActionTendencies {
     pub hide_tendency: f64,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct ContentReponse {
     pub confidence: f64,
 
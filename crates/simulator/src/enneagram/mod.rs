@@ -1,23 +1,23 @@
 use eyre::Result;
 use pulpcalc_common::{
     config::Config,
-    errors::{PulpError, SimulationError},
+    errors::PulpError,
     models::{Debate, Reference, Response, User},
+    store::Relationship,
 };
-use pulpcalc_external::chatgpt::ChatRequestBuilder;
 use rand::prelude::*;
 use regex::Regex;
-use reqwest::Client;
 use serde::Deserialize;
-use serde_json::from_str;
 
-use crate::enneagram::chat_responses::{ActionTendencies, ContentReponse, TendencyRespose};
-use crate::enneagram::prompts::{
-    ENNEAGRAM_REPLY_CONTENT_PROMPT, ENNEAGRAM_RESPONSE_CONTENT_PROMPT, ENNEAGRAM_TENDENCY_PROMPT,
-};
+use crate::enneagram::chat_responses::ActionTendencies;
+use crate::enneagram::prompts::{reply_content_prompt, response_content_prompt, tendency_prompt};
+use crate::util::{pick, score_round};
+
+pub(crate) mod chat_responses;
+pub(crate) mod prompts;
 
-mod chat_responses;
-mod prompts;
+/// Why a simulation whose distribution places no users fails
+const NO_USERS: &str = "no enneagram users; raise simulation_size or the distribution";
 
 #[derive(Debug, Default)]
 pub struct EnneagramData {
@@ -93,45 +93,17 @@ impl EnneagramSimulation {
 
     pub async fn run_simulation(
         &self,
-        config: Config,
+        mut config: Config,
         mut debate: Debate,
+        rng: &mut StdRng,
     ) -> Result<(), PulpError> {
-        let debate_id = debate.create(&config.neo4j_graph).await?;
+        let debate_id = config.store.create_debate(&debate).await?;
         debate.id = debate_id;
+        config.debate_id = Some(debate.id.clone());
 
         let mut users: Vec<EnneagramUser> = Vec::new();
 
-        let mut t = ENNEAGRAM_TENDENCY_PROMPT.to_string();
-        t = t.replace("THIS_TOPIC", &self.topic.clone());
-
-        let key = config.open_ai_key.clone();
-        let tendency_chat_res = ChatRequestBuilder::new()
-            .messages(t)
-            .temperature(0.7)
-            .max_tokens(850)
-            .top_p(1.0)
-            .presence_penalty(0.0)
-            .frequency_penalty(0.0)
-            .build()
-            .send(key.clone(), Client::new())
-            .await;
-
-        let tendencies =
-            from_str::<TendencyRespose>(&tendency_chat_res.choices[0].message.content.clone());
-
-        let t_res = match tendencies {
-            Ok(res) => Some(res),
-
-            Err(e) => {
-                println!(
-                    "failed to unmarshal tendencies: {:?}: {}",
-                    e, tendency_chat_res.choices[0].message.content
-                );
-
-                None
-            }
-        }
-        .unwrap();
+        let t_res = tendency_prompt(&self.topic).send(&config).await?;
 
         // generate responses to that content
         for d in self.distribution.iter() {
@@ -141,12 +113,16 @@ impl EnneagramSimulation {
                 let mut user = EnneagramUser::default();
                 user.tendencies = t_res.clone().map_user_tendencies(i as i64);
 
-                let user_id = user.base_user.create(&config.neo4j_graph).await?;
+                let user_id = config.store.create_user(&user.base_user).await?;
                 user.base_user.id = user_id;
 
-                debate
-                    .add_participant(&config.neo4j_graph, user.base_user.clone())
-                    .await;
+                config
+                    .store
+                    .relate(Relationship::Participated {
+                        user_id: user.base_user.id.clone(),
+                        debate_id: debate.id.clone(),
+                    })
+                    .await?;
 
                 users.push(user);
 
@@ -155,70 +131,32 @@ impl EnneagramSimulation {
         }
 
         for _ in 1..self.simulation_size {
-            let rint = (random::<f32>() * users.clone().len() as f32).floor() as usize;
-            let rand_user = &users[rint];
-
-            let mut cont_prompt = ENNEAGRAM_RESPONSE_CONTENT_PROMPT.to_string();
-            cont_prompt = cont_prompt.replace(
-                "VALID_VOTE_TENDENCY",
-                &rand_user.tendencies.valid_vote_tendency.to_string(),
-            );
-            cont_prompt = cont_prompt.replace(
-                "INVALID_VOTE_TENDENCY",
-                &rand_user.tendencies.invalid_vote_tendency.to_string(),
-            );
-            cont_prompt = cont_prompt.replace(
-                "ABSTAIN_VOTE_TENDENCY",
-                &rand_user.tendencies.invalid_vote_tendency.to_string(),
-            );
-            cont_prompt = cont_prompt.replace(
-                "REPORT_TENDENCY",
-                &rand_user.tendencies.invalid_vote_tendency.to_string(),
-            );
-            cont_prompt = cont_prompt.replace(
-                "HIDE_TENDENCY",
-                &rand_user.tendencies.invalid_vote_tendency.to_string(),
-            );
-            cont_prompt = cont_prompt.replace("THIS_TOPIC", &self.topic.clone());
-
-            let response_chat_res = ChatRequestBuilder::new()
-                .messages(cont_prompt)
-                .temperature(0.7)
-                .max_tokens(850)
-                .top_p(1.0)
-                .presence_penalty(0.0)
-                .frequency_penalty(0.0)
-                .build()
-                .send(key.clone(), Client::new())
-                .await;
+            config.llm_usage.check_budget(&config.llm_budget)?;
 
-            let content =
-                from_str::<ContentReponse>(&response_chat_res.choices[0].message.content.clone());
+            let rand_user = pick(&users, rng, NO_USERS)?;
 
-            let cont_res = match content {
-                Ok(res) => Some(res),
+            let prompt = response_content_prompt(&self.topic, &rand_user.tendencies);
+            let cont_res = match prompt.send(&config).await {
+                Ok(res) => res,
 
                 Err(e) => {
-                    println!(
-                        "failed to unmarshal content: {:?}: {}",
-                        e, response_chat_res.choices[0].message.content
-                    );
+                    println!("failed to generate response: {}", e);
 
-                    None
+                    continue;
                 }
-            }
-            .unwrap();
+            };
 
             let mut debate_response = Response::default();
+            debate_response.stamp_prompt(&prompt, &config.prompts);
             debate_response.content = cont_res.content.clone();
             debate_response.confidence = cont_res.confidence;
 
             debate_response.score = debate_response
-                .calculate_content_attribute_score(key.clone())
-                .await?
-                + debate_response.calculate_engagement_score();
+                .calculate_content_attribute_score(&config)
+                .await?;
 
-            let debate_response_id = debate_response.create(&config.neo4j_graph).await?;
+            debate_response.created_at = config.clock.tick();
+            let debate_response_id = config.store.create_response(&debate_response).await?;
             debate_response.id = debate_response_id;
 
             let re = Regex::new(r"/(?:(?:https?|ftp|file):\\|www\\.|ftp\\.)(?:\\([-A-Z0-9+&@#\\%=~_|$?!:,.]*\\)|[-A-Z0-9+&@#\\%=~_|$?!:,.])*(?:\\([-A-Z0-9+&@#\\%=~_|$?!:,.]*\\)|[A-Z0-9+&@#\\%=~_|$])/igm").unwrap();
@@ -226,103 +164,90 @@ impl EnneagramSimulation {
                 let mut reference = Reference::default();
                 reference.content = link.as_str().to_string();
 
-                reference.create(&config.neo4j_graph).await?;
+                reference.id = config.store.create_reference(&reference).await?;
 
-                debate_response
-                    .clone()
-                    .add_has_referecne(&config.neo4j_graph, reference)
-                    .await;
+                config
+                    .store
+                    .relate(Relationship::HasReference {
+                        response_id: debate_response.id.clone(),
+                        reference_id: reference.id,
+                    })
+                    .await?;
             }
 
-            generate_engagement(&config, debate_response.clone(), self.depth, users.clone())
-                .await?;
+            let mut round = match generate_engagement(
+                &config,
+                debate_response.clone(),
+                self.depth,
+                users.clone(),
+                rng,
+            )
+            .await
+            {
+                Ok(replies) => replies,
 
-            debate_response
-                .add_user_responded(&config.neo4j_graph, rand_user.base_user.to_owned())
+                Err(e) => {
+                    println!("failed to generate engagement: {}", e);
+
+                    Vec::new()
+                }
+            };
+
+            config
+                .store
+                .relate(Relationship::Responded {
+                    user_id: rand_user.base_user.id.clone(),
+                    response_id: debate_response.id.clone(),
+                })
                 .await?;
 
-            debate_response
-                .add_debate_response_relationship(&config.neo4j_graph, debate.clone())
+            config
+                .store
+                .relate(Relationship::DebateResponse {
+                    response_id: debate_response.id.clone(),
+                    debate_id: debate.id.clone(),
+                })
                 .await?;
+
+            // Everything the round wrote goes to the store together, the engagement is scored from it
+            config.store.flush().await?;
+
+            round.insert(0, debate_response);
+            score_round(&config, &mut round).await?;
         }
 
+        config.store.flush().await?;
+
         Ok(())
     }
 }
 
+/// Writes a thread of up to `depth` replies below `response` and returns them, scored on their content alone
 pub async fn generate_engagement(
     config: &Config,
     response: Response,
     mut depth: u64,
     users: Vec<EnneagramUser>,
-) -> Result<(), PulpError> {
-    let key = config.open_ai_key.clone();
-
-    let rint = (random::<f32>() * users.len() as f32).floor() as usize;
-    let rand_user = &users[rint];
-
-    let mut reply_prompt = ENNEAGRAM_REPLY_CONTENT_PROMPT.to_string();
-    reply_prompt = reply_prompt.replace("THIS_CONTENT", &response.content.clone());
-    reply_prompt = reply_prompt.replace(
-        "VALID_VOTE_TENDENCY",
-        &rand_user.tendencies.valid_vote_tendency.to_string(),
-    );
-    reply_prompt = reply_prompt.replace(
-        "INVALID_VOTE_TENDENCY",
-        &rand_user.tendencies.invalid_vote_tendency.to_string(),
-    );
-    reply_prompt = reply_prompt.replace(
-        "ABSTAIN_VOTE_TENDENCY",
-        &rand_user.tendencies.invalid_vote_tendency.to_string(),
-    );
-    reply_prompt = reply_prompt.replace(
-        "REPORT_TENDENCY",
-        &rand_user.tendencies.invalid_vote_tendency.to_string(),
-    );
-    reply_prompt = reply_prompt.replace(
-        "HIDE_TENDENCY",
-        &rand_user.tendencies.invalid_vote_tendency.to_string(),
-    );
-
-    let reply_chat_res = ChatRequestBuilder::new()
-        .messages(reply_prompt)
-        .temperature(0.7)
-        .max_tokens(850)
-        .top_p(1.0)
-        .presence_penalty(0.0)
-        .frequency_penalty(0.0)
-        .build()
-        .send(key.clone(), Client::new())
-        .await;
-
-    let content = from_str::<ContentReponse>(&reply_chat_res.choices[0].message.content.clone());
-
-    let cont_res = match content {
-        Ok(res) => Some(res),
-
-        Err(e) => {
-            println!(
-                "failed to unmarshal content: {:?}: {}",
-                e, reply_chat_res.choices[0].message.content
-            );
-
-            None
-        }
-    }
-    .unwrap();
+    rng: &mut StdRng,
+) -> Result<Vec<Response>, PulpError> {
+    let rand_user = pick(&users, rng, NO_USERS)?;
+
+    let prompt = reply_content_prompt(&response.content, &rand_user.tendencies);
+    let cont_res = prompt.send(config).await?;
 
     let mut response_reply = Response::default();
+    response_reply.stamp_prompt(&prompt, &config.prompts);
     response_reply.content = cont_res.content.clone();
     response_reply.confidence = cont_res.confidence;
 
     response_reply.score = response_reply
-        .calculate_content_attribute_score(key.clone())
-        .await?
-        + response_reply.calculate_engagement_score();
+        .calculate_content_attribute_score(config)
+        .await?;
 
     depth -= 1;
 
-    let response_reply_id = response_reply.create(&config.neo4j_graph).await?;
+    response_reply.created_at = config.clock.tick();
+    let response_reply_id = config.store.create_response(&response_reply).await?;
     response_reply.id = response_reply_id;
 
     let re = Regex::new(r"/(?:(?:https?|ftp|file):\\|www\\.|ftp\\.)(?:\\([-A-Z0-9+&@#\\%=~_|$?!:,.]*\\)|[-A-Z0-9+&@#\\%=~_|$?!:,.])*(?:\\([-A-Z0-9+&@#\\%=~_|$?!:,.]*\\)|[A-Z0-9+&@#\\%=~_|$])/igm").unwrap();
@@ -330,92 +255,64 @@ pub async fn generate_engagement(
         let mut reference = Reference::default();
         reference.content = link.as_str().to_string();
 
-        reference.create(&config.neo4j_graph).await?;
+        reference.id = config.store.create_reference(&reference).await?;
 
-        response_reply
-            .clone()
-            .add_has_referecne(&config.neo4j_graph, reference)
+        config
+            .store
+            .relate(Relationship::HasReference {
+                response_id: response_reply.id.clone(),
+                reference_id: reference.id,
+            })
             .await?;
     }
 
-    response_reply
-        .add_user_responded(&config.neo4j_graph, rand_user.base_user.to_owned())
+    config
+        .store
+        .relate(Relationship::Responded {
+            user_id: rand_user.base_user.id.clone(),
+            response_id: response_reply.id.clone(),
+        })
         .await?;
 
-    response
-        .add_reply_relationship(&config.neo4j_graph, response_reply.clone())
+    config
+        .store
+        .relate(Relationship::Replied {
+            response_id: response.id.clone(),
+            reply_id: response_reply.id.clone(),
+        })
         .await?;
 
     // get reference
 
+    let mut replies = vec![response_reply.clone()];
     let mut res: Response = response_reply;
     while depth > 0 {
-        let rint = (random::<f32>() * users.len() as f32).floor() as usize;
-        let rand_user = &users[rint];
-
-        let mut reply_prompt = ENNEAGRAM_REPLY_CONTENT_PROMPT.to_string();
-        reply_prompt = reply_prompt.replace("THIS_CONTENT", &res.content.clone());
-        reply_prompt = reply_prompt.replace(
-            "VALID_VOTE_TENDENCY",
-            &rand_user.tendencies.valid_vote_tendency.to_string(),
-        );
-        reply_prompt = reply_prompt.replace(
-            "INVALID_VOTE_TENDENCY",
-            &rand_user.tendencies.invalid_vote_tendency.to_string(),
-        );
-        reply_prompt = reply_prompt.replace(
-            "ABSTAIN_VOTE_TENDENCY",
-            &rand_user.tendencies.invalid_vote_tendency.to_string(),
-        );
-        reply_prompt = reply_prompt.replace(
-            "REPORT_TENDENCY",
-            &rand_user.tendencies.invalid_vote_tendency.to_string(),
-        );
-        reply_prompt = reply_prompt.replace(
-            "HIDE_TENDENCY",
-            &rand_user.tendencies.invalid_vote_tendency.to_string(),
-        );
-
-        let reply_chat_res = ChatRequestBuilder::new()
-            .messages(reply_prompt)
-            .temperature(0.7)
-            .max_tokens(850)
-            .top_p(1.0)
-            .presence_penalty(0.0)
-            .frequency_penalty(0.0)
-            .build()
-            .send(key.clone(), Client::new())
-            .await;
-
-        let content =
-            from_str::<ContentReponse>(&reply_chat_res.choices[0].message.content.clone());
+        let rand_user = pick(&users, rng, NO_USERS)?;
 
-        let cont_res = match content {
-            Ok(res) => Some(res),
+        let prompt = reply_content_prompt(&res.content, &rand_user.tendencies);
+        let cont_res = match prompt.send(config).await {
+            Ok(res) => res,
 
             Err(e) => {
-                println!(
-                    "failed to unmarshal content: {:?}: {}",
-                    e, reply_chat_res.choices[0].message.content
-                );
+                println!("failed to generate reply: {}", e);
 
-                None
+                break;
             }
-        }
-        .unwrap();
+        };
 
         let mut depth_response_reply = Response::default();
+        depth_response_reply.stamp_prompt(&prompt, &config.prompts);
         depth_response_reply.content = cont_res.content.clone();
         depth_response_reply.confidence = cont_res.confidence;
 
         depth_response_reply.score = depth_response_reply
-            .calculate_content_attribute_score(key.clone())
-            .await?
-            + depth_response_reply.calculate_engagement_score();
+            .calculate_content_attribute_score(config)
+            .await?;
 
         depth -= 1;
 
-        let depth_response_reply_id = depth_response_reply.create(&config.neo4j_graph).await?;
+        depth_response_reply.created_at = config.clock.tick();
+        let depth_response_reply_id = config.store.create_response(&depth_response_reply).await?;
         depth_response_reply.id = depth_response_reply_id;
 
         let re = Regex::new(r"/(?:(?:https?|ftp|file):\\|www\\.|ftp\\.)(?:\\([-A-Z0-9+&@#\\%=~_|$?!:,.]*\\)|[-A-Z0-9+&@#\\%=~_|$?!:,.])*(?:\\([-A-Z0-9+&@#\\%=~_|$?!:,.]*\\)|[A-Z0-9+&@#\\%=~_|$])/igm").unwrap();
@@ -423,27 +320,42 @@ pub async fn generate_engagement(
             let mut reference = Reference::default();
             reference.content = link.as_str().to_string();
 
-            reference.create(&config.neo4j_graph).await;
+            if let Ok(reference_id) = config.store.create_reference(&reference).await {
+                reference.id = reference_id;
+            }
 
-            depth_response_reply
-                .clone()
-                .add_has_referecne(&config.neo4j_graph, reference)
+            let _ = config
+                .store
+                .relate(Relationship::HasReference {
+                    response_id: depth_response_reply.id.clone(),
+                    reference_id: reference.id,
+                })
                 .await;
         }
 
-        depth_response_reply
-            .add_user_responded(&config.neo4j_graph, rand_user.base_user.to_owned())
+        let _ = config
+            .store
+            .relate(Relationship::Responded {
+                user_id: rand_user.base_user.id.clone(),
+                response_id: depth_response_reply.id.clone(),
+            })
             .await;
 
         println!("Response: {:?}", depth_response_reply);
 
-        res.add_reply_relationship(&config.neo4j_graph, depth_response_reply.clone())
+        let _ = config
+            .store
+            .relate(Relationship::Replied {
+                response_id: res.id.clone(),
+                reply_id: depth_response_reply.id.clone(),
+            })
             .await;
 
+        replies.push(depth_response_reply.clone());
         res = depth_response_reply;
     }
 
-    Ok(())
+    Ok(replies)
 }
 
 pub struct EnneagramSimulationBuilder {
@@ -1,6 +1,9 @@
+use crate::enneagram::chat_responses::{ActionTendencies, ContentReponse, TendencyRespose};
+use pulpcalc_common::prompt::{Prompt, SamplingParams};
+
 pub const  ENNEAGRAM_TENDENCY_PROMPT: &'static str = "Strictly for research purposes with no ill-intent regarding stereotypes and assumptions in mind;
 given a set of five actions that a user can take on a social media comment that pertains to
-THIS_TOPIC: valid vote, invalid vote, abstain vote, report, hide, and a set of enneagram types,
+{{topic}}: valid vote, invalid vote, abstain vote, report, hide, and a set of enneagram types,
 what is the most likely action that each enneagram type will take? Return your answer as a JSON object.
 Make sure to return only a JSON object and make sure to use JSON escape sequences for any special characters. Do not return anything besides the JSON object!
 Use this schema for your answer:
@@ -72,12 +75,12 @@ Use this schema for your answer:
 
 pub const ENNEAGRAM_RESPONSE_CONTENT_PROMPT: &'static str = "Strictly for research purposes with no ill-intent regarding stereotypes and assumptions in mind;
 given these user tendencies:
-Tendency to cast a valid vote: VALID_VOTE_TENDENCY
-Tendency to cast a invalid vote: INVALID_VOTE_TENDENCY
-Tendency to cast a abstain vote: ABSTAIN_VOTE_TENDENCY
-Tendency to cast report a post: REPORT_TENDENCY
-Tendency to cast a hide a post: HIDE_TENDENCY
-on a social media comment that pertains to THIS_TOPIC, generate a response to the topic most like the user.  Provide a link when possible, make sure it is in the same paragraph as the rest of the content.
+Tendency to cast a valid vote: {{valid_vote_tendency}}
+Tendency to cast a invalid vote: {{invalid_vote_tendency}}
+Tendency to cast a abstain vote: {{abstain_vote_tendency}}
+Tendency to cast report a post: {{report_tendency}}
+Tendency to cast a hide a post: {{hide_tendency}}
+on a social media comment that pertains to {{topic}}, generate a response to the topic most like the user.  Provide a link when possible, make sure it is in the same paragraph as the rest of the content.
 Make sure to return only a JSON object, make sure to use JSON escape sequences for any special characters, and make sure there is no trailing comma. Do not return anything besides the JSON object! Use the below schema for your answer.
 {
 	\"content\": \"This is a response to the topic\",
@@ -86,18 +89,63 @@ Make sure to return only a JSON object, make sure to use JSON escape sequences f
 
 pub const ENNEAGRAM_REPLY_CONTENT_PROMPT: &'static str = "Strictly for research purposes with no ill-intent regarding stereotypes and assumptions in mind;
 given these user tendencies:
-Tendency to cast a valid vote: VALID_VOTE_TENDENCY
-Tendency to cast a invalid vote: INVALID_VOTE_TENDENCY
-Tendency to cast a abstain vote: ABSTAIN_VOTE_TENDENCY
-Tendency to cast report a post: REPORT_TENDENCY
-Tendency to cast a hide a post: HIDE_TENDENCY
-on a social media comment with THIS_CONTENT, generate a response to the comment most like the user. Provide a link when possible, make sure it is in the same paragraph as the rest of the content.
+Tendency to cast a valid vote: {{valid_vote_tendency}}
+Tendency to cast a invalid vote: {{invalid_vote_tendency}}
+Tendency to cast a abstain vote: {{abstain_vote_tendency}}
+Tendency to cast report a post: {{report_tendency}}
+Tendency to cast a hide a post: {{hide_tendency}}
+on a social media comment with {{content}}, generate a response to the comment most like the user. Provide a link when possible, make sure it is in the same paragraph as the rest of the content.
 Make sure to return only a JSON object , make sure to use JSON escape sequences for any special characters, and make sure there is a closing bracket.  Do not return anything besides the JSON object! Use the below schema for your answer.
 {
 	\"content\": \"This is a response to the topic\",
 	\"confidence\": 0.0 #This value must be between 0.0 and .999
 }";
 
+pub fn tendency_prompt(topic: &str) -> Prompt<TendencyRespose> {
+    Prompt::new("enneagram_tendency", ENNEAGRAM_TENDENCY_PROMPT)
+        .sampling(SamplingParams::default().max_tokens(850))
+        .var("topic", topic)
+}
+
+pub fn response_content_prompt(
+    topic: &str,
+    tendencies: &ActionTendencies,
+) -> Prompt<ContentReponse> {
+    with_tendencies(
+        Prompt::new(
+            "enneagram_response_content",
+            ENNEAGRAM_RESPONSE_CONTENT_PROMPT,
+        ),
+        tendencies,
+    )
+    .var("topic", topic)
+}
+
+pub fn reply_content_prompt(
+    content: &str,
+    tendencies: &ActionTendencies,
+) -> Prompt<ContentReponse> {
+    with_tendencies(
+        Prompt::new("enneagram_reply_content", ENNEAGRAM_REPLY_CONTENT_PROMPT),
+        tendencies,
+    )
+    .var("content", content)
+}
+
+/// Binds the action tendencies of the user answering the prompt
+fn with_tendencies(
+    prompt: Prompt<ContentReponse>,
+    tendencies: &ActionTendencies,
+) -> Prompt<ContentReponse> {
+    prompt
+        .sampling(SamplingParams::default().max_tokens(850))
+        .var("valid_vote_tendency", tendencies.valid_vote_tendency)
+        .var("invalid_vote_tendency", tendencies.invalid_vote_tendency)
+        .var("abstain_vote_tendency", tendencies.abstain_vote_tendency)
+        .var("report_tendency", tendencies.report_tendency)
+        .var("hide_tendency", tendencies.hide_tendency)
+}
+
 pub enum EnneagramPrompts {
     ContentPrompt,
     TendencyPrompy,
@@ -124,3 +172,23 @@ impl Into<&str> for EnneagramPrompts {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pulpcalc_common::{registry::check_template, template::Template};
+
+    #[test]
+    fn prompts_match_the_builtin_placeholders() {
+        for (name, template) in [
+            ("enneagram_tendency", ENNEAGRAM_TENDENCY_PROMPT),
+            (
+                "enneagram_response_content",
+                ENNEAGRAM_RESPONSE_CONTENT_PROMPT,
+            ),
+            ("enneagram_reply_content", ENNEAGRAM_REPLY_CONTENT_PROMPT),
+        ] {
+            check_template(name, &Template::parse(template).unwrap()).unwrap();
+        }
+    }
+}
@@ -6,3 +6,125 @@ mod util;
 pub use util::new_business_from_file;
 pub use util::new_enneagram_from_file;
 pub use util::new_personas_from_file;
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        business::prompts::*,
+        enneagram::{chat_responses::*, prompts::*},
+        personas::{
+            models::PersonasUser,
+            prompts::{learned::*, response::*, sentiment::*, vote::*},
+        },
+    };
+    use pulpcalc_common::{models::gpt_scoring::*, prompt::Prompt, template::Template};
+    use pulpcalc_external::{
+        chatgpt::{ChatMessage, ChatRequest},
+        mock::{MockProvider, PromptFamily},
+        provider::ChatProvider,
+    };
+    use schemars::JsonSchema;
+    use serde::de::DeserializeOwned;
+
+    const SEED: u64 = 42;
+    const TOPIC: &str = "Should cities ban cars downtown?";
+    const CONTENT: &str = "Banning cars frees streets for people, see https://example.com/study";
+
+    /// The mock reply to `request`, checking it is recognized as `family` and answered the same every time
+    async fn reply(request: &ChatRequest, family: PromptFamily) -> String {
+        let prompt = &request.messages.last().unwrap().content;
+        assert_eq!(PromptFamily::detect(prompt), Some(family), "{}", prompt);
+
+        let mock = MockProvider::new(SEED);
+        let first = mock.send(request).await.unwrap();
+        let again = MockProvider::new(SEED).send(request).await.unwrap();
+        assert_eq!(first.content(), again.content(), "{:?}", family);
+
+        first.content().to_string()
+    }
+
+    /// Sends the rendered built in `prompt` to the mock and parses the reply strictly, without the JSON
+    /// extraction fallback
+    async fn parse<R: DeserializeOwned + JsonSchema>(prompt: Prompt<R>, family: PromptFamily) -> R {
+        let content = reply(&prompt.request().unwrap(), family).await;
+
+        serde_json::from_str(&content)
+            .unwrap_or_else(|e| panic!("{:?} reply {}: {}", family, content, e))
+    }
+
+    #[tokio::test]
+    async fn the_mock_answers_every_builtin_prompt_with_its_reply_type() {
+        let user = PersonasUser::default();
+        let references = vec!["https://example.com/study".to_string()];
+        let tendencies = ActionTendencies::default();
+
+        let relevance = parse(relevance_prompt(TOPIC, CONTENT), PromptFamily::Relevance).await;
+        assert!((0.0..1.0).contains(&relevance.relevance));
+        let soundness = parse(soundness_prompt(TOPIC, CONTENT), PromptFamily::Soundness).await;
+        assert!((0.0..1.0).contains(&soundness.soundness));
+        let grammar = parse(grammar_prompt(CONTENT), PromptFamily::Grammar).await;
+        assert!((0.0..1.0).contains(&grammar.grammar));
+        let mastery = parse(
+            mastery_vocab_prompt(TOPIC, CONTENT),
+            PromptFamily::MasteryVocab,
+        )
+        .await;
+        assert_eq!(
+            mastery.mastery_words.unwrap_or_default().len() as i64,
+            mastery.mastery_vocab
+        );
+
+        for prompt in [
+            persona_content_prompt(TOPIC, &user),
+            persona_content_prompt_with_reference(TOPIC, &user),
+            persona_content_prompt_with_supported_references(TOPIC, &user, &references),
+            persona_content_prompt_with_unsupported_references(TOPIC, &user, &references),
+        ] {
+            let content = parse(prompt, PromptFamily::PersonaContent).await;
+            assert!(!content.content.is_empty());
+        }
+
+        let vote = parse(vote_prompt(CONTENT, &user), PromptFamily::Vote).await;
+        assert!(["valid", "invalid", "abstain"].contains(&vote.vote.as_str()));
+
+        let learned = parse(learned_prompt(TOPIC, CONTENT, &user), PromptFamily::Learned).await;
+        assert!((18..80).contains(&learned.age));
+        assert!(!learned.reason.is_empty());
+
+        parse(tendency_prompt(TOPIC), PromptFamily::EnneagramTendency).await;
+        for prompt in [
+            response_content_prompt(TOPIC, &tendencies),
+            reply_content_prompt(CONTENT, &tendencies),
+        ] {
+            let content = parse(prompt, PromptFamily::EnneagramContent).await;
+            assert!(!content.content.is_empty());
+        }
+
+        let blog = parse(generate_blog_prompt(TOPIC), PromptFamily::GenerateBlog).await;
+        assert!(!blog.title.is_empty() && !blog.body.is_empty());
+        parse(nahco_prompt(TOPIC), PromptFamily::Nahco).await;
+        let content = parse(
+            persona_content_prompt_with_nahco_reference(TOPIC),
+            PromptFamily::PersonaContent,
+        )
+        .await;
+        assert!(!content.content.is_empty());
+
+        // The sentiment prompt is not sent through a `Prompt`, its reply type has no schema
+        let template = Template::parse(SENTIMENT_CONTENT_PROMPT).unwrap();
+        let vars = template
+            .variables()
+            .into_iter()
+            .map(|name| (name.to_string(), "unknown".to_string()))
+            .collect::<Vec<_>>();
+        let request = ChatRequest {
+            messages: vec![ChatMessage::user(template.render(&vars).unwrap())],
+            model: "gpt-3.5-turbo".to_string(),
+            ..Default::default()
+        };
+        let content = reply(&request, PromptFamily::Sentiment).await;
+        let sentiment: SentimentResponse = serde_json::from_str(&content).unwrap();
+        assert!(["positive", "negative", "neutral"].contains(&sentiment.sentiment.as_str()));
+        assert!(!sentiment.reason.is_empty());
+    }
+}
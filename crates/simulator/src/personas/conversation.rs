@@ -0,0 +1,183 @@
+use pulpcalc_common::{prompt::Prompt, registry::PromptRegistry};
+use pulpcalc_external::chatgpt::ChatMessage;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use std::{collections::HashMap, sync::Mutex};
+
+/// What each persona has said so far in a debate, sent back with its next prompt so
+/// the replies it writes further down a thread stay consistent with what it argued
+#[derive(Debug, Default)]
+pub struct Conversations {
+    turns: Mutex<HashMap<String, Vec<ChatMessage>>>,
+
+    /// Number of earlier prompt and reply pairs kept per persona
+    max_turns: usize,
+}
+
+impl Conversations {
+    pub fn new(max_turns: usize) -> Self {
+        Self {
+            turns: Mutex::new(HashMap::new()),
+            max_turns,
+        }
+    }
+
+    /// The most recent turns of the persona with `user_id`, oldest first
+    pub fn history(&self, user_id: &str) -> Vec<ChatMessage> {
+        match self.turns.lock().unwrap().get(user_id) {
+            Some(turns) => turns.clone(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Records the prompt the persona with `user_id` answered, as rendered from the registry, and its reply
+    pub fn record<R: DeserializeOwned + JsonSchema>(
+        &self,
+        user_id: &str,
+        prompt: &Prompt<R>,
+        registry: &PromptRegistry,
+        reply: &str,
+    ) {
+        if self.max_turns == 0 {
+            return;
+        }
+
+        let mut conversations = self.turns.lock().unwrap();
+        let turns = conversations.entry(user_id.to_string()).or_default();
+
+        self.push(turns, prompt, registry, reply);
+    }
+
+    /// `history` followed by a turn not recorded yet, trimmed the way recording it would
+    pub fn continued<R: DeserializeOwned + JsonSchema>(
+        &self,
+        mut history: Vec<ChatMessage>,
+        prompt: &Prompt<R>,
+        registry: &PromptRegistry,
+        reply: &str,
+    ) -> Vec<ChatMessage> {
+        self.push(&mut history, prompt, registry, reply);
+
+        history
+    }
+
+    fn push<R: DeserializeOwned + JsonSchema>(
+        &self,
+        turns: &mut Vec<ChatMessage>,
+        prompt: &Prompt<R>,
+        registry: &PromptRegistry,
+        reply: &str,
+    ) {
+        if self.max_turns == 0 {
+            return;
+        }
+
+        let content = match prompt.clone().resolve(registry).render() {
+            Ok(content) => content,
+            Err(_) => return,
+        };
+
+        turns.push(ChatMessage::user(content));
+        turns.push(ChatMessage::assistant(reply.to_string()));
+
+        let excess = turns.len().saturating_sub(self.max_turns * 2);
+        turns.drain(..excess);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::personas::{
+        models::PersonasUser,
+        prompts::response::{persona_content_prompt, PersonaContentPrompt},
+    };
+
+    fn prompt(topic: &str) -> PersonaContentPrompt {
+        persona_content_prompt(topic, &PersonasUser::default())
+    }
+
+    /// The replies of each turn of `history`, checking it alternates prompts and replies
+    fn replies(history: &[ChatMessage]) -> Vec<&str> {
+        history
+            .chunks(2)
+            .map(|turn| {
+                assert_eq!(turn[0].role, "user");
+                assert!(turn[0].content.contains("Topic"));
+                assert_eq!(turn[1].role, "assistant");
+
+                turn[1].content.as_str()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn only_the_latest_turns_are_kept() {
+        let conversations = Conversations::new(2);
+        let registry = PromptRegistry::default();
+
+        for reply in ["first", "second", "third"] {
+            conversations.record("persona", &prompt("Topic"), &registry, reply);
+        }
+
+        let history = conversations.history("persona");
+        assert_eq!(replies(&history), ["second", "third"]);
+    }
+
+    #[test]
+    fn no_turns_are_kept_without_history() {
+        let conversations = Conversations::new(0);
+        let registry = PromptRegistry::default();
+
+        conversations.record("persona", &prompt("Topic"), &registry, "first");
+
+        assert!(conversations.history("persona").is_empty());
+        let continued = conversations.continued(Vec::new(), &prompt("Topic"), &registry, "first");
+        assert!(continued.is_empty());
+    }
+
+    #[test]
+    fn each_persona_has_its_own_history() {
+        let conversations = Conversations::new(3);
+        let registry = PromptRegistry::default();
+
+        conversations.record("a", &prompt("Topic"), &registry, "a first");
+        conversations.record("b", &prompt("Topic"), &registry, "b first");
+        conversations.record("a", &prompt("Topic"), &registry, "a second");
+
+        assert_eq!(
+            replies(&conversations.history("a")),
+            ["a first", "a second"]
+        );
+        assert_eq!(replies(&conversations.history("b")), ["b first"]);
+        assert!(conversations.history("c").is_empty());
+    }
+
+    #[test]
+    fn a_continued_history_is_trimmed_like_a_recorded_one() {
+        let conversations = Conversations::new(2);
+        let registry = PromptRegistry::default();
+
+        conversations.record("persona", &prompt("Topic"), &registry, "first");
+        conversations.record("persona", &prompt("Topic"), &registry, "second");
+
+        let continued = conversations.continued(
+            conversations.history("persona"),
+            &prompt("Topic"),
+            &registry,
+            "third",
+        );
+        assert_eq!(replies(&continued), ["second", "third"]);
+
+        // Nothing is recorded until the turn is
+        assert_eq!(
+            replies(&conversations.history("persona")),
+            ["first", "second"]
+        );
+        conversations.record("persona", &prompt("Topic"), &registry, "third");
+        assert_eq!(
+            format!("{:?}", conversations.history("persona")),
+            format!("{:?}", continued)
+        );
+    }
+}
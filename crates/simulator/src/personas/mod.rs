@@ -1,25 +1,44 @@
 use crate::personas::{
-    models::{Learned, PersonasUser},
+    conversation::Conversations,
+    models::{Gender, Learned, PersonasUser},
     prompts::{
-        learned::{LearnedPrompt, LearnedResponse},
-        response::{ContentResponse, PersonaContentPrompt, PersonaContentPromptWithReference},
-        vote::{VoteContentPrompt, VoteResponse},
+        learned::{learned_prompt, LearnedResponse},
+        response::{
+            persona_content_prompt, persona_system_message, ContentResponse, PersonaContentPrompt,
+        },
+        vote::{vote_prompt, VoteResponse},
     },
 };
+use crate::util::{pick, score_round};
 use eyre::Result;
 use futures::future::join_all;
 use pulpcalc_common::{
     config::Config,
     errors::PulpError,
-    llm_config::LLMRequest,
-    models::{vote::VoteType, Debate, Reference, Response},
+    models::{
+        vote::{Vote, VoteType},
+        Debate, Reference, Response,
+    },
+    store::Relationship,
 };
+use pulpcalc_external::chatgpt::ChatMessage;
 use rand::prelude::*;
 use serde::Deserialize;
-use tokio::task::{self, JoinHandle};
+use std::{collections::HashMap, sync::Arc};
+use tokio::{
+    sync::oneshot,
+    task::{self, JoinHandle},
+};
 
+pub mod conversation;
 pub mod models;
-mod prompts;
+pub(crate) mod prompts;
+
+/// Prompt and reply pairs a persona remembers when `max_history` is not set
+pub const DEFAULT_MAX_HISTORY: usize = 3;
+
+/// Why a simulation over a store without persona users fails
+const NO_USERS: &str = "no persona users; run with --init";
 
 #[derive(Debug, Clone, Default)]
 pub struct PersonasSimulation {
@@ -53,52 +72,55 @@ impl PersonasSimulation {
 
     pub async fn run_simulation(
         &self,
-        config: Config,
+        mut config: Config,
         personas_config: PersonasSimulationConfig,
+        rng: &mut StdRng,
     ) -> Result<(), PulpError> {
-        let users = PersonasUser::get_all_users(&config.neo4j_graph).await?;
-        let rint = (random::<f32>() * users.clone().len() as f32).floor() as usize;
-        let rand_user = &users[rint];
+        let mut users = config.store.get_all_personas_users().await?;
+        // Stores return personas in no particular order, ordering them by who they are lets the same
+        // seed pick personas that are sent the same prompts
+        users.sort_by_cached_key(|user| (persona_system_message(user), user.base_user.id.clone()));
+
+        let rand_user = pick(&users, rng, NO_USERS)?;
 
         for mut debate in self.debates.clone() {
-            let debate_id = debate.create(&config.neo4j_graph).await?;
+            let debate_id = config.store.create_debate(&debate).await?;
             debate.id = debate_id;
+            config.debate_id = Some(debate.id.clone());
+
+            let conversations = Arc::new(Conversations::new(
+                personas_config.max_history.unwrap_or(DEFAULT_MAX_HISTORY),
+            ));
 
-            debate
-                .update_commenters(
-                    &config.neo4j_graph,
+            config
+                .store
+                .update_debate_commenters(
+                    &debate.id,
                     personas_config.max_commenters.unwrap().clone() as i64,
                 )
                 .await?;
 
             for _ in 0..personas_config.max_commenters.unwrap().clone() {
+                config.llm_usage.check_budget(&config.llm_budget)?;
+
                 let mut response = Response::default();
 
-                let mut prompt = PersonaContentPrompt::default();
-                prompt.replace_attributes(vec![
-                    ("THIS_CONTENT".to_string(), response.content.clone()),
-                    (
-                        "POLITICAL_ORIENTATION".to_string(),
-                        rand_user.political_orientation.to_string(),
-                    ),
-                    (
-                        "ENNEAGRAM_TYPE".to_string(),
-                        rand_user.personality.personality_base.enneagram.to_string(),
-                    ),
-                    ("GENDER".to_string(), rand_user.gender.to_string()),
-                    ("AGE".to_string(), rand_user.age.to_string()),
-                    (
-                        "CORE_FEAR".to_string(),
-                        rand_user.personality.personality_base.core_fear.clone(),
-                    ),
-                    (
-                        "CORE_DESIRE".to_string(),
-                        rand_user.personality.personality_base.core_desire.clone(),
-                    ),
-                ]);
-
-                let content_res = match prompt.send(config.open_ai_key.clone()).await {
-                    Ok(content) => content,
+                let prompt = persona_content_prompt(&response.content, &rand_user)
+                    .system(persona_system_message(rand_user))
+                    .history(conversations.history(&rand_user.base_user.id));
+                response.stamp_prompt(&prompt, &config.prompts);
+
+                let content_res = match prompt.send(&config).await {
+                    Ok(content) => {
+                        conversations.record(
+                            &rand_user.base_user.id,
+                            &prompt,
+                            &config.prompts,
+                            &content.content,
+                        );
+
+                        content
+                    }
 
                     Err(e) => {
                         println!("{:?}", e);
@@ -112,17 +134,18 @@ impl PersonasSimulation {
                 response.pathos = content_res.pathos;
                 response.logos = content_res.logos;
 
-                let id = response.create(&config.neo4j_graph).await?;
+                response.created_at = config.clock.tick();
+                let id = config.store.create_response(&response).await?;
                 response.id = id;
 
-                response
-                    .update_ethos(&config.neo4j_graph, response.ethos)
-                    .await?;
-                response
-                    .update_logos(&config.neo4j_graph, response.logos)
-                    .await?;
-                response
-                    .update_pathos(&config.neo4j_graph, response.pathos)
+                config
+                    .store
+                    .update_response_appeals(
+                        &response.id,
+                        response.ethos,
+                        response.pathos,
+                        response.logos,
+                    )
                     .await?;
 
                 Self::generate_votes(
@@ -131,98 +154,198 @@ impl PersonasSimulation {
                     debate.clone(),
                     personas_config.max_commenters.unwrap(),
                     users.clone(),
+                    rng,
                 )
                 .await?;
 
-                Self::generate_engagement(
+                let replies = Self::generate_engagement(
                     config.clone(),
                     personas_config.clone(),
                     response.clone(),
                     users.clone(),
+                    conversations.clone(),
                     0,
                     3,
                     &mut debate,
+                    rng,
                 )
                 .await?;
 
-                response.score = response
-                    .calculate_content_attribute_score(config.open_ai_key.clone())
-                    .await?
-                    + response.calculate_engagement_score();
+                response.score = response.calculate_content_attribute_score(&config).await?;
 
-                response
-                    .update_score(&config.neo4j_graph, response.score)
+                config
+                    .store
+                    .update_response_attributes(&response.id, &response.attributes)
                     .await?;
 
-                response
-                    .add_debate_response_relationship(&config.neo4j_graph, debate.clone())
+                config
+                    .store
+                    .relate(Relationship::DebateResponse {
+                        response_id: response.id.clone(),
+                        debate_id: debate.id.clone(),
+                    })
                     .await?;
 
-                rand_user
-                    .add_user_responded(&config.neo4j_graph, response.clone())
+                config
+                    .store
+                    .relate(Relationship::PersonaResponded {
+                        persona_id: rand_user.base_user.id.clone(),
+                        response_id: response.id.clone(),
+                    })
                     .await?;
+
+                // Everything the round wrote goes to the store together, the engagement is scored from it
+                config.store.flush().await?;
+
+                let mut round = vec![response];
+                round.extend(replies);
+                score_round(&config, &mut round).await?;
             }
 
             println!("Debate: {:?}", debate);
 
-            debate
-                .update_responses(&config.neo4j_graph, debate.responses)
+            config
+                .store
+                .update_debate_responses(&debate.id, debate.responses)
                 .await?;
+
+            config.store.flush().await?;
         }
 
+        println!(
+            "LLM retries: {}, throttled waits: {}",
+            config.llm_stats.retries(),
+            config.llm_stats.throttled_waits()
+        );
+        println!(
+            "LLM replies extracted: {}, repair prompts: {}, repaired: {}, unparsable: {}",
+            config.llm_reply_stats.extracted(),
+            config.llm_reply_stats.repairs(),
+            config.llm_reply_stats.repaired(),
+            config.llm_reply_stats.failed()
+        );
+
         Ok(())
     }
 
-    pub async fn init_users() -> Vec<PersonasUser> {
-        let mut users = vec![];
-        for _ in 0..100 {
-            users.push(PersonasUser::default());
+    /// Creates `count` persona users within the restrictions of `personas_config`, drawing their ages and
+    /// enneagram types from `rng`
+    pub async fn init_users(
+        config: &Config,
+        personas_config: &PersonasSimulationConfig,
+        count: i64,
+        rng: &mut StdRng,
+    ) -> Result<Vec<PersonasUser>, PulpError> {
+        let restrictions = personas_config.user_restrictions.clone().unwrap();
+
+        let mut users: Vec<PersonasUser> = Vec::new();
+        for _ in 0..count {
+            let mut user = PersonasUser::default();
+
+            if personas_config.adults_only {
+                user.age = 18;
+            } else {
+                user.age = (rng.gen::<f32>() * restrictions.max_age as f32).floor() as i64;
+            }
+
+            user.vote_valid_influence = restrictions.vote_valid_reason.clone();
+            user.vote_invalid_influence = restrictions.vote_invalid_reason.clone();
+            user.vote_abstain_influence = restrictions.vote_abstain_reason.clone();
+
+            user.personality.personality_base.core_desire = "money".to_string();
+            user.personality.personality_base.core_fear = "death".to_string();
+            user.personality.personality_base.enneagram = (rng.gen::<f32>() * 9.0).floor() as i64;
+
+            users.push(user);
+        }
+
+        let mut created: Vec<PersonasUser> = Vec::new();
+
+        let mut j = 0.0;
+        while j < restrictions.male_distribution * (personas_config.simulation_size - 1) as f64 {
+            let mut user = users.pop().unwrap();
+            user.gender = Gender::from("male");
+
+            user.base_user.id = config.store.create_personas_user(&user).await?;
+            created.push(user);
+
+            j += 1.0;
         }
 
-        users
+        let mut z = 0.0;
+        while z
+            < (restrictions.female_distribution * (personas_config.simulation_size - 1) as f64)
+                .floor()
+        {
+            let mut user = users.pop().unwrap();
+            user.gender = Gender::from("female");
+
+            user.base_user.id = config.store.create_personas_user(&user).await?;
+            created.push(user);
+
+            z += 1.0;
+        }
+
+        let mut k = 0.0;
+        while k
+            < (restrictions.other_distribution * (personas_config.simulation_size - 1) as f64)
+                .floor()
+        {
+            let mut user = users.pop().unwrap();
+            user.gender = Gender::from("other");
+
+            user.base_user.id = config.store.create_personas_user(&user).await?;
+            created.push(user);
+
+            k += 1.0;
+        }
+
+        let mut l = 0.0;
+        while l < restrictions.not_saying_gender_distribution
+            * (personas_config.simulation_size - 1) as f64
+        {
+            let mut user = users.pop().unwrap();
+            user.gender = Gender::from("rather not say");
+
+            user.base_user.id = config.store.create_personas_user(&user).await?;
+            created.push(user);
+
+            l += 1.0;
+        }
+
+        Ok(created)
     }
 
     /// Generates child responses to a given piece of content, uses user attributes to generate as "real"
-    /// a response as possible
+    /// a response as possible. Returns the replies it wrote, scored on their content alone
     pub async fn generate_engagement(
         config: Config,
         pcfg: PersonasSimulationConfig,
         response: Response,
         users: Vec<PersonasUser>,
+        conversations: Arc<Conversations>,
         mut width: i64,
         mut depth: i64,
         debate: &mut Debate,
-    ) -> Result<(), PulpError> {
-        let key = config.open_ai_key.clone();
-
-        let rint = (random::<f32>() * users.len() as f32).floor() as usize;
-        let rand_user = &users[rint];
-
-        let mut prompt = PersonaContentPrompt::default();
-        prompt.replace_attributes(vec![
-            ("THIS_CONTENT".to_string(), response.content.clone()),
-            (
-                "POLITICAL_ORIENTATION".to_string(),
-                rand_user.political_orientation.to_string(),
-            ),
-            (
-                "ENNEAGRAM_TYPE".to_string(),
-                rand_user.personality.personality_base.enneagram.to_string(),
-            ),
-            ("GENDER".to_string(), rand_user.gender.to_string()),
-            ("AGE".to_string(), rand_user.age.to_string()),
-            (
-                "CORE_FEAR".to_string(),
-                rand_user.personality.personality_base.core_fear.clone(),
-            ),
-            (
-                "CORE_DESIRE".to_string(),
-                rand_user.personality.personality_base.core_desire.clone(),
-            ),
-        ]);
-
-        let response_res = match prompt.send(key.clone()).await {
-            Ok(content) => content,
+        rng: &mut StdRng,
+    ) -> Result<Vec<Response>, PulpError> {
+        let rand_user = pick(&users, rng, NO_USERS)?;
+
+        let prompt = persona_content_prompt(&response.content, &rand_user)
+            .system(persona_system_message(rand_user))
+            .history(conversations.history(&rand_user.base_user.id));
+
+        let response_res = match prompt.send(&config).await {
+            Ok(content) => {
+                conversations.record(
+                    &rand_user.base_user.id,
+                    &prompt,
+                    &config.prompts,
+                    &content.content,
+                );
+
+                content
+            }
 
             Err(e) => {
                 println!("{:?}", e);
@@ -232,6 +355,7 @@ impl PersonasSimulation {
         };
 
         let mut response_reply = Response::default();
+        response_reply.stamp_prompt(&prompt, &config.prompts);
         response_reply.content = response_res.content.clone();
         response_reply.confidence = response_res.confidence as f64;
         response_reply.ethos = response_res.ethos;
@@ -239,27 +363,31 @@ impl PersonasSimulation {
         response_reply.logos = response_res.logos;
 
         response_reply.score = response_reply
-            .calculate_content_attribute_score(key.clone())
-            .await?
-            + response_reply.calculate_engagement_score();
-
-        response
-            .update_score(&config.neo4j_graph, response.score)
+            .calculate_content_attribute_score(&config)
             .await?;
 
         depth -= 1;
 
-        let response_reply_id = response_reply.create(&config.neo4j_graph).await?;
+        response_reply.created_at = config.clock.tick();
+        let response_reply_id = config.store.create_response(&response_reply).await?;
         response_reply.id = response_reply_id;
 
         debate.responses += 1;
 
-        rand_user
-            .add_user_responded(&config.neo4j_graph, response_reply.clone())
+        config
+            .store
+            .relate(Relationship::PersonaResponded {
+                persona_id: rand_user.base_user.id.clone(),
+                response_id: response_reply.id.clone(),
+            })
             .await?;
 
-        response
-            .add_reply_relationship(&config.neo4j_graph, response_reply.clone())
+        config
+            .store
+            .relate(Relationship::Replied {
+                response_id: response.id.clone(),
+                reply_id: response_reply.id.clone(),
+            })
             .await?;
 
         Self::get_learned_attributes(&config, rand_user, &response, &response_reply, debate)
@@ -267,57 +395,77 @@ impl PersonasSimulation {
 
         // get reference
 
-        let mut handles: Vec<JoinHandle<()>> = Vec::new();
+        let mut replies = vec![response_reply.clone()];
+        let mut handles: Vec<
+            JoinHandle<(Option<(String, PersonaContentPrompt, String)>, Response)>,
+        > = Vec::new();
+        let mut earlier_turns: HashMap<String, oneshot::Receiver<Vec<ChatMessage>>> =
+            HashMap::new();
         let mut res: Response = response_reply;
         while depth > 0 {
-            let rint = (random::<f32>() * users.clone().len() as f32).floor() as usize;
-            let rand_user = users[rint].clone();
+            depth -= 1;
+
+            let rand_user = pick(&users, rng, NO_USERS)?.clone();
+
+            // The replies are written concurrently and their turns recorded once they are all done, in the
+            // order they were started. A persona drawn again waits for the history after its earlier reply
+            // in the chain, so its prompts are the same from run to run and include what it said before
+            let history = conversations.history(&rand_user.base_user.id);
+            let earlier_turn = earlier_turns.remove(&rand_user.base_user.id);
+            let (later_turn, next_turn) = oneshot::channel();
+            earlier_turns.insert(rand_user.base_user.id.clone(), next_turn);
+
+            // Stamped before the replies run concurrently, in the order they are started
+            let created_at = config.clock.tick();
 
             // TODO: references
             if pcfg.max_voters > Some(0) {
                 let dh = task::spawn({
                     let config = config.clone();
+                    let conversations = conversations.clone();
                     let users = users.clone();
                     let response = response.clone();
                     let mut debate = debate.clone();
-                    let key = key.clone();
                     let mut res = res.clone();
 
                     async move {
-                        let mut prompt = PersonaContentPrompt::default();
-                        prompt.replace_attributes(vec![
-                            ("THIS_CONTENT".to_string(), response.content.clone()),
-                            (
-                                "POLITICAL_ORIENTATION".to_string(),
-                                rand_user.political_orientation.to_string(),
-                            ),
-                            (
-                                "ENNEAGRAM_TYPE".to_string(),
-                                rand_user.personality.personality_base.enneagram.to_string(),
-                            ),
-                            ("GENDER".to_string(), rand_user.gender.to_string()),
-                            ("AGE".to_string(), rand_user.age.to_string()),
-                            (
-                                "CORE_FEAR".to_string(),
-                                rand_user.personality.personality_base.core_fear.clone(),
-                            ),
-                            (
-                                "CORE_DESIRE".to_string(),
-                                rand_user.personality.personality_base.core_desire.clone(),
-                            ),
-                        ]);
-
-                        let response_res = match prompt.send(key.clone()).await {
-                            Ok(content) => content,
+                        let history = match earlier_turn {
+                            Some(earlier_turn) => earlier_turn.await.unwrap_or(history),
+                            None => history,
+                        };
+
+                        let prompt = persona_content_prompt(&response.content, &rand_user)
+                            .system(persona_system_message(&rand_user))
+                            .history(history.clone());
+
+                        let (response_res, turn) = match prompt.send(&config).await {
+                            Ok(content) => {
+                                let _ = later_turn.send(conversations.continued(
+                                    history,
+                                    &prompt,
+                                    &config.prompts,
+                                    &content.content,
+                                ));
+
+                                let turn = (
+                                    rand_user.base_user.id.clone(),
+                                    prompt.clone(),
+                                    content.content.clone(),
+                                );
+
+                                (content, Some(turn))
+                            }
 
                             Err(e) => {
                                 println!("{:?}", e);
+                                let _ = later_turn.send(history);
 
-                                ContentResponse::default()
+                                (ContentResponse::default(), None)
                             }
                         };
 
                         let mut depth_response_reply = Response::default();
+                        depth_response_reply.stamp_prompt(&prompt, &config.prompts);
                         depth_response_reply.content = response_res.content.clone();
                         depth_response_reply.confidence = response_res.confidence as f64;
                         depth_response_reply.references =
@@ -327,27 +475,31 @@ impl PersonasSimulation {
                         depth_response_reply.logos = response_res.logos;
 
                         depth_response_reply.score = depth_response_reply
-                            .calculate_content_attribute_score(key.clone())
+                            .calculate_content_attribute_score(&config)
                             .await
-                            .expect("msg")
-                            + depth_response_reply.calculate_engagement_score();
-
-                        depth -= 1;
+                            .expect("msg");
 
+                        depth_response_reply.created_at = created_at;
                         let depth_response_reply_id =
-                            depth_response_reply.create(&config.neo4j_graph).await;
+                            config.store.create_response(&depth_response_reply).await;
                         if let Ok(did) = depth_response_reply_id {
                             depth_response_reply.id = did;
                         }
 
-                        rand_user
-                            .add_user_responded(&config.neo4j_graph, depth_response_reply.clone())
+                        let _ = config
+                            .store
+                            .relate(Relationship::PersonaResponded {
+                                persona_id: rand_user.base_user.id.clone(),
+                                response_id: depth_response_reply.id.clone(),
+                            })
+                            .await;
+                        let _ = config
+                            .store
+                            .relate(Relationship::Replied {
+                                response_id: res.id.clone(),
+                                reply_id: depth_response_reply.id.clone(),
+                            })
                             .await;
-                        res.add_reply_relationship(
-                            &config.neo4j_graph,
-                            depth_response_reply.clone(),
-                        )
-                        .await;
 
                         Self::get_learned_attributes(
                             &config,
@@ -360,14 +512,14 @@ impl PersonasSimulation {
 
                         debate.responses += 1;
 
-                        depth_response_reply
-                            .update_ethos(&config.neo4j_graph, depth_response_reply.ethos)
-                            .await;
-                        depth_response_reply
-                            .update_logos(&config.neo4j_graph, depth_response_reply.logos)
-                            .await;
-                        depth_response_reply
-                            .update_pathos(&config.neo4j_graph, depth_response_reply.pathos)
+                        let _ = config
+                            .store
+                            .update_response_appeals(
+                                &depth_response_reply.id,
+                                depth_response_reply.ethos,
+                                depth_response_reply.pathos,
+                                depth_response_reply.logos,
+                            )
                             .await;
 
                         for reference in depth_response_reply.references.clone() {
@@ -376,44 +528,25 @@ impl PersonasSimulation {
                             reff.content = reference.clone();
                             reff.internal = false;
 
-                            let reff_id = reff.create(&config.neo4j_graph).await;
+                            let reff_id = config.store.create_reference(&reff).await;
                             if let Ok(rid) = reff_id {
                                 reff.id = rid;
                             }
 
-                            reff.add_response_referenced_relationship(
-                                &config.neo4j_graph,
-                                depth_response_reply.clone(),
-                            )
-                            .await;
+                            let _ = config
+                                .store
+                                .relate(Relationship::Referenced {
+                                    response_id: depth_response_reply.id.clone(),
+                                    reference_id: reff.id.clone(),
+                                })
+                                .await;
                         }
 
-                        depth_response_reply
-                            .update_score(&config.neo4j_graph, response.score)
-                            .await;
-
-                        rand_user
-                            .add_user_responded(&config.neo4j_graph, depth_response_reply.clone())
-                            .await;
-
                         println!("Reference Response: {:#?}", depth_response_reply.clone());
 
-                        res.add_reply_relationship(
-                            &config.neo4j_graph,
-                            depth_response_reply.clone(),
-                        )
-                        .await;
-
-                        Self::get_learned_attributes(
-                            &config,
-                            &rand_user,
-                            &res,
-                            &depth_response_reply,
-                            &debate,
-                        )
-                        .await;
-
                         res = depth_response_reply;
+
+                        (turn, res)
                     }
                 });
 
@@ -421,47 +554,50 @@ impl PersonasSimulation {
             } else {
                 let dh = task::spawn({
                     let config = config.clone();
+                    let conversations = conversations.clone();
                     let users = users.clone();
                     let response = response.clone();
                     let mut debate = debate.clone();
-                    let key = key.clone();
                     let mut res = res.clone();
 
                     async move {
-                        let mut prompt = PersonaContentPrompt::default();
-                        prompt.replace_attributes(vec![
-                            ("THIS_CONTENT".to_string(), response.content.clone()),
-                            (
-                                "POLITICAL_ORIENTATION".to_string(),
-                                rand_user.political_orientation.to_string(),
-                            ),
-                            (
-                                "ENNEAGRAM_TYPE".to_string(),
-                                rand_user.personality.personality_base.enneagram.to_string(),
-                            ),
-                            ("GENDER".to_string(), rand_user.gender.to_string()),
-                            ("AGE".to_string(), rand_user.age.to_string()),
-                            (
-                                "CORE_FEAR".to_string(),
-                                rand_user.personality.personality_base.core_fear.clone(),
-                            ),
-                            (
-                                "CORE_DESIRE".to_string(),
-                                rand_user.personality.personality_base.core_desire.clone(),
-                            ),
-                        ]);
-
-                        let response_res = match prompt.send(key.clone()).await {
-                            Ok(content) => content,
+                        let history = match earlier_turn {
+                            Some(earlier_turn) => earlier_turn.await.unwrap_or(history),
+                            None => history,
+                        };
+
+                        let prompt = persona_content_prompt(&response.content, &rand_user)
+                            .system(persona_system_message(&rand_user))
+                            .history(history.clone());
+
+                        let (response_res, turn) = match prompt.send(&config).await {
+                            Ok(content) => {
+                                let _ = later_turn.send(conversations.continued(
+                                    history,
+                                    &prompt,
+                                    &config.prompts,
+                                    &content.content,
+                                ));
+
+                                let turn = (
+                                    rand_user.base_user.id.clone(),
+                                    prompt.clone(),
+                                    content.content.clone(),
+                                );
+
+                                (content, Some(turn))
+                            }
 
                             Err(e) => {
                                 println!("{:?}", e);
+                                let _ = later_turn.send(history);
 
-                                ContentResponse::default()
+                                (ContentResponse::default(), None)
                             }
                         };
 
                         let mut depth_response_reply = Response::default();
+                        depth_response_reply.stamp_prompt(&prompt, &config.prompts);
                         depth_response_reply.content = response_res.content.clone();
                         depth_response_reply.confidence = response_res.confidence as f64;
                         depth_response_reply.ethos = response_res.ethos;
@@ -469,46 +605,36 @@ impl PersonasSimulation {
                         depth_response_reply.logos = response_res.logos;
 
                         depth_response_reply.score = depth_response_reply
-                            .calculate_content_attribute_score(key.clone())
+                            .calculate_content_attribute_score(&config)
                             .await
-                            .expect("msg")
-                            + depth_response_reply.calculate_engagement_score();
-
-                        depth -= 1;
-
-                        depth_response_reply
-                            .update_ethos(&config.neo4j_graph, depth_response_reply.ethos)
-                            .await;
-                        depth_response_reply
-                            .update_logos(&config.neo4j_graph, depth_response_reply.logos)
-                            .await;
-                        depth_response_reply
-                            .update_pathos(&config.neo4j_graph, depth_response_reply.pathos)
-                            .await;
+                            .expect("msg");
 
+                        depth_response_reply.created_at = created_at;
                         if let Ok(depth_response_reply_id) =
-                            depth_response_reply.create(&config.neo4j_graph).await
+                            config.store.create_response(&depth_response_reply).await
                         {
                             depth_response_reply.id = depth_response_reply_id;
                         }
 
                         debate.responses += 1;
 
-                        depth_response_reply
-                            .update_score(&config.neo4j_graph, response.score)
-                            .await;
-
-                        rand_user
-                            .add_user_responded(&config.neo4j_graph, depth_response_reply.clone())
+                        let _ = config
+                            .store
+                            .relate(Relationship::PersonaResponded {
+                                persona_id: rand_user.base_user.id.clone(),
+                                response_id: depth_response_reply.id.clone(),
+                            })
                             .await;
 
                         println!("Response: {:?}", depth_response_reply.clone());
 
-                        res.add_reply_relationship(
-                            &config.neo4j_graph,
-                            depth_response_reply.clone(),
-                        )
-                        .await;
+                        let _ = config
+                            .store
+                            .relate(Relationship::Replied {
+                                response_id: res.id.clone(),
+                                reply_id: depth_response_reply.id.clone(),
+                            })
+                            .await;
 
                         Self::get_learned_attributes(
                             &config,
@@ -520,6 +646,8 @@ impl PersonasSimulation {
                         .await;
 
                         res = depth_response_reply;
+
+                        (turn, res)
                     }
                 });
 
@@ -527,9 +655,15 @@ impl PersonasSimulation {
             }
         }
 
-        join_all(handles).await;
+        for (turn, reply) in join_all(handles).await.into_iter().flatten() {
+            if let Some((user_id, prompt, content)) = turn {
+                conversations.record(&user_id, &prompt, &config.prompts, &content);
+            }
 
-        Ok(())
+            replies.push(reply);
+        }
+
+        Ok(replies)
     }
 
     pub async fn get_learned_attributes(
@@ -539,31 +673,9 @@ impl PersonasSimulation {
         reply: &Response,
         debate: &Debate,
     ) -> Result<(), PulpError> {
-        let mut prompt = LearnedPrompt::default();
-        prompt.replace_attributes(vec![
-            ("THIS_CONTENT".to_string(), response.content.clone()),
-            ("THIS_RESPONSE".to_string(), reply.content.clone()),
-            (
-                "POLITICAL_ORIENTATION".to_string(),
-                user.political_orientation.to_string(),
-            ),
-            (
-                "ENNEAGRAM_TYPE".to_string(),
-                user.personality.personality_base.enneagram.to_string(),
-            ),
-            ("AGE".to_string(), user.age.to_string()),
-            ("GENDER".to_string(), user.gender.to_string()),
-            (
-                "CORE_FEAR".to_string(),
-                user.personality.personality_base.core_fear.clone(),
-            ),
-            (
-                "CORE_DESIRE".to_string(),
-                user.personality.personality_base.core_desire.clone(),
-            ),
-        ]);
-
-        let response_res = match prompt.send(config.open_ai_key.clone()).await {
+        let prompt = learned_prompt(&response.content, &reply.content, user);
+
+        let response_res = match prompt.send(&config).await {
             Ok(content) => content,
 
             Err(e) => {
@@ -577,21 +689,33 @@ impl PersonasSimulation {
         // TODO: add learned attributes
         learned.learned_content = response_res.political_orientation;
 
-        let learned_id = learned.create(&config.neo4j_graph).await?;
+        let learned_id = config.store.create_learned(&learned).await?;
         learned.id = learned_id;
 
         println!("Learned: {:#?}", learned);
 
-        learned
-            .add_user_learned(&config.neo4j_graph, user.clone())
+        config
+            .store
+            .relate(Relationship::Learned {
+                persona_id: user.base_user.id.clone(),
+                learned_id: learned.id.clone(),
+            })
             .await?;
 
-        learned
-            .add_learned_in(&config.neo4j_graph, debate.clone())
+        config
+            .store
+            .relate(Relationship::LearnedIn {
+                learned_id: learned.id.clone(),
+                debate_id: debate.id.clone(),
+            })
             .await?;
 
-        learned
-            .add_learned_from(&config.neo4j_graph, response.clone())
+        config
+            .store
+            .relate(Relationship::LearnedFrom {
+                learned_id: learned.id.clone(),
+                response_id: response.id.clone(),
+            })
             .await?;
 
         Ok(())
@@ -604,14 +728,15 @@ impl PersonasSimulation {
         debate: Debate,
         votes: u64,
         users: Vec<PersonasUser>,
+        rng: &mut StdRng,
     ) -> Result<(), PulpError> {
-        debate
-            .update_voters(&config.neo4j_graph, votes as i64)
+        config
+            .store
+            .update_debate_voters(&debate.id, votes as i64)
             .await?;
 
         for _ in 0..votes {
-            let rint = (random::<f32>() * users.clone().len() as f32).floor() as usize;
-            let rand_user = users[rint].clone();
+            let rand_user = pick(&users, rng, NO_USERS)?.clone();
 
             // task::spawn({
             // let config = config.clone();
@@ -620,30 +745,9 @@ impl PersonasSimulation {
             // let rand_user = rand_user.clone();
 
             // async move {
-            let mut vote = VoteContentPrompt::default();
-            vote.replace_attributes(vec![
-                ("THIS_CONTENT".to_string(), response.clone().content.clone()),
-                (
-                    "POLITICAL_ORIENTATION".to_string(),
-                    rand_user.political_orientation.to_string(),
-                ),
-                (
-                    "ENNEAGRAM_TYPE".to_string(),
-                    rand_user.personality.personality_base.enneagram.to_string(),
-                ),
-                ("GENDER".to_string(), rand_user.gender.to_string()),
-                ("AGE".to_string(), rand_user.age.to_string()),
-                (
-                    "CORE_FEAR".to_string(),
-                    rand_user.personality.personality_base.core_fear.clone(),
-                ),
-                (
-                    "CORE_DESIRE".to_string(),
-                    rand_user.personality.personality_base.core_desire.clone(),
-                ),
-            ]);
-
-            let vote_res = match vote.send(config.open_ai_key.clone()).await {
+            let vote = vote_prompt(&response.content, &rand_user);
+
+            let vote_res = match vote.send(&config).await {
                 Ok(content) => content,
 
                 Err(e) => {
@@ -654,23 +758,34 @@ impl PersonasSimulation {
             };
 
             let mut votes = (0, 0, 0);
-            let vote_type = VoteType::from(vote_res.vote.as_str());
-            match vote_type {
-                VoteType::Valid(_) => votes.0 += 1,
+            let vote_type = match VoteType::from(vote_res.vote.as_str()) {
+                VoteType::Valid(_) => {
+                    votes.0 += 1;
+                    VoteType::Valid(Some(vote_res.reason))
+                }
 
-                VoteType::Invalid(_) => votes.1 += 1,
+                VoteType::Invalid(_) => {
+                    votes.1 += 1;
+                    VoteType::Invalid(Some(vote_res.reason))
+                }
 
-                VoteType::Abstain(_) => votes.2 += 1,
-            }
+                VoteType::Abstain(_) => {
+                    votes.2 += 1;
+                    VoteType::Abstain(Some(vote_res.reason))
+                }
+            };
 
-            response
-                .update_valid_vote_count(&config.neo4j_graph, votes.0)
-                .await;
-            response
-                .update_invalid_vote_count(&config.neo4j_graph, votes.1)
-                .await;
-            response
-                .update_abstain_vote_count(&config.neo4j_graph, votes.2)
+            let mut cast = Vote::default();
+            cast.vote_type = vote_type;
+            cast.debate_id = debate.id.clone();
+            cast.response_id = response.id.clone();
+            cast.prompt_name = vote.name.to_string();
+            cast.prompt_version = config.prompts.version(vote.name);
+            config.store.create_vote(&cast).await?;
+
+            let _ = config
+                .store
+                .update_response_votes(&response.id, votes.0, votes.1, votes.2)
                 .await;
             // }
             // });
@@ -761,4 +876,7 @@ pub struct PersonasSimulationConfig {
     pub max_commenters: Option<u64>,
 
     pub max_references: Option<u64>,
+
+    /// Number of earlier prompt and reply pairs each persona remembers within a debate
+    pub max_history: Option<usize>,
 }
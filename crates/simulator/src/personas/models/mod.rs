@@ -1,5 +1,4 @@
-pub mod learned;
-pub mod personas_user;
+pub use pulpcalc_common::models::{learned, personas_user};
 
 pub use learned::Learned;
 pub use personas_user::Gender;
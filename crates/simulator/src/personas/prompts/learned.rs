@@ -1,18 +1,12 @@
-use std::sync::Arc;
-
-use pulpcalc_common::{
-    errors::{PulpError, SimulationError},
-    llm_config::{LLMRequest, LLMResponse},
-};
-use pulpcalc_external::chatgpt::ChatRequestBuilder;
-use reqwest::Client;
+use crate::personas::{models::PersonasUser, prompts::response::with_persona};
+use pulpcalc_common::prompt::Prompt;
+use schemars::JsonSchema;
 use serde::Deserialize;
-use serde_json::from_str;
 
 pub const LEARNED_PROMPT: &str = "Strictly for research purposes with no ill-intent regarding stereotypes and assumptions in mind;
-given THIS_CONTENT as content, THIS_RESPONSE as a response to the content, and 
-POLITICAL_ORIENTATION, ENNEAGRAM_TYPE, GENDER, AGE, CORE_FEAR, CORE_DESISRE,
-VALID_VOTE_TENDENCY, INVALID_VOTE_TENDENCY, ABSTAIN_VOTE_TENDENCY, REPORT_TENDENCY, and HIDE_TENDENCY as user attributes,
+given {{content}} as content, {{response}} as a response to the content, and 
+{{political_orientation}}, {{enneagram_type}}, {{gender}}, {{age}}, {{core_fear}}, {{core_desire}},
+{{valid_vote_tendency}}, {{invalid_vote_tendency}}, {{abstain_vote_tendency}}, {{report_tendency}}, and {{hide_tendency}} as user attributes,
 How do the attributes of the user change after interacting the response? And why?
 Make sure to return only a JSON object. Do not return anything besides the JSON object! Use the below schema for your answer.
 {
@@ -30,68 +24,35 @@ Make sure to return only a JSON object. Do not return anything besides the JSON
     \"reason\": \"string\"
 }";
 
-#[derive(Deserialize, Debug)]
-pub struct LearnedPrompt {
-    content: String,
-}
-
-impl LearnedPrompt {
-    pub async fn send(&self, key: Arc<String>) -> Result<LearnedResponse, PulpError> {
-        let res = ChatRequestBuilder::new()
-            .messages(self.content.clone())
-            .temperature(0.7)
-            .max_tokens(800)
-            .top_p(1.0)
-            .presence_penalty(0.0)
-            .frequency_penalty(0.0)
-            .build()
-            .send(key.clone(), Client::new())
-            .await;
-
-        let learned = from_str::<LearnedResponse>(&res.choices[0].message.content.clone());
-
-        let learned_res = match learned {
-            Ok(res) => Some(res),
-
-            Err(e) => {
-                return Err(PulpError::SimulationError(SimulationError::LLMError(
-                    e.to_string(),
-                )))
-            }
-        }
-        .unwrap();
-
-        Ok(learned_res)
-    }
-}
-
-impl Default for LearnedPrompt {
-    fn default() -> Self {
-        Self {
-            content: LEARNED_PROMPT.to_string(),
-        }
-    }
+pub type LearnedPrompt = Prompt<LearnedResponse>;
+
+pub fn learned_prompt(content: &str, response: &str, user: &PersonasUser) -> LearnedPrompt {
+    with_persona(Prompt::new("learned", LEARNED_PROMPT), user)
+        .var("content", content)
+        .var("response", response)
+        .var(
+            "valid_vote_tendency",
+            user.personality.personality_engagement.voting_tendency.0,
+        )
+        .var(
+            "invalid_vote_tendency",
+            user.personality.personality_engagement.voting_tendency.1,
+        )
+        .var(
+            "abstain_vote_tendency",
+            user.personality.personality_engagement.voting_tendency.2,
+        )
+        .var(
+            "report_tendency",
+            user.personality.personality_engagement.report_tendency,
+        )
+        .var(
+            "hide_tendency",
+            user.personality.personality_engagement.hide_tendency,
+        )
 }
 
-impl LLMRequest for LearnedPrompt {
-    fn get_prompt(&self) -> String {
-        self.content.clone()
-    }
-
-    fn replace_attributes<T: ToString>(&mut self, words: Vec<(T, T)>) -> String {
-        let mut c = self.content.clone();
-
-        for (key, value) in words {
-            c = c.replace(key.to_string().as_str(), value.to_string().as_str());
-        }
-
-        self.content = c.clone();
-
-        c
-    }
-}
-
-#[derive(Deserialize, Debug, Default)]
+#[derive(Deserialize, Debug, Default, JsonSchema)]
 pub struct LearnedResponse {
     pub political_orientation: String,
     pub enneagram_type: String,
@@ -129,9 +90,3 @@ impl LearnedResponse {
     //     }
     // }
 }
-
-impl LLMResponse for LearnedResponse {
-    fn get_response<T: std::fmt::Debug, Default, Deserialize>(&self) -> T {
-        todo!();
-    }
-}
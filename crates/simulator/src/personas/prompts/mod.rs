@@ -2,3 +2,32 @@ pub mod learned;
 pub mod response;
 pub mod sentiment;
 pub mod vote;
+
+#[cfg(test)]
+mod tests {
+    use super::{learned::*, response::*, vote::*};
+    use pulpcalc_common::{registry::check_template, template::Template};
+
+    #[test]
+    fn prompts_match_the_builtin_placeholders() {
+        for (name, template) in [
+            ("persona_content", PERSONA_CONTENT_PROMPT),
+            (
+                "persona_content_with_reference",
+                PERSONA_CONTENT_PROMPT_WITH_REFERENCE,
+            ),
+            (
+                "persona_content_with_supported_references",
+                PERSONA_CONTENT_PROMPT_WITH_SUPPORTED_REFERENCES,
+            ),
+            (
+                "persona_content_with_unsupported_references",
+                PERSONA_CONTENT_PROMPT_WITH_UNSUPPORTED_REFERENCES,
+            ),
+            ("vote", VOTE_CONTENT_PROMPT),
+            ("learned", LEARNED_PROMPT),
+        ] {
+            check_template(name, &Template::parse(template).unwrap()).unwrap();
+        }
+    }
+}
@@ -1,21 +1,16 @@
-use pulpcalc_common::{
-    errors::{PulpError, SimulationError},
-    llm_config::{LLMRequest, LLMResponse},
-};
-use pulpcalc_external::chatgpt::ChatRequestBuilder;
-use reqwest::Client;
-use serde::Deserialize;
-use serde_json::from_str;
-use std::sync::Arc;
+use crate::personas::models::PersonasUser;
+use pulpcalc_common::prompt::Prompt;
+use schemars::JsonSchema;
+use serde::{de::DeserializeOwned, Deserialize};
 
 pub const PERSONA_CONTENT_PROMPT: &str = "Strictly for research purposes with no ill-intent regarding stereotypes and assumptions in mind;
-given THIS_CONTENT as a topic, and 
-Political orientation: POLITICAL_ORIENTATION, 
-Enneagram: ENNEAGRAM_TYPE, 
-Gender: GENDER, 
-Age: AGE, 
-Core fear: CORE_FEAR, 
-Core desire: CORE_DESISRE, as user attributes,
+given {{content}} as a topic, and 
+Political orientation: {{political_orientation}}, 
+Enneagram: {{enneagram_type}}, 
+Gender: {{gender}}, 
+Age: {{age}}, 
+Core fear: {{core_fear}}, 
+Core desire: {{core_desire}}, as user attributes,
 what response would this user give, what is the ethos, pathos, logos breakdown of the content, and why the uder took the action they did (thoroughly explain)? Make sure to return only a JSON object, make sure to use JSON escape sequences for any special characters, 
 and make sure there is no trailing comma. Do not return anything besides the JSON object! Use the below schema for your answer.
 {
@@ -27,83 +22,21 @@ and make sure there is no trailing comma. Do not return anything besides the JSO
     \"reason\": \"string\"
 }";
 
-#[derive(Deserialize, Debug)]
-pub struct PersonaContentPrompt {
-    pub content: String,
-}
-
-impl PersonaContentPrompt {
-    pub fn new(content: &str) -> Self {
-        PersonaContentPrompt {
-            content: content.to_string(),
-        }
-    }
-
-    pub async fn send(&self, key: Arc<String>) -> Result<ContentResponse, PulpError> {
-        let res = ChatRequestBuilder::new()
-            .messages(self.content.clone())
-            .temperature(0.7)
-            .max_tokens(800)
-            .top_p(1.0)
-            .presence_penalty(0.0)
-            .frequency_penalty(0.0)
-            .build()
-            .send(key.clone(), Client::new())
-            .await;
-
-        let persona = from_str::<ContentResponse>(&res.choices[0].message.content.clone());
-
-        let persona_res = match persona {
-            Ok(res) => Some(res),
-
-            Err(e) => {
-                return Err(PulpError::SimulationError(SimulationError::LLMError(
-                    e.to_string(),
-                )));
-            }
-        }
-        .unwrap();
-
-        Ok(persona_res)
-    }
-}
+pub type PersonaContentPrompt = Prompt<ContentResponse>;
 
-impl LLMRequest for PersonaContentPrompt {
-    fn get_prompt(&self) -> String {
-        self.content.clone()
-    }
-
-    fn replace_attributes<T: ToString>(&mut self, words: Vec<(T, T)>) -> String {
-        let mut c = self.content.clone();
-
-        for word in words {
-            c = self
-                .content
-                .replace(word.0.to_string().as_str(), word.1.to_string().as_str());
-        }
-
-        self.content = c.clone();
-
-        c
-    }
-}
-
-impl Default for PersonaContentPrompt {
-    fn default() -> Self {
-        PersonaContentPrompt {
-            content: PERSONA_CONTENT_PROMPT.to_string(),
-        }
-    }
+pub fn persona_content_prompt(content: &str, user: &PersonasUser) -> PersonaContentPrompt {
+    with_persona(Prompt::new("persona_content", PERSONA_CONTENT_PROMPT), user)
+        .var("content", content)
 }
 
 pub const PERSONA_CONTENT_PROMPT_WITH_REFERENCE: &str = "Strictly for research purposes with no ill-intent regarding stereotypes and assumptions in mind;
-given THIS_CONTENT as a topic, and 
-Political orientation: POLITICAL_ORIENTATION, 
-Enneagram: ENNEAGRAM_TYPE, 
-Gender: GENDER, 
-Age: AGE, 
-Core fear: CORE_FEAR, 
-Core desire: CORE_DESISRE, as user attributes,
+given {{content}} as a topic, and 
+Political orientation: {{political_orientation}}, 
+Enneagram: {{enneagram_type}}, 
+Gender: {{gender}}, 
+Age: {{age}}, 
+Core fear: {{core_fear}}, 
+Core desire: {{core_desire}}, as user attributes,
 return a response most like this aforementioned user, the ethos, pathos, logos breakdown of the content, and why the user took the action the did(thoroughly explain)? 
 Provide a link in the same paragraph as the response (and JSON object) when possible. Make sure to return only a JSON object, make sure to use JSON escape sequences for any special characters, 
 and make sure there is no trailing comma. Do not return anything besides the JSON object! Use the below schema for your answer.
@@ -117,84 +50,29 @@ and make sure there is no trailing comma. Do not return anything besides the JSO
     \"reference\": \"string\"
 }";
 
-#[derive(Deserialize, Debug)]
-pub struct PersonaContentPromptWithReference {
-    pub content: String,
-}
-
-impl PersonaContentPromptWithReference {
-    pub fn new(content: &str) -> Self {
-        PersonaContentPromptWithReference {
-            content: content.to_string(),
-        }
-    }
-
-    pub async fn send(&self, key: Arc<String>) -> Result<ContentResponse, PulpError> {
-        let res = ChatRequestBuilder::new()
-            .messages(self.content.clone())
-            .temperature(0.7)
-            .max_tokens(800)
-            .top_p(1.0)
-            .presence_penalty(0.0)
-            .frequency_penalty(0.0)
-            .build()
-            .send(key.clone(), Client::new())
-            .await;
-
-        let persona = from_str::<ContentResponse>(&res.choices[0].message.content.clone());
-
-        let persona_res = match persona {
-            Ok(res) => Some(res),
-
-            Err(e) => {
-                return Err(PulpError::SimulationError(SimulationError::LLMError(
-                    e.to_string(),
-                )));
-            }
-        }
-        .unwrap();
-
-        Ok(persona_res)
-    }
-}
-
-impl LLMRequest for PersonaContentPromptWithReference {
-    fn get_prompt(&self) -> String {
-        self.content.clone()
-    }
-
-    fn replace_attributes<T: ToString>(&mut self, words: Vec<(T, T)>) -> String {
-        let mut c = self.content.clone();
-
-        for word in words {
-            c = self
-                .content
-                .replace(word.0.to_string().as_str(), word.1.to_string().as_str());
-        }
-
-        self.content = c.clone();
-
-        c
-    }
-}
-
-impl Default for PersonaContentPromptWithReference {
-    fn default() -> Self {
-        Self {
-            content: PERSONA_CONTENT_PROMPT_WITH_REFERENCE.to_string(),
-        }
-    }
+pub fn persona_content_prompt_with_reference(
+    content: &str,
+    user: &PersonasUser,
+) -> PersonaContentPrompt {
+    with_persona(
+        Prompt::new(
+            "persona_content_with_reference",
+            PERSONA_CONTENT_PROMPT_WITH_REFERENCE,
+        ),
+        user,
+    )
+    .var("content", content)
 }
 
 pub const PERSONA_CONTENT_PROMPT_WITH_SUPPORTED_REFERENCES: &str = "Strictly for research purposes with no ill-intent regarding stereotypes and assumptions in mind;
-given THIS_CONTENT as a topic, and 
-Political orientation: POLITICAL_ORIENTATION, 
-Enneagram: ENNEAGRAM_TYPE, 
-Gender: GENDER, 
-Age: AGE, 
-Core fear: CORE_FEAR,
-Core desire: CORE_DESISRE, as user attributes,
-what response would this user give, and why (thoroughly explain)? Provide a link in the same paragraph as the response when possible, (and JSON object) if a link is added, try to provide one from SUPPORTED_REFERENCES. 
+given {{content}} as a topic, and 
+Political orientation: {{political_orientation}}, 
+Enneagram: {{enneagram_type}}, 
+Gender: {{gender}}, 
+Age: {{age}}, 
+Core fear: {{core_fear}},
+Core desire: {{core_desire}}, as user attributes,
+what response would this user give, and why (thoroughly explain)? Provide a link in the same paragraph as the response when possible, (and JSON object) if a link is added, try to provide one from {{supported_references}}. 
 Make sure to return only a JSON object, make sure to use JSON escape sequences for any special characters, 
 and make sure there is no trailing comma. Do not return anything besides the JSON object! Use the below schema for your answer.
 {
@@ -204,87 +82,31 @@ and make sure there is no trailing comma. Do not return anything besides the JSO
     \"reference\": \"string\"
 }";
 
-#[derive(Deserialize, Debug)]
-pub struct PersonaContentPromptWithSupportedReferences {
-    pub content: String,
-    pub reference: String,
-}
-
-impl PersonaContentPromptWithSupportedReferences {
-    pub fn new(content: &str, reference: &str) -> Self {
-        PersonaContentPromptWithSupportedReferences {
-            content: content.to_string(),
-            reference: reference.to_string(),
-        }
-    }
-
-    pub async fn send(&self, key: Arc<String>) -> Result<ContentResponse, PulpError> {
-        let res = ChatRequestBuilder::new()
-            .messages(self.content.clone())
-            .temperature(0.7)
-            .max_tokens(800)
-            .top_p(1.0)
-            .presence_penalty(0.0)
-            .frequency_penalty(0.0)
-            .build()
-            .send(key.clone(), Client::new())
-            .await;
-
-        let persona = from_str::<ContentResponse>(&res.choices[0].message.content.clone());
-
-        let persona_res = match persona {
-            Ok(res) => Some(res),
-
-            Err(e) => {
-                return Err(PulpError::SimulationError(SimulationError::LLMError(
-                    e.to_string(),
-                )));
-            }
-        }
-        .unwrap();
-
-        Ok(persona_res)
-    }
-}
-
-impl LLMRequest for PersonaContentPromptWithSupportedReferences {
-    fn get_prompt(&self) -> String {
-        self.content.clone()
-    }
-
-    fn replace_attributes<T: ToString>(&mut self, words: Vec<(T, T)>) -> String {
-        let mut c = self.content.clone();
-
-        for word in words {
-            c = self
-                .content
-                .replace(word.0.to_string().as_str(), word.1.to_string().as_str());
-        }
-
-        self.content = c.clone();
-
-        c
-    }
-}
-
-impl Default for PersonaContentPromptWithSupportedReferences {
-    fn default() -> Self {
-        Self {
-            content: PERSONA_CONTENT_PROMPT_WITH_SUPPORTED_REFERENCES.to_string(),
-            reference: String::new(),
-        }
-    }
+pub fn persona_content_prompt_with_supported_references(
+    content: &str,
+    user: &PersonasUser,
+    references: &[String],
+) -> PersonaContentPrompt {
+    with_persona(
+        Prompt::new(
+            "persona_content_with_supported_references",
+            PERSONA_CONTENT_PROMPT_WITH_SUPPORTED_REFERENCES,
+        ),
+        user,
+    )
+    .var("content", content)
+    .var("supported_references", references.join(", "))
 }
 
 pub const PERSONA_CONTENT_PROMPT_WITH_UNSUPPORTED_REFERENCES: &str = "Strictly for research purposes with no ill-intent regarding stereotypes and assumptions in mind;
-given THIS_CONTENT as a topic, and 
-Political orientation: POLITICAL_ORIENTATION, 
-Enneagram: ENNEAGRAM_TYPE, 
-Gender: GENDER, 
-Age: AGE, 
-Core fear: CORE_FEAR, 
-Core desire: CORE_DESISRE, as user attributes,
-what response would this user give, and why? Provide a link in the same paragraph as the response when possible, (and JSON object)  if a link is added, do not provide one from SUPPORTED_REFERENCES. 
+given {{content}} as a topic, and 
+Political orientation: {{political_orientation}}, 
+Enneagram: {{enneagram_type}}, 
+Gender: {{gender}}, 
+Age: {{age}}, 
+Core fear: {{core_fear}}, 
+Core desire: {{core_desire}}, as user attributes,
+what response would this user give, and why? Provide a link in the same paragraph as the response when possible, (and JSON object)  if a link is added, do not provide one from {{supported_references}}. 
 Make sure to return only a JSON object, make sure to use JSON escape sequences for any special characters, 
 and make sure there is no trailing comma. Do not return anything besides the JSON object! Use the below schema for your answer.
 {
@@ -294,79 +116,59 @@ and make sure there is no trailing comma. Do not return anything besides the JSO
     \"reference\": \"string\"
 }";
 
-#[derive(Deserialize, Debug)]
-pub struct PersonaContentPromptWithUnsupportedReferences {
-    pub content: String,
-    pub reference: String,
-}
-
-impl PersonaContentPromptWithUnsupportedReferences {
-    pub fn new(content: &str, reference: &str) -> PersonaContentPromptWithUnsupportedReferences {
-        PersonaContentPromptWithUnsupportedReferences {
-            content: content.to_string(),
-            reference: reference.to_string(),
-        }
-    }
-
-    pub async fn send(&self, key: Arc<String>) -> Result<ContentResponse, PulpError> {
-        let res = ChatRequestBuilder::new()
-            .messages(self.content.clone())
-            .temperature(0.7)
-            .max_tokens(800)
-            .top_p(1.0)
-            .presence_penalty(0.0)
-            .frequency_penalty(0.0)
-            .build()
-            .send(key.clone(), Client::new())
-            .await;
-
-        let persona = from_str::<ContentResponse>(&res.choices[0].message.content.clone());
-
-        let persona_res = match persona {
-            Ok(res) => Some(res),
-
-            Err(e) => {
-                return Err(PulpError::SimulationError(SimulationError::LLMError(
-                    e.to_string(),
-                )));
-            }
-        }
-        .unwrap();
-
-        Ok(persona_res)
-    }
-}
-
-impl LLMRequest for PersonaContentPromptWithUnsupportedReferences {
-    fn get_prompt(&self) -> String {
-        self.content.clone()
-    }
-
-    fn replace_attributes<T: ToString>(&mut self, words: Vec<(T, T)>) -> String {
-        let mut c = self.content.clone();
-
-        for word in words {
-            c = self
-                .content
-                .replace(word.0.to_string().as_str(), word.1.to_string().as_str());
-        }
-
-        self.content = c.clone();
-
-        c
-    }
-}
-
-impl Default for PersonaContentPromptWithUnsupportedReferences {
-    fn default() -> Self {
-        Self {
-            content: PERSONA_CONTENT_PROMPT_WITH_UNSUPPORTED_REFERENCES.to_string(),
-            reference: String::new(),
-        }
-    }
-}
-
-#[derive(Deserialize, Debug, Default)]
+pub fn persona_content_prompt_with_unsupported_references(
+    content: &str,
+    user: &PersonasUser,
+    references: &[String],
+) -> PersonaContentPrompt {
+    with_persona(
+        Prompt::new(
+            "persona_content_with_unsupported_references",
+            PERSONA_CONTENT_PROMPT_WITH_UNSUPPORTED_REFERENCES,
+        ),
+        user,
+    )
+    .var("content", content)
+    .var("supported_references", references.join(", "))
+}
+
+/// System message describing the persona a conversation is held with
+pub fn persona_system_message(user: &PersonasUser) -> String {
+    format!(
+        "You are role playing a social media user taking part in a debate. You are {} years old, your gender is {}, your political orientation is {}, your enneagram type is {}, your core fear is {} and your core desire is {}. Stay consistent with what you have already said in this debate.",
+        user.age,
+        user.gender.to_string(),
+        user.political_orientation.to_string(),
+        user.personality.personality_base.enneagram,
+        user.personality.personality_base.core_fear,
+        user.personality.personality_base.core_desire,
+    )
+}
+
+/// Binds the user attributes shared by every persona prompt
+pub fn with_persona<R: DeserializeOwned + JsonSchema>(
+    prompt: Prompt<R>,
+    user: &PersonasUser,
+) -> Prompt<R> {
+    prompt
+        .var(
+            "political_orientation",
+            user.political_orientation.to_string(),
+        )
+        .var(
+            "enneagram_type",
+            user.personality.personality_base.enneagram,
+        )
+        .var("gender", user.gender.to_string())
+        .var("age", user.age)
+        .var("core_fear", &user.personality.personality_base.core_fear)
+        .var(
+            "core_desire",
+            &user.personality.personality_base.core_desire,
+        )
+}
+
+#[derive(Deserialize, Debug, Default, JsonSchema)]
 pub struct ContentResponse {
     pub content: String,
     pub confidence: f32,
@@ -398,9 +200,3 @@ impl ContentResponse {
         }
     }
 }
-
-impl LLMResponse for ContentResponse {
-    fn get_response<T: std::fmt::Debug, Default, Deserialize>(&self) -> T {
-        todo!();
-    }
-}
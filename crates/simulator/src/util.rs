@@ -1,9 +1,30 @@
 use crate::business::BusinessSimulation;
 use crate::enneagram::EnneagramSimulation;
 use crate::personas::PersonasSimulationConfig;
+use pulpcalc_common::{
+    config::Config,
+    errors::{PulpError, SimulationError},
+    models::Response,
+};
+use rand::{rngs::StdRng, Rng};
 use std::fs;
 use toml;
 
+/// A user drawn from `users` with `rng`, failing with `missing` when there are none to draw from
+pub(crate) fn pick<'a, T>(
+    users: &'a [T],
+    rng: &mut StdRng,
+    missing: &str,
+) -> Result<&'a T, PulpError> {
+    if users.is_empty() {
+        return Err(PulpError::SimulationError(SimulationError::SimError(
+            missing.to_string(),
+        )));
+    }
+
+    Ok(&users[rng.gen_range(0..users.len())])
+}
+
 pub fn new_enneagram_from_file(file: &str) -> Vec<EnneagramSimulation> {
     let contents = match fs::read_to_string(file) {
         Ok(c) => c,
@@ -78,3 +99,40 @@ pub fn new_business_from_file(file: String) -> Vec<BusinessSimulation> {
 
     businesses
 }
+
+/// Adds the engagement points of every response a round wrote to the content points it was stored with
+/// and writes the scores. Runs once the round is flushed and reads every engagement before writing any
+/// score, so scoring doesn't flush the store again and the scores go out with the next round.
+pub(crate) async fn score_round(
+    config: &Config,
+    responses: &mut [Response],
+) -> Result<(), PulpError> {
+    for response in responses.iter_mut() {
+        response.score += response.calculate_engagement_score(config).await?;
+    }
+
+    for response in responses.iter() {
+        response.store_score(config).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn picking_from_no_users_fails() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let users: Vec<String> = Vec::new();
+
+        assert_eq!(
+            pick(&users, &mut rng, "no users"),
+            Err(PulpError::SimulationError(SimulationError::SimError(
+                "no users".to_string()
+            )))
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use pulpcalc_common::{
+    config::Config,
+    models::Debate,
+    store::{BatchedStore, MemoryStore},
+};
+use simulator::{
+    enneagram::EnneagramSimulation, new_personas_from_file, personas::PersonasSimulation,
+};
+use std::sync::Arc;
+
+const PERSONAS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../Personas.toml");
+
+/// A config sending prompts to the mock provider and writes to a memory store, in batches large enough
+/// that only the flushes of the simulation send them
+async fn config() -> (Config, Arc<BatchedStore>) {
+    let mut config = Config::default().await;
+    config.seed = 5;
+    config.llm_provider = Some(String::from("mock"));
+    config.llm = config.new_llm_provider();
+
+    let store = Arc::new(BatchedStore::new(Arc::new(MemoryStore::new()), 100_000));
+    config.store = store.clone();
+
+    (config, store)
+}
+
+#[tokio::test]
+async fn personas_rounds_are_flushed_once_each() {
+    let (config, store) = config().await;
+
+    let mut personas_config = new_personas_from_file(PERSONAS.to_string());
+    personas_config.simulation_size = 20;
+    personas_config.max_commenters = Some(3);
+    personas_config.max_voters = Some(2);
+
+    let mut rng = config.rng();
+    PersonasSimulation::init_users(&config, &personas_config, 20, &mut rng)
+        .await
+        .unwrap();
+    config.store.flush().await.unwrap();
+    let before = store.batches();
+
+    let mut simulation = PersonasSimulation::default();
+    simulation.debates.push(Debate {
+        topic: personas_config.debate_topics.clone().unwrap()[0].clone(),
+        category: personas_config.debate_categories.clone().unwrap()[0].clone(),
+        ..Default::default()
+    });
+
+    simulation
+        .run_simulation(config.clone(), personas_config, &mut rng)
+        .await
+        .unwrap();
+
+    // One flush for each of the 3 commenters and one once the debate is done
+    assert_eq!(store.batches() - before, 4);
+}
+
+#[tokio::test]
+async fn enneagram_rounds_are_flushed_once_each() {
+    let (config, store) = config().await;
+
+    let simulation = EnneagramSimulation {
+        simulation_size: 4,
+        distribution: vec![0.5, 0.5],
+        depth: 2,
+        topic: "Is remote work here to stay?".to_string(),
+        category: "work".to_string(),
+        ..Default::default()
+    };
+
+    let mut rng = config.rng();
+    simulation
+        .run_simulation(config.clone(), Debate::default(), &mut rng)
+        .await
+        .unwrap();
+
+    // One flush for each of the 3 responses and the final one
+    assert_eq!(store.batches(), 4);
+}
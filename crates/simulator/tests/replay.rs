@@ -0,0 +1,141 @@
+use pulpcalc_common::{config::Config, models::Debate};
+use pulpcalc_external::cassette::CassetteMode;
+use simulator::{new_personas_from_file, personas::PersonasSimulation};
+use std::{env, fs, path::Path};
+
+const PERSONAS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../Personas.toml");
+
+/// What a run wrote, independent of the ids the store generated
+#[derive(Debug, PartialEq)]
+struct Output {
+    /// Content, score and prompt of every response, sorted
+    responses: Vec<(String, i64, String)>,
+
+    /// Entities and relationships written per label
+    counts: Vec<(String, i64)>,
+
+    misses: u64,
+}
+
+/// Runs the personas simulation of `Personas.toml` with `seed` against the mock provider, once `setup`
+/// has picked its cache or cassette
+async fn simulate(seed: u64, setup: impl FnOnce(&mut Config)) -> Output {
+    let mut config = Config::default().await;
+    config.seed = seed;
+    config.llm_provider = Some(String::from("mock"));
+    config.llm = config.new_llm_provider();
+    setup(&mut config);
+    config.use_store("memory").await.unwrap();
+
+    let run = config.start_run("personas", PERSONAS).await.unwrap();
+    assert_eq!(run.seed as u64, seed);
+
+    let mut personas_config = new_personas_from_file(PERSONAS.to_string());
+    personas_config.simulation_size = 20;
+    personas_config.max_commenters = Some(3);
+    personas_config.max_voters = Some(2);
+
+    let mut rng = config.rng();
+    PersonasSimulation::init_users(&config, &personas_config, 20, &mut rng)
+        .await
+        .unwrap();
+
+    let mut simulation = PersonasSimulation::default();
+    for (topic, category) in personas_config
+        .debate_topics
+        .clone()
+        .unwrap()
+        .into_iter()
+        .zip(personas_config.debate_categories.clone().unwrap())
+    {
+        simulation.debates.push(Debate {
+            topic,
+            category,
+            ..Default::default()
+        });
+    }
+
+    simulation
+        .run_simulation(config.clone(), personas_config, &mut rng)
+        .await
+        .unwrap();
+    config.store.flush().await.unwrap();
+
+    let mut responses = Vec::new();
+    let mut timed = false;
+    for id in config
+        .store
+        .run_entities(&run.id, "Response")
+        .await
+        .unwrap()
+    {
+        let response = config.store.get_response(&id).await.unwrap();
+        timed |= response.engagements.response_timing > 0;
+        responses.push((response.content, response.score, response.prompt_name));
+    }
+    responses.sort();
+    assert!(timed, "no reply earned timing points");
+
+    Output {
+        responses,
+        counts: config.store.run_counts(&run.id).await.unwrap(),
+        misses: config.cassette_misses(),
+    }
+}
+
+fn record(config: &mut Config, cassette: &Path) {
+    config
+        .use_cassette(CassetteMode::Record, cassette.to_str().unwrap())
+        .unwrap();
+}
+
+fn replay(config: &mut Config, cassette: &Path) {
+    config
+        .use_cassette(CassetteMode::Replay, cassette.to_str().unwrap())
+        .unwrap();
+}
+
+#[tokio::test]
+async fn replaying_a_recorded_run_writes_the_same_output() {
+    let cassette = env::temp_dir().join(format!("pulpcalc-replay-{}.jsonl", std::process::id()));
+
+    let recorded = simulate(7, |config| record(config, &cassette)).await;
+    let replayed = simulate(7, |config| replay(config, &cassette)).await;
+    fs::remove_file(&cassette).unwrap();
+
+    assert!(!recorded.responses.is_empty());
+    assert_eq!(replayed.misses, 0);
+    assert_eq!(recorded, replayed);
+}
+
+#[tokio::test]
+async fn running_a_seed_twice_stores_the_same_scores() {
+    let first = simulate(11, |_| {}).await;
+    let second = simulate(11, |_| {}).await;
+
+    assert!(!first.responses.is_empty());
+    assert_eq!(first, second);
+}
+
+#[tokio::test]
+async fn recording_with_a_warm_cache_records_every_reply() {
+    let cache = env::temp_dir().join(format!("pulpcalc-replay-cache-{}.db", std::process::id()));
+    let cassette = env::temp_dir().join(format!(
+        "pulpcalc-replay-cached-{}.jsonl",
+        std::process::id()
+    ));
+    let use_cache = |config: &mut Config| config.use_cache(cache.to_str().unwrap()).unwrap();
+
+    simulate(5, use_cache).await;
+    let recorded = simulate(5, |config| {
+        use_cache(config);
+        record(config, &cassette);
+    })
+    .await;
+    let replayed = simulate(5, |config| replay(config, &cassette)).await;
+    fs::remove_file(&cache).unwrap();
+    fs::remove_file(&cassette).unwrap();
+
+    assert_eq!(replayed.misses, 0);
+    assert_eq!(recorded, replayed);
+}